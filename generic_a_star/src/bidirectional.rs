@@ -0,0 +1,450 @@
+use std::{collections::HashMap, fmt::Display};
+
+use binary_heap_plus::{BinaryHeap, MinComparator};
+use deterministic_default_hasher::DeterministicDefaultHasher;
+use extend_map::ExtendFilter;
+use num_traits::Bounded;
+
+use crate::{
+    AStarContext, AStarNode, AStarPerformanceCounters, AStarResult, AStarState, interruption,
+    memory,
+};
+
+/// An [`AStarContext`] that can additionally be searched backwards, from the target nodes towards the root node.
+///
+/// Nodes generated by the reverse search use the same node type as the forward search, but with a reversed meaning:
+/// * [`AStarNode::cost`] is the cost from the node to the target.
+/// * [`AStarNode::a_star_lower_bound`] is a lower bound on the cost from the root to the node.
+/// * [`AStarNode::predecessor`] is the identifier of the node's successor in the forward graph.
+/// * [`AStarNode::predecessor_edge_type`] is the edge from the node to its successor in the forward graph.
+pub trait BidirectionalAStarContext: AStarContext {
+    /// Create the root nodes of the reverse search.
+    ///
+    /// These are all target nodes of the A* graph, with a cost of zero.
+    fn create_reverse_roots(&self, output: &mut impl Extend<Self::Node>);
+
+    /// Generate the predecessors of this node in the forward graph.
+    fn generate_reverse_successors(
+        &mut self,
+        node: &Self::Node,
+        output: &mut impl Extend<Self::Node>,
+    );
+
+    /// Returns true if this node is the root node of the forward search.
+    fn is_reverse_target(&self, node: &Self::Node) -> bool;
+}
+
+/// A bidirectional A* search.
+///
+/// One search runs forwards from the root, and the other one runs backwards from the targets.
+/// The search terminates once the cheapest path through a node closed by both searches is provably optimal.
+#[derive(Debug)]
+pub struct BidirectionalAStar<Context: BidirectionalAStarContext> {
+    state: AStarState<<Context::Node as AStarNode>::Identifier, <Context::Node as AStarNode>::Cost>,
+    context: Context,
+    forward_closed_list: HashMap<
+        <Context::Node as AStarNode>::Identifier,
+        Context::Node,
+        DeterministicDefaultHasher,
+    >,
+    forward_open_list: BinaryHeap<Context::Node, MinComparator>,
+    reverse_closed_list: HashMap<
+        <Context::Node as AStarNode>::Identifier,
+        Context::Node,
+        DeterministicDefaultHasher,
+    >,
+    reverse_open_list: BinaryHeap<Context::Node, MinComparator>,
+    /// The node where the forward and the reverse part of the best path found so far meet, and the cost of that path.
+    #[allow(clippy::type_complexity)]
+    meeting_node: Option<(
+        <Context::Node as AStarNode>::Identifier,
+        <Context::Node as AStarNode>::Cost,
+    )>,
+    performance_counters: AStarPerformanceCounters,
+    /// The sum of the heap sizes of all nodes in the open and closed lists.
+    node_heap_size: usize,
+}
+
+/// The error returned by [`BidirectionalAStar::initialise`] if the context is not [label setting](AStarContext::is_label_setting),
+/// since the termination criterion of the bidirectional search requires non-negative edge costs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotLabelSettingError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Forward,
+    Reverse,
+}
+
+struct BidirectionalBacktrackingIteratorWithCost<'a_star, Context: BidirectionalAStarContext> {
+    a_star: &'a_star BidirectionalAStar<Context>,
+    #[allow(clippy::type_complexity)]
+    reverse_part: std::vec::IntoIter<(
+        <Context::Node as AStarNode>::EdgeType,
+        <Context::Node as AStarNode>::Cost,
+    )>,
+    current: Option<<Context::Node as AStarNode>::Identifier>,
+}
+
+impl<Context: BidirectionalAStarContext> BidirectionalAStar<Context> {
+    pub fn new(context: Context) -> Self {
+        Self {
+            state: AStarState::Empty,
+            context,
+            forward_closed_list: Default::default(),
+            forward_open_list: BinaryHeap::new_min(),
+            reverse_closed_list: Default::default(),
+            reverse_open_list: BinaryHeap::new_min(),
+            meeting_node: None,
+            performance_counters: Default::default(),
+            node_heap_size: 0,
+        }
+    }
+
+    pub fn state(
+        &self,
+    ) -> &AStarState<<Context::Node as AStarNode>::Identifier, <Context::Node as AStarNode>::Cost>
+    {
+        &self.state
+    }
+
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    pub fn into_context(self) -> Context {
+        self.context
+    }
+
+    pub fn performance_counters(&self) -> &AStarPerformanceCounters {
+        &self.performance_counters
+    }
+
+    pub fn reset(&mut self) {
+        self.state = AStarState::Empty;
+        self.context.reset();
+        self.forward_closed_list.clear();
+        self.forward_open_list.clear();
+        self.reverse_closed_list.clear();
+        self.reverse_open_list.clear();
+        self.meeting_node = None;
+        self.performance_counters = Default::default();
+        self.node_heap_size = 0;
+    }
+
+    /// Returns an error if the context is not [label setting](AStarContext::is_label_setting).
+    pub fn initialise(&mut self) -> Result<(), NotLabelSettingError> {
+        assert_eq!(self.state, AStarState::Empty);
+        if !self.context.is_label_setting() {
+            return Err(NotLabelSettingError);
+        }
+
+        self.state = AStarState::Init;
+        self.forward_open_list.push(self.context.create_root());
+        self.context
+            .create_reverse_roots(&mut self.reverse_open_list);
+        self.node_heap_size = self
+            .forward_open_list
+            .iter()
+            .chain(self.reverse_open_list.iter())
+            .map(AStarNode::heap_size)
+            .sum();
+
+        Ok(())
+    }
+
+    pub fn search(
+        &mut self,
+    ) -> AStarResult<<Context::Node as AStarNode>::Identifier, <Context::Node as AStarNode>::Cost>
+    {
+        assert!(matches!(
+            self.state,
+            AStarState::Init | AStarState::Searching | AStarState::Terminated { .. }
+        ));
+
+        let cost_limit = self
+            .context
+            .cost_limit()
+            .unwrap_or(<Context::Node as AStarNode>::Cost::max_value());
+        let mut applied_cost_limit = false;
+        let memory_limit = self.context.memory_limit().unwrap_or(usize::MAX);
+
+        self.state = AStarState::Searching;
+        let mut iterations = 0;
+
+        loop {
+            let forward_lower_bound = self
+                .forward_open_list
+                .peek()
+                .map(|node| node.cost() + node.a_star_lower_bound());
+            let reverse_lower_bound = self
+                .reverse_open_list
+                .peek()
+                .map(|node| node.cost() + node.a_star_lower_bound());
+
+            // If one of the open lists is empty, then all paths through that side of the graph have been found.
+            // Otherwise, all paths that have not yet been found cost at least the larger of the two lower bounds.
+            let terminate = match (forward_lower_bound, reverse_lower_bound) {
+                (Some(forward_lower_bound), Some(reverse_lower_bound)) => {
+                    let lower_bound = forward_lower_bound.max(reverse_lower_bound);
+                    self.meeting_node
+                        .as_ref()
+                        .is_some_and(|(_, cost)| *cost <= lower_bound)
+                }
+                _ => true,
+            };
+
+            if terminate {
+                let result = if let Some((meeting_identifier, cost)) = self.meeting_node.clone() {
+                    AStarResult::FoundTarget {
+                        identifier: self.reverse_path_end(meeting_identifier),
+                        cost,
+                        cost_lower_bound: None,
+                    }
+                } else if applied_cost_limit {
+                    AStarResult::ExceededCostLimit { cost_limit }
+                } else {
+                    AStarResult::NoTarget
+                };
+
+                self.state = AStarState::Terminated {
+                    result: result.clone(),
+                };
+                return result;
+            }
+
+            let memory_usage = self.memory_usage();
+            self.performance_counters.peak_memory_usage = self
+                .performance_counters
+                .peak_memory_usage
+                .max(memory_usage);
+            if memory_usage > memory_limit {
+                let max_cost = forward_lower_bound
+                    .unwrap()
+                    .min(reverse_lower_bound.unwrap());
+                self.state = AStarState::Terminated {
+                    result: AStarResult::ExceededMemoryLimit { max_cost },
+                };
+                return AStarResult::ExceededMemoryLimit { max_cost };
+            }
+
+            if let Some(result) = interruption(
+                &self.context,
+                &mut iterations,
+                forward_lower_bound
+                    .unwrap()
+                    .min(reverse_lower_bound.unwrap()),
+            ) {
+                self.state = AStarState::Terminated {
+                    result: result.clone(),
+                };
+                return result;
+            }
+
+            // Expand the smaller frontier.
+            let direction = if self.forward_open_list.len() <= self.reverse_open_list.len() {
+                Direction::Forward
+            } else {
+                Direction::Reverse
+            };
+
+            let (open_list, closed_list, other_closed_list) = match direction {
+                Direction::Forward => (
+                    &mut self.forward_open_list,
+                    &mut self.forward_closed_list,
+                    &self.reverse_closed_list,
+                ),
+                Direction::Reverse => (
+                    &mut self.reverse_open_list,
+                    &mut self.reverse_closed_list,
+                    &self.forward_closed_list,
+                ),
+            };
+
+            let node = open_list.pop().unwrap();
+            self.node_heap_size -= node.heap_size();
+
+            if direction == Direction::Forward && node.predecessor().is_none() {
+                if let Some(next_root) = self.context.next_root(&node) {
+                    if next_root.cost() + next_root.a_star_lower_bound() <= cost_limit {
+                        self.node_heap_size += next_root.heap_size();
+                        open_list.push(next_root);
+                        self.performance_counters.opened_nodes += 1;
+                    } else {
+                        applied_cost_limit = true;
+                    }
+                }
+            }
+
+            if closed_list.contains_key(node.identifier()) {
+                self.performance_counters.suboptimal_opened_nodes += 1;
+                continue;
+            }
+
+            let open_nodes_without_new_successors = open_list.len();
+            let mut output = ExtendFilter::new(&mut *open_list, |node: &Context::Node| {
+                let result = node.cost() + node.a_star_lower_bound() <= cost_limit;
+                applied_cost_limit = applied_cost_limit || !result;
+                if result {
+                    self.node_heap_size += node.heap_size();
+                }
+                result
+            });
+            let is_endpoint = match direction {
+                Direction::Forward => {
+                    self.context.generate_successors(&node, &mut output);
+                    self.context.is_target(&node)
+                }
+                Direction::Reverse => {
+                    self.context.generate_reverse_successors(&node, &mut output);
+                    self.context.is_reverse_target(&node)
+                }
+            };
+            self.performance_counters.opened_nodes +=
+                open_list.len() - open_nodes_without_new_successors;
+
+            // Check if a new path was found through this node.
+            let path_cost = [
+                is_endpoint.then(|| node.cost()),
+                other_closed_list
+                    .get(node.identifier())
+                    .map(|other| node.cost() + other.cost()),
+            ]
+            .into_iter()
+            .flatten()
+            .min();
+            if let Some(path_cost) = path_cost {
+                if self
+                    .meeting_node
+                    .as_ref()
+                    .is_none_or(|(_, cost)| path_cost < *cost)
+                {
+                    self.meeting_node = Some((node.identifier().clone(), path_cost));
+                }
+            }
+
+            self.node_heap_size += node.heap_size();
+            closed_list.insert(node.identifier().clone(), node);
+            self.performance_counters.closed_nodes += 1;
+        }
+    }
+
+    /// Returns the approximate number of bytes currently used by the search.
+    ///
+    /// This includes the open and closed lists of both directions as well as the heap memory reported by the context.
+    pub fn memory_usage(&self) -> usize {
+        memory::vec_heap_size::<Context::Node>(
+            self.forward_open_list.capacity() + self.reverse_open_list.capacity(),
+        ) + memory::hash_map_heap_size::<<Context::Node as AStarNode>::Identifier, Context::Node>(
+            self.forward_closed_list.capacity(),
+        ) + memory::hash_map_heap_size::<<Context::Node as AStarNode>::Identifier, Context::Node>(
+            self.reverse_closed_list.capacity(),
+        ) + self.node_heap_size
+            + self.context.heap_size()
+    }
+
+    /// Backtrack from the target node to the root node.
+    pub fn backtrack(
+        &self,
+    ) -> impl use<'_, Context> + Iterator<Item = <Context::Node as AStarNode>::EdgeType> {
+        self.backtrack_with_costs().map(|(edge_type, _)| edge_type)
+    }
+
+    /// Backtrack from the target node to the root node.
+    ///
+    /// The elements of the iterator are a pair of an edge and the cost of the node that is reached by the edge.
+    /// The cost of the first node is never returned.
+    pub fn backtrack_with_costs(
+        &self,
+    ) -> impl use<'_, Context>
+    + Iterator<
+        Item = (
+            <Context::Node as AStarNode>::EdgeType,
+            <Context::Node as AStarNode>::Cost,
+        ),
+    > {
+        let AStarState::Terminated {
+            result: AStarResult::FoundTarget { cost, .. },
+        } = &self.state
+        else {
+            panic!("Cannot backtrack since no target was found.")
+        };
+        let (meeting_identifier, _) = self.meeting_node.as_ref().unwrap();
+
+        // The reverse part is stored in the closed list of the reverse search, pointing towards the target.
+        // Hence, we collect it to be able to return it from the target to the meeting node.
+        let mut reverse_part = Vec::new();
+        if let Some(mut node) = self.reverse_closed_list.get(meeting_identifier) {
+            while let Some(successor) = node.predecessor() {
+                let edge_type = node.predecessor_edge_type().unwrap();
+                node = self.reverse_closed_list.get(successor).unwrap();
+                reverse_part.push((edge_type, *cost - node.cost()));
+            }
+        }
+        reverse_part.reverse();
+
+        BidirectionalBacktrackingIteratorWithCost {
+            a_star: self,
+            reverse_part: reverse_part.into_iter(),
+            current: self
+                .forward_closed_list
+                .contains_key(meeting_identifier)
+                .then(|| meeting_identifier.clone()),
+        }
+    }
+
+    /// Returns the identifier of the target node reached by following the reverse part of the path through the given meeting node.
+    fn reverse_path_end(
+        &self,
+        meeting_identifier: <Context::Node as AStarNode>::Identifier,
+    ) -> <Context::Node as AStarNode>::Identifier {
+        let mut current = meeting_identifier;
+        while let Some(successor) = self
+            .reverse_closed_list
+            .get(&current)
+            .and_then(|node| node.predecessor())
+        {
+            current = successor.clone();
+        }
+        current
+    }
+}
+
+impl Display for NotLabelSettingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "bidirectional search requires non-negative costs")
+    }
+}
+
+impl std::error::Error for NotLabelSettingError {}
+
+impl<Context: BidirectionalAStarContext> Iterator
+    for BidirectionalBacktrackingIteratorWithCost<'_, Context>
+{
+    type Item = (
+        <Context::Node as AStarNode>::EdgeType,
+        <Context::Node as AStarNode>::Cost,
+    );
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(item) = self.reverse_part.next() {
+            return Some(item);
+        }
+
+        let current = self
+            .a_star
+            .forward_closed_list
+            .get(self.current.as_ref()?)
+            .unwrap();
+        let cost = current.cost();
+
+        if let Some(predecessor) = current.predecessor().cloned() {
+            let predecessor_edge_type = current.predecessor_edge_type().unwrap();
+            self.current = Some(predecessor);
+            Some((predecessor_edge_type, cost))
+        } else {
+            self.current = None;
+            None
+        }
+    }
+}
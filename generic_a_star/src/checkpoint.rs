@@ -0,0 +1,133 @@
+use serde::{
+    Deserialize, Serialize, Serializer,
+    de::DeserializeOwned,
+    ser::{SerializeSeq, SerializeStruct},
+};
+
+use crate::{
+    AStar, AStarContext, AStarNode, AStarPerformanceCounters, AStarState,
+    closed_list::ClosedListMode, observer::AStarObserver, open_list::AStarOpenList,
+};
+
+/// An [`AStarContext`] whose state can be stored in a checkpoint of a search.
+pub trait CheckpointContext: AStarContext {
+    /// The state of the context that is built up during the search.
+    ///
+    /// State that is recomputed when constructing the context does not need to be part of the checkpoint.
+    type Checkpoint: Serialize + DeserializeOwned;
+
+    /// Returns the state of the context to be stored in a checkpoint.
+    fn checkpoint(&self) -> &Self::Checkpoint;
+
+    /// Restores the state of the context from a checkpoint.
+    fn restore_checkpoint(&mut self, checkpoint: Self::Checkpoint);
+}
+
+/// A checkpoint of an in-progress [`AStar`] search, as written by [`AStar::write_checkpoint`].
+#[derive(Debug, Deserialize)]
+#[serde(bound(deserialize = "Node: DeserializeOwned, ContextCheckpoint: DeserializeOwned"))]
+pub struct AStarCheckpoint<Node, ContextCheckpoint> {
+    open_list: Vec<Node>,
+    closed_list: Vec<Node>,
+    performance_counters: AStarPerformanceCounters,
+    context: ContextCheckpoint,
+}
+
+/// Serializes the items of an iterator as a sequence of the given length without collecting them.
+struct SerializeIter<Iter> {
+    iter: Iter,
+    len: usize,
+}
+
+impl<
+    Context: CheckpointContext,
+    Observer: AStarObserver<Context::Node>,
+    OpenList: AStarOpenList<Context::Node>,
+> AStar<Context, Observer, OpenList>
+where
+    Context::Node: Serialize,
+{
+    /// Write a checkpoint of the search to `serializer`.
+    ///
+    /// The checkpoint contains the open and closed lists, the performance counters and the [checkpoint of the context](CheckpointContext::checkpoint).
+    /// It can be used to continue the search later via [`resume`](Self::resume).
+    ///
+    /// **Panics** if the search was not initialised, is weighted, has an [overridden cost limit](Self::set_cost_limit)
+    /// or has a [compact](crate::closed_list::ClosedListMode::Compact) closed list.
+    pub fn write_checkpoint<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        assert_ne!(self.state, AStarState::Empty);
+        assert!(
+            self.epsilon.is_none(),
+            "Weighted searches cannot be checkpointed"
+        );
+        assert!(
+            self.cost_limit.is_none(),
+            "Searches with an overridden cost limit cannot be checkpointed"
+        );
+        let closed_nodes = self
+            .closed_list
+            .full_nodes()
+            .expect("Searches with a compact closed list cannot be checkpointed");
+
+        let mut checkpoint = serializer.serialize_struct("AStarCheckpoint", 4)?;
+        checkpoint.serialize_field(
+            "open_list",
+            &SerializeIter {
+                iter: self.open_list.iter(),
+                len: self.open_list.len(),
+            },
+        )?;
+        checkpoint.serialize_field(
+            "closed_list",
+            &SerializeIter {
+                iter: closed_nodes,
+                len: self.closed_list.len(),
+            },
+        )?;
+        checkpoint.serialize_field("performance_counters", &self.performance_counters)?;
+        checkpoint.serialize_field("context", self.context.checkpoint())?;
+        checkpoint.end()
+    }
+}
+
+impl<
+    Context: CheckpointContext,
+    Observer: AStarObserver<Context::Node>,
+    OpenList: AStarOpenList<Context::Node>,
+> AStar<Context, Observer, OpenList>
+{
+    /// Continue a search from a checkpoint written by [`write_checkpoint`](Self::write_checkpoint).
+    ///
+    /// The context must have been constructed in the same way as the context of the checkpointed search.
+    /// Afterwards, the search can be continued by e.g. [`search`](Self::search).
+    ///
+    /// **Panics** if the search is not empty or has a [compact](ClosedListMode::Compact) closed list.
+    pub fn resume(&mut self, checkpoint: AStarCheckpoint<Context::Node, Context::Checkpoint>) {
+        assert_eq!(self.state, AStarState::Empty);
+        // The closed nodes are not ordered such that predecessors come first, so they cannot be stored compactly.
+        assert_eq!(
+            self.closed_list.mode(),
+            ClosedListMode::Full,
+            "Searches with a compact closed list cannot be resumed"
+        );
+
+        self.context.restore_checkpoint(checkpoint.context);
+        self.node_heap_size = checkpoint.open_list.iter().map(AStarNode::heap_size).sum();
+        self.open_list.extend(checkpoint.open_list);
+        for node in checkpoint.closed_list {
+            self.closed_list.insert(node);
+        }
+        self.performance_counters = checkpoint.performance_counters;
+        self.state = AStarState::Searching;
+    }
+}
+
+impl<Iter: Iterator<Item: Serialize> + Clone> Serialize for SerializeIter<Iter> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut sequence = serializer.serialize_seq(Some(self.len))?;
+        for item in self.iter.clone() {
+            sequence.serialize_element(&item)?;
+        }
+        sequence.end()
+    }
+}
@@ -0,0 +1,330 @@
+//! The closed list of an [`AStar`](crate::AStar) search.
+
+use std::collections::{HashMap, hash_map::Entry};
+
+use deterministic_default_hasher::DeterministicDefaultHasher;
+
+use crate::{AStarNode, memory};
+
+/// Determines how the closed list of an [`AStar`](crate::AStar) search stores its nodes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ClosedListMode {
+    /// Closed nodes are stored in full.
+    #[default]
+    Full,
+    /// Closed nodes are stored in an arena, keeping only their cost, the index of their predecessor and the edge type from their predecessor.
+    ///
+    /// This uses much less memory if nodes are large, but the full nodes cannot be retrieved anymore.
+    Compact,
+}
+
+/// A node in the closed list, as returned by [`AStar::closed_node`](crate::AStar::closed_node).
+#[derive(Debug)]
+pub enum ClosedNode<'closed_list, Node: AStarNode> {
+    /// A node stored in [full](ClosedListMode::Full) mode.
+    Full(&'closed_list Node),
+    /// A node stored in [compact](ClosedListMode::Compact) mode.
+    Compact {
+        cost: Node::Cost,
+        predecessor_edge_type: Option<&'closed_list Node::EdgeType>,
+    },
+}
+
+/// The closed list, storing nodes according to its [`ClosedListMode`].
+#[derive(Debug)]
+pub(crate) enum ClosedList<Node: AStarNode> {
+    Full {
+        nodes: HashMap<Node::Identifier, Node, DeterministicDefaultHasher>,
+        /// The sum of the heap sizes of all nodes.
+        node_heap_size: usize,
+    },
+    Compact {
+        indices: HashMap<Node::Identifier, u32, DeterministicDefaultHasher>,
+        arena: Vec<CompactNode<Node>>,
+    },
+}
+
+/// A node stored in [compact](ClosedListMode::Compact) mode.
+#[derive(Debug)]
+pub(crate) struct CompactNode<Node: AStarNode> {
+    cost: Node::Cost,
+    /// The index of the predecessor in the arena, or [`NO_PREDECESSOR`] for root nodes.
+    predecessor: u32,
+    predecessor_edge_type: Option<Node::EdgeType>,
+}
+
+const NO_PREDECESSOR: u32 = u32::MAX;
+
+/// Iterates over the edges from a closed node to a root node, together with the cost of the node that is reached by each edge.
+pub(crate) struct Backtrack<'closed_list, Node: AStarNode> {
+    closed_list: &'closed_list ClosedList<Node>,
+    current: BacktrackPosition<'closed_list, Node>,
+}
+
+enum BacktrackPosition<'closed_list, Node> {
+    Full(&'closed_list Node),
+    Compact(u32),
+}
+
+impl<'closed_list, Node: AStarNode> ClosedNode<'closed_list, Node> {
+    pub fn cost(&self) -> Node::Cost {
+        match self {
+            Self::Full(node) => node.cost(),
+            Self::Compact { cost, .. } => *cost,
+        }
+    }
+
+    pub fn predecessor_edge_type(&self) -> Option<Node::EdgeType> {
+        match self {
+            Self::Full(node) => node.predecessor_edge_type(),
+            Self::Compact {
+                predecessor_edge_type,
+                ..
+            } => predecessor_edge_type.cloned(),
+        }
+    }
+
+    /// Returns the full node, or `None` if it was stored in [compact](ClosedListMode::Compact) mode.
+    pub fn node(&self) -> Option<&'closed_list Node> {
+        match self {
+            Self::Full(node) => Some(node),
+            Self::Compact { .. } => None,
+        }
+    }
+}
+
+impl<Node: AStarNode> ClosedList<Node> {
+    pub fn new(mode: ClosedListMode) -> Self {
+        match mode {
+            ClosedListMode::Full => Self::Full {
+                nodes: Default::default(),
+                node_heap_size: 0,
+            },
+            ClosedListMode::Compact => Self::Compact {
+                indices: Default::default(),
+                arena: Default::default(),
+            },
+        }
+    }
+
+    pub fn mode(&self) -> ClosedListMode {
+        match self {
+            Self::Full { .. } => ClosedListMode::Full,
+            Self::Compact { .. } => ClosedListMode::Compact,
+        }
+    }
+
+    /// Removes all nodes and switches to the given mode.
+    ///
+    /// Allocations are reused if the mode does not change.
+    pub fn reset(&mut self, mode: ClosedListMode) {
+        if self.mode() != mode {
+            *self = Self::new(mode);
+        } else {
+            self.clear();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        match self {
+            Self::Full {
+                nodes,
+                node_heap_size,
+            } => {
+                nodes.clear();
+                *node_heap_size = 0;
+            }
+            Self::Compact { indices, arena } => {
+                indices.clear();
+                arena.clear();
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Full { nodes, .. } => nodes.len(),
+            Self::Compact { indices, .. } => indices.len(),
+        }
+    }
+
+    pub fn get(&self, identifier: &Node::Identifier) -> Option<ClosedNode<'_, Node>> {
+        match self {
+            Self::Full { nodes, .. } => nodes.get(identifier).map(ClosedNode::Full),
+            Self::Compact { indices, arena } => indices.get(identifier).map(|index| {
+                let node = &arena[*index as usize];
+                ClosedNode::Compact {
+                    cost: node.cost,
+                    predecessor_edge_type: node.predecessor_edge_type.as_ref(),
+                }
+            }),
+        }
+    }
+
+    /// Inserts a node, replacing a previous visit of it.
+    ///
+    /// Returns true if there was a previous visit.
+    pub fn insert(&mut self, node: Node) -> bool {
+        match self {
+            Self::Full {
+                nodes,
+                node_heap_size,
+            } => {
+                *node_heap_size += node.heap_size();
+                let previous_visit = nodes.insert(node.identifier().clone(), node);
+                *node_heap_size -= previous_visit.as_ref().map_or(0, AStarNode::heap_size);
+                previous_visit.is_some()
+            }
+            Self::Compact { indices, arena } => {
+                let compact_node = CompactNode {
+                    cost: node.cost(),
+                    predecessor: node.predecessor().map_or(NO_PREDECESSOR, |predecessor| {
+                        *indices
+                            .get(predecessor)
+                            .expect("The predecessor of a closed node must be closed")
+                    }),
+                    predecessor_edge_type: node.predecessor_edge_type(),
+                };
+
+                match indices.entry(node.identifier().clone()) {
+                    Entry::Occupied(entry) => {
+                        arena[*entry.get() as usize] = compact_node;
+                        true
+                    }
+                    Entry::Vacant(entry) => {
+                        let index = u32::try_from(arena.len())
+                            .ok()
+                            .filter(|index| *index != NO_PREDECESSOR)
+                            .expect("Too many closed nodes for a compact closed list");
+                        entry.insert(index);
+                        arena.push(compact_node);
+                        false
+                    }
+                }
+            }
+        }
+    }
+
+    /// Iterates over all nodes if they are stored in [full](ClosedListMode::Full) mode.
+    #[cfg(feature = "serde")]
+    pub fn full_nodes(&self) -> Option<impl Iterator<Item = &Node> + Clone> {
+        match self {
+            Self::Full { nodes, .. } => Some(nodes.values()),
+            Self::Compact { .. } => None,
+        }
+    }
+
+    /// Iterates over the identifiers of all closed nodes, together with the nodes.
+    pub fn iter(&self) -> impl Iterator<Item = (&Node::Identifier, ClosedNode<'_, Node>)> {
+        let (full, compact) = match self {
+            Self::Full { nodes, .. } => (Some(nodes.iter()), None),
+            Self::Compact { indices, arena } => (None, Some((indices.iter(), arena))),
+        };
+
+        full.into_iter()
+            .flatten()
+            .map(|(identifier, node)| (identifier, ClosedNode::Full(node)))
+            .chain(compact.into_iter().flat_map(|(indices, arena)| {
+                indices.map(|(identifier, index)| {
+                    let node = &arena[*index as usize];
+                    (
+                        identifier,
+                        ClosedNode::Compact {
+                            cost: node.cost,
+                            predecessor_edge_type: node.predecessor_edge_type.as_ref(),
+                        },
+                    )
+                })
+            }))
+    }
+
+    /// Backtrack from a node to a root node.
+    ///
+    /// Returns `None` if the node is not closed.
+    pub fn backtrack(&self, identifier: &Node::Identifier) -> Option<Backtrack<'_, Node>> {
+        let current = match self {
+            Self::Full { nodes, .. } => BacktrackPosition::Full(nodes.get(identifier)?),
+            Self::Compact { indices, .. } => BacktrackPosition::Compact(*indices.get(identifier)?),
+        };
+
+        Some(Backtrack {
+            closed_list: self,
+            current,
+        })
+    }
+
+    /// Backtrack from a node to the root node that its path starts from, and return the root node.
+    ///
+    /// Returns `None` if the node is not closed.
+    pub fn root(&self, identifier: &Node::Identifier) -> Option<ClosedNode<'_, Node>> {
+        match self {
+            Self::Full { nodes, .. } => {
+                let mut node = nodes.get(identifier)?;
+                while let Some(predecessor) = node.predecessor() {
+                    node = nodes.get(predecessor).unwrap();
+                }
+                Some(ClosedNode::Full(node))
+            }
+            Self::Compact { indices, arena } => {
+                let mut node = &arena[*indices.get(identifier)? as usize];
+                while node.predecessor != NO_PREDECESSOR {
+                    node = &arena[node.predecessor as usize];
+                }
+                Some(ClosedNode::Compact {
+                    cost: node.cost,
+                    predecessor_edge_type: node.predecessor_edge_type.as_ref(),
+                })
+            }
+        }
+    }
+
+    /// Returns the number of bytes allocated by the closed list, including heap memory owned by the nodes.
+    pub fn heap_size(&self) -> usize {
+        match self {
+            Self::Full {
+                nodes,
+                node_heap_size,
+            } => {
+                memory::hash_map_heap_size::<Node::Identifier, Node>(nodes.capacity())
+                    + node_heap_size
+            }
+            Self::Compact { indices, arena } => {
+                memory::hash_map_heap_size::<Node::Identifier, u32>(indices.capacity())
+                    + memory::vec_heap_size::<CompactNode<Node>>(arena.capacity())
+            }
+        }
+    }
+}
+
+impl<Node: AStarNode> Iterator for Backtrack<'_, Node> {
+    type Item = (Node::EdgeType, Node::Cost);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.closed_list, &self.current) {
+            (ClosedList::Full { nodes, .. }, BacktrackPosition::Full(node)) => {
+                let predecessor = node.predecessor()?;
+                let item = (node.predecessor_edge_type().unwrap(), node.cost());
+                self.current = BacktrackPosition::Full(nodes.get(predecessor).unwrap());
+                Some(item)
+            }
+            (ClosedList::Compact { arena, .. }, BacktrackPosition::Compact(index)) => {
+                let node = &arena[*index as usize];
+                if node.predecessor == NO_PREDECESSOR {
+                    return None;
+                }
+
+                let item = (node.predecessor_edge_type.clone().unwrap(), node.cost);
+                self.current = BacktrackPosition::Compact(node.predecessor);
+                Some(item)
+            }
+            _ => unreachable!("The backtracking position matches the mode of the closed list"),
+        }
+    }
+}
+
+impl<Node: AStarNode> Default for ClosedList<Node> {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
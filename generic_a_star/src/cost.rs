@@ -1,12 +1,19 @@
 use std::{
     fmt::{Debug, Display},
     hash::Hash,
+    num::ParseFloatError,
     ops::{Add, AddAssign, Sub, SubAssign},
     str::FromStr,
 };
 
+use noisy_float::types::R64;
 use num_traits::{Bounded, CheckedAdd, CheckedSub, SaturatingSub, Zero};
 
+use crate::{
+    AStarNode,
+    open_list::{AStarOpenList, BinaryHeapOpenList},
+};
+
 /// The cost of an A* node.
 pub trait AStarCost:
     From<Self::CostType>
@@ -27,9 +34,15 @@ pub trait AStarCost:
     + Eq
     + Hash
     + Copy
+    + Send
+    + Sync
+    + MaybeSerde
 {
     type CostType;
 
+    /// The open list that is used by default for nodes with this cost type.
+    type OpenList<Node: AStarNode<Cost = Self>>: AStarOpenList<Node>;
+
     fn as_f64(&self) -> f64;
 
     fn as_u64(&self) -> u64;
@@ -37,6 +50,22 @@ pub trait AStarCost:
     fn as_primitive(&self) -> Self::CostType;
 }
 
+/// Requires [`serde::Serialize`] and [`serde::de::DeserializeOwned`] if the `serde` feature is enabled, and nothing otherwise.
+///
+/// This allows to store costs in [checkpoints](crate::checkpoint) without requiring serde when it is not used.
+#[cfg(feature = "serde")]
+pub trait MaybeSerde: serde::Serialize + serde::de::DeserializeOwned {}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> MaybeSerde for T {}
+
+/// Requires [`serde::Serialize`] and [`serde::de::DeserializeOwned`] if the `serde` feature is enabled, and nothing otherwise.
+#[cfg(not(feature = "serde"))]
+pub trait MaybeSerde {}
+
+#[cfg(not(feature = "serde"))]
+impl<T> MaybeSerde for T {}
+
 macro_rules! primitive_cost {
     ($name:ident, $primitive:ident) => {
         #[doc = concat!("The cost of an A* node.\n\nThis cost type uses [`", stringify!($primitive), "`] as the internal representation of cost.")]
@@ -47,6 +76,9 @@ macro_rules! primitive_cost {
         impl $crate::cost::AStarCost for $name {
             type CostType = $primitive;
 
+            type OpenList<Node: $crate::AStarNode<Cost = Self>> =
+                $crate::open_list::BucketQueueOpenList<Node>;
+
             fn as_f64(&self) -> f64 {
                 self.0 as f64
             }
@@ -160,3 +192,152 @@ primitive_cost!(U32Cost, u32);
 primitive_cost!(I32Cost, i32);
 primitive_cost!(U64Cost, u64);
 primitive_cost!(I64Cost, i64);
+
+/// The cost of an A* node.
+///
+/// This cost type uses a finite non-negative [`f64`] as the internal representation of cost, and is suited for costs such as negative log probabilities.
+/// Like with the unsigned integer cost types, arithmetic panics if a result is out of range, and saturating subtraction saturates at zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct F64Cost(R64);
+
+/// The error returned when parsing an [`F64Cost`] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseF64CostError {
+    Float(ParseFloatError),
+    /// The parsed value is negative, infinite or NaN.
+    OutOfRange,
+}
+
+impl F64Cost {
+    /// Returns `None` if `value` is negative, infinite or NaN.
+    pub fn try_new(value: f64) -> Option<Self> {
+        if value >= 0.0 {
+            R64::try_new(value).map(Self)
+        } else {
+            None
+        }
+    }
+}
+
+impl AStarCost for F64Cost {
+    type CostType = f64;
+
+    type OpenList<Node: AStarNode<Cost = Self>> = BinaryHeapOpenList<Node>;
+
+    fn as_f64(&self) -> f64 {
+        self.0.raw()
+    }
+
+    fn as_u64(&self) -> u64 {
+        self.0.raw() as u64
+    }
+
+    fn as_primitive(&self) -> Self::CostType {
+        self.0.raw()
+    }
+}
+
+impl From<f64> for F64Cost {
+    fn from(value: f64) -> Self {
+        Self::try_new(value).unwrap_or_else(|| panic!("Cost {value} is negative or not finite"))
+    }
+}
+
+impl From<u8> for F64Cost {
+    fn from(value: u8) -> Self {
+        Self(R64::new(value.into()))
+    }
+}
+
+impl Add for F64Cost {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(&rhs).unwrap()
+    }
+}
+
+impl Sub for F64Cost {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(&rhs).unwrap()
+    }
+}
+
+impl SaturatingSub for F64Cost {
+    fn saturating_sub(&self, rhs: &Self) -> Self {
+        self.checked_sub(rhs).unwrap_or_else(Self::zero)
+    }
+}
+
+impl CheckedAdd for F64Cost {
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Self::try_new(self.0.raw() + rhs.0.raw())
+    }
+}
+
+impl CheckedSub for F64Cost {
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        Self::try_new(self.0.raw() - rhs.0.raw())
+    }
+}
+
+impl AddAssign for F64Cost {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for F64Cost {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Display for F64Cost {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl FromStr for F64Cost {
+    type Err = ParseF64CostError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = f64::from_str(s).map_err(ParseF64CostError::Float)?;
+        Self::try_new(value).ok_or(ParseF64CostError::OutOfRange)
+    }
+}
+
+impl Zero for F64Cost {
+    fn zero() -> Self {
+        Self(R64::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+}
+
+impl Bounded for F64Cost {
+    fn min_value() -> Self {
+        Self::zero()
+    }
+
+    fn max_value() -> Self {
+        Self(R64::max_value())
+    }
+}
+
+impl Display for ParseF64CostError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Float(error) => write!(f, "{error}"),
+            Self::OutOfRange => write!(f, "cost is negative or not finite"),
+        }
+    }
+}
+
+impl std::error::Error for ParseF64CostError {}
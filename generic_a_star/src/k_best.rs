@@ -0,0 +1,352 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    hash::{BuildHasher, Hash},
+};
+
+use binary_heap_plus::{BinaryHeap, MinComparator};
+use deterministic_default_hasher::DeterministicDefaultHasher;
+use num_traits::Bounded;
+
+use crate::{
+    AStar, AStarContext, AStarNode, AStarResult, AStarState, interruption, memory,
+    observer::AStarObserver, open_list::AStarOpenList,
+};
+
+/// The cheapest paths found by [`AStar::search_k_best`], ordered by ascending cost.
+#[derive(Debug)]
+pub struct KBestPaths<Node: AStarNode> {
+    /// All expanded nodes.
+    labels: Vec<Label<Node>>,
+    /// The indices of the labels of the targets of the paths.
+    targets: Vec<usize>,
+    result: AStarResult<Node::Identifier, Node::Cost>,
+}
+
+/// An expanded node, together with the label it was generated from.
+#[derive(Debug)]
+struct Label<Node> {
+    node: Node,
+    predecessor: Option<usize>,
+}
+
+/// A node in the open list of a k-best search, together with the label it was generated from.
+struct OpenEntry<Node> {
+    node: Node,
+    predecessor: Option<usize>,
+    /// A hash identifying the path from the root to this node.
+    path_key: u64,
+}
+
+impl<
+    Context: AStarContext,
+    Observer: AStarObserver<Context::Node>,
+    OpenList: AStarOpenList<Context::Node>,
+> AStar<Context, Observer, OpenList>
+{
+    /// Search for the `k` cheapest distinct paths from the root to a target node.
+    ///
+    /// If `max_cost_delta` is given, then only paths whose cost is at most the optimal cost plus `max_cost_delta` are returned.
+    /// To get all paths within the cost delta, set `k` to `usize::MAX`.
+    ///
+    /// Each node is expanded at most `k` times, once for each of its cheapest paths from the root.
+    /// For the paths to be ordered correctly, the A* lower bound must be consistent.
+    /// If fewer than `k` paths exist or a limit is reached, then the paths found so far are returned.
+    ///
+    /// The closed list is not used by this search, so the paths need to be backtracked via the returned [`KBestPaths`].
+    pub fn search_k_best(
+        &mut self,
+        k: usize,
+        max_cost_delta: Option<<Context::Node as AStarNode>::Cost>,
+    ) -> KBestPaths<Context::Node> {
+        let mut path_count = 0;
+        self.search_k_best_internal(k, max_cost_delta, |_, _| {
+            path_count += 1;
+            path_count
+        })
+    }
+
+    /// Like [`search_k_best`](Self::search_k_best), but paths are only distinct if their edges map to different keys.
+    ///
+    /// Edges that are mapped to `None` are ignored.
+    /// Of multiple paths to the same node with equal keys, only the cheapest is considered.
+    /// This allows to e.g. skip paths that differ only in internal states of the graph.
+    pub fn search_k_best_distinct<Key: Hash>(
+        &mut self,
+        k: usize,
+        max_cost_delta: Option<<Context::Node as AStarNode>::Cost>,
+        mut edge_key: impl FnMut(<Context::Node as AStarNode>::EdgeType) -> Option<Key>,
+    ) -> KBestPaths<Context::Node> {
+        let hasher = DeterministicDefaultHasher;
+        let root_key = hasher.hash_one(());
+
+        self.search_k_best_internal(k, max_cost_delta, |predecessor_key, node| {
+            let Some(predecessor_key) = predecessor_key else {
+                return root_key;
+            };
+
+            if let Some(key) = node.predecessor_edge_type().and_then(&mut edge_key) {
+                hasher.hash_one((predecessor_key, key))
+            } else {
+                predecessor_key
+            }
+        })
+    }
+
+    fn search_k_best_internal(
+        &mut self,
+        k: usize,
+        max_cost_delta: Option<<Context::Node as AStarNode>::Cost>,
+        mut path_key: impl FnMut(Option<u64>, &Context::Node) -> u64,
+    ) -> KBestPaths<Context::Node> {
+        assert_eq!(self.state, AStarState::Init);
+        assert!(k > 0, "Searching for zero paths makes no sense");
+
+        let cost_limit = self
+            .context
+            .cost_limit()
+            .unwrap_or(<Context::Node as AStarNode>::Cost::max_value());
+        let mut applied_cost_limit = false;
+        let memory_limit = self.context.memory_limit().unwrap_or(usize::MAX);
+
+        self.state = AStarState::Searching;
+
+        let mut open_list = BinaryHeap::from_vec_cmp(
+            self.open_list
+                .drain()
+                .map(|node| OpenEntry {
+                    path_key: path_key(None, &node),
+                    node,
+                    predecessor: None,
+                })
+                .collect(),
+            MinComparator,
+        );
+        let mut labels: Vec<Label<Context::Node>> = Vec::new();
+        let mut targets: Vec<usize> = Vec::new();
+        // The keys of the paths via which each node was expanded.
+        let mut expanded_path_keys: HashMap<_, Vec<u64>, DeterministicDefaultHasher> =
+            Default::default();
+        let mut target_path_keys: HashSet<u64, DeterministicDefaultHasher> = Default::default();
+        let mut successors = Vec::new();
+        let mut iterations = 0;
+        // The sum of the heap sizes of the nodes in the open list and the labels, as well as of the path key lists.
+        let mut heap_size: usize = open_list.iter().map(|entry| entry.node.heap_size()).sum();
+
+        // The result is only relevant if no target is found.
+        let result = loop {
+            let Some(OpenEntry {
+                node,
+                predecessor,
+                path_key: node_path_key,
+            }) = open_list.pop()
+            else {
+                if applied_cost_limit {
+                    self.observer
+                        .on_limit_reached(&AStarResult::ExceededCostLimit { cost_limit });
+                    break AStarResult::ExceededCostLimit { cost_limit };
+                } else {
+                    break AStarResult::NoTarget;
+                }
+            };
+            heap_size -= node.heap_size();
+
+            if let (Some(&optimal_target), Some(max_cost_delta)) = (targets.first(), max_cost_delta)
+            {
+                let optimal_cost = labels[optimal_target].node.cost();
+                // Nodes are ordered by cost plus lower bound.
+                if node.cost() + node.a_star_lower_bound() > optimal_cost + max_cost_delta {
+                    break AStarResult::NoTarget;
+                }
+            }
+
+            let memory_usage = memory::vec_heap_size::<Label<Context::Node>>(labels.capacity())
+                + memory::vec_heap_size::<OpenEntry<Context::Node>>(open_list.capacity())
+                + memory::hash_map_heap_size::<<Context::Node as AStarNode>::Identifier, Vec<u64>>(
+                    expanded_path_keys.capacity(),
+                )
+                + memory::hash_map_heap_size::<u64, ()>(target_path_keys.capacity())
+                + heap_size
+                + self.context.heap_size();
+            self.performance_counters.peak_memory_usage = self
+                .performance_counters
+                .peak_memory_usage
+                .max(memory_usage);
+            if memory_usage > memory_limit {
+                let result = AStarResult::ExceededMemoryLimit {
+                    max_cost: node.cost(),
+                };
+                self.observer.on_limit_reached(&result);
+                break result;
+            }
+
+            if let Some(result) = interruption(&self.context, &mut iterations, node.cost()) {
+                self.observer.on_limit_reached(&result);
+                break result;
+            }
+
+            if predecessor.is_none() {
+                if let Some(next_root) = self.context.next_root(&node) {
+                    if next_root.cost() + next_root.a_star_lower_bound() <= cost_limit {
+                        heap_size += next_root.heap_size();
+                        self.observer.on_node_opened(&next_root);
+                        open_list.push(OpenEntry {
+                            path_key: path_key(None, &next_root),
+                            node: next_root,
+                            predecessor: None,
+                        });
+                        self.performance_counters.opened_nodes += 1;
+                    } else {
+                        applied_cost_limit = true;
+                    }
+                }
+            }
+
+            let path_keys = expanded_path_keys
+                .entry(node.identifier().clone())
+                .or_default();
+            if path_keys.len() >= k || path_keys.contains(&node_path_key) {
+                // The node was already reached via its k cheapest paths, or via a cheaper equivalent path.
+                self.performance_counters.suboptimal_opened_nodes += 1;
+                self.observer.on_suboptimal_node(&node);
+                continue;
+            }
+            if self.observer.on_node_closed(&node).is_break() {
+                break AStarResult::Stopped {
+                    max_cost: node.cost(),
+                };
+            }
+            heap_size -= memory::vec_heap_size::<u64>(path_keys.capacity());
+            path_keys.push(node_path_key);
+            heap_size += memory::vec_heap_size::<u64>(path_keys.capacity());
+            self.performance_counters.closed_nodes += 1;
+
+            if self.context.is_target(&node) {
+                if target_path_keys.insert(node_path_key) {
+                    self.observer.on_target_found(&node);
+                    heap_size += node.heap_size();
+                    targets.push(labels.len());
+                    labels.push(Label { node, predecessor });
+
+                    if targets.len() == k {
+                        break AStarResult::NoTarget;
+                    }
+                }
+
+                continue;
+            }
+
+            self.context.generate_successors(&node, &mut successors);
+            let label = labels.len();
+            heap_size += node.heap_size();
+            labels.push(Label { node, predecessor });
+
+            for successor in successors.drain(..) {
+                if successor.cost() + successor.a_star_lower_bound() <= cost_limit {
+                    heap_size += successor.heap_size();
+                    self.observer.on_node_opened(&successor);
+                    open_list.push(OpenEntry {
+                        path_key: path_key(Some(node_path_key), &successor),
+                        node: successor,
+                        predecessor: Some(label),
+                    });
+                    self.performance_counters.opened_nodes += 1;
+                } else {
+                    applied_cost_limit = true;
+                }
+            }
+        };
+
+        // If a target was found, then the result refers to the optimal one.
+        let result = if let Some(&optimal_target) = targets.first() {
+            let target = &labels[optimal_target].node;
+            AStarResult::FoundTarget {
+                identifier: target.identifier().clone(),
+                cost: target.cost(),
+                cost_lower_bound: None,
+            }
+        } else {
+            result
+        };
+
+        self.state = AStarState::Terminated {
+            result: result.clone(),
+        };
+        KBestPaths {
+            labels,
+            targets,
+            result,
+        }
+    }
+}
+
+impl<Node: AStarNode> KBestPaths<Node> {
+    /// Returns the number of paths found.
+    pub fn len(&self) -> usize {
+        self.targets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty()
+    }
+
+    /// Returns the result of the search.
+    ///
+    /// If at least one path was found, then this is the result of the optimal path.
+    pub fn result(&self) -> &AStarResult<Node::Identifier, Node::Cost> {
+        &self.result
+    }
+
+    /// Returns the result of the path with the given index.
+    pub fn path_result(&self, index: usize) -> AStarResult<Node::Identifier, Node::Cost> {
+        let target = &self.labels[self.targets[index]].node;
+        AStarResult::FoundTarget {
+            identifier: target.identifier().clone(),
+            cost: target.cost(),
+            cost_lower_bound: None,
+        }
+    }
+
+    /// Backtrack the path with the given index from its target node to the root node.
+    pub fn backtrack(&self, index: usize) -> impl use<'_, Node> + Iterator<Item = Node::EdgeType> {
+        self.backtrack_with_costs(index)
+            .map(|(edge_type, _)| edge_type)
+    }
+
+    /// Backtrack the path with the given index from its target node to the root node.
+    ///
+    /// The elements of the iterator are a pair of an edge and the cost of the node that is reached by the edge.
+    /// The cost of the first node is never returned.
+    pub fn backtrack_with_costs(
+        &self,
+        index: usize,
+    ) -> impl use<'_, Node> + Iterator<Item = (Node::EdgeType, Node::Cost)> {
+        let mut current = Some(self.targets[index]);
+
+        std::iter::from_fn(move || {
+            let Label { node, predecessor } = &self.labels[current?];
+            current = *predecessor;
+            current.map(|_| (node.predecessor_edge_type().unwrap(), node.cost()))
+        })
+    }
+}
+
+impl<Node: Ord> Ord for OpenEntry<Node> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.node.cmp(&other.node)
+    }
+}
+
+impl<Node: Ord> PartialOrd for OpenEntry<Node> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<Node: Ord> PartialEq for OpenEntry<Node> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<Node: Ord> Eq for OpenEntry<Node> {}
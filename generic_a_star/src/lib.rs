@@ -1,20 +1,36 @@
 #![forbid(clippy::mod_module_files)]
 
 use std::{
-    collections::HashMap,
+    cmp::Ordering,
     fmt::{Debug, Display},
     hash::Hash,
+    time::Instant,
 };
 
-use binary_heap_plus::{BinaryHeap, MinComparator};
+use cancellation::CancellationToken;
+use closed_list::{ClosedList, ClosedListMode, ClosedNode};
+use compare::Compare;
 use cost::AStarCost;
-use deterministic_default_hasher::DeterministicDefaultHasher;
-use extend_map::ExtendFilter;
 use num_traits::Bounded;
+use observer::AStarObserver;
+use open_list::{AStarOpenList, DefaultOpenList};
 use reset::Reset;
 
+pub mod bidirectional;
+pub mod cancellation;
+#[cfg(feature = "serde")]
+pub mod checkpoint;
+pub mod closed_list;
 pub mod cost;
+pub mod k_best;
+pub mod memory;
+pub mod observer;
+pub mod open_list;
+pub mod parallel;
 pub mod reset;
+#[cfg(test)]
+mod tests;
+pub mod verification;
 
 /// A node of the A* graph.
 /// The node must implement [`Ord`], ordering it by its cost plus A* cost, ascending.
@@ -28,7 +44,7 @@ pub trait AStarNode: Sized + Ord + Debug + Display {
     /// The type collecting possible edge types.
     ///
     /// These are used when backtracking a solution.
-    type EdgeType: Debug;
+    type EdgeType: Debug + Clone;
 
     type Cost: AStarCost;
 
@@ -48,6 +64,14 @@ pub trait AStarNode: Sized + Ord + Debug + Display {
 
     /// Returns the edge type used to reach this node from the predecessor, or `None` if this is a root node.
     fn predecessor_edge_type(&self) -> Option<Self::EdgeType>;
+
+    /// Returns the number of bytes this node owns on the heap, in addition to its own size.
+    ///
+    /// This is used to account the memory usage of the open and closed lists.
+    /// The default implementation returns zero, which is correct for nodes without heap allocations.
+    fn heap_size(&self) -> usize {
+        0
+    }
 }
 
 pub trait AStarContext: Reset {
@@ -55,8 +79,22 @@ pub trait AStarContext: Reset {
     type Node: AStarNode;
 
     /// Create the root node of the A* graph.
+    ///
+    /// If the graph has multiple roots, then this is the first one, and the others are created by [`next_root`](Self::next_root).
     fn create_root(&self) -> Self::Node;
 
+    /// Create the root node following `root`.
+    ///
+    /// This allows to declare many roots, e.g. every cell of the first row and column of an alignment matrix, without creating them all up front.
+    /// The following root is created only once `root` is expanded, so the roots must be created in ascending order of their cost plus A* lower bound.
+    /// Otherwise, the search may not be optimal.
+    ///
+    /// This method returns `None` in its default implementation, i.e. the A* graph has a single root.
+    fn next_root(&self, root: &Self::Node) -> Option<Self::Node> {
+        let _ = root;
+        None
+    }
+
     /// Generate the successors of this node.
     fn generate_successors(&mut self, node: &Self::Node, output: &mut impl Extend<Self::Node>);
 
@@ -73,6 +111,25 @@ pub trait AStarContext: Reset {
     /// If it is exceeded, then [`AStarResult::ExceededMemoryLimit`] is returned
     fn memory_limit(&self) -> Option<usize>;
 
+    /// A point in time at which the search is aborted.
+    ///
+    /// If it is reached, then [`AStarResult::ExceededTimeLimit`] is returned.
+    /// The deadline is checked only every few closed nodes, so the search may slightly overrun it.
+    ///
+    /// This method returns `None` in its default implementation.
+    fn deadline(&self) -> Option<Instant> {
+        None
+    }
+
+    /// A token that allows to cancel the search from another thread.
+    ///
+    /// If it is cancelled, then [`AStarResult::Cancelled`] is returned.
+    ///
+    /// This method returns `None` in its default implementation.
+    fn cancellation_token(&self) -> Option<&CancellationToken> {
+        None
+    }
+
     /// Returns true if the nodes are generated in a label-setting manner.
     ///
     /// Label setting means that once a node has been closed, it will never be opened at a smaller cost.
@@ -83,14 +140,33 @@ pub trait AStarContext: Reset {
     fn is_label_setting(&self) -> bool {
         true
     }
+
+    /// Returns the number of bytes the context uses on the heap.
+    ///
+    /// This is included in the memory usage that is compared against the [memory limit](Self::memory_limit).
+    /// It is queried once for each closed node, so it should be cheap to compute.
+    ///
+    /// This method returns zero in its default implementation.
+    fn heap_size(&self) -> usize {
+        0
+    }
 }
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AStarPerformanceCounters {
     pub opened_nodes: usize,
     /// Opened nodes that do not have optimal costs.
     pub suboptimal_opened_nodes: usize,
     pub closed_nodes: usize,
+    /// Closed nodes that were opened again with a lower cost.
+    ///
+    /// This only happens in weighted searches and if the context is not [label setting](AStarContext::is_label_setting).
+    pub reopened_nodes: usize,
+    /// The maximum memory usage in bytes reached during the search.
+    ///
+    /// This includes the open and closed lists as well as the heap memory reported by the context.
+    pub peak_memory_usage: usize,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -107,23 +183,51 @@ pub enum AStarState<NodeIdentifier, Cost> {
     },
 }
 
+/// The comparator used for the open list.
+///
+/// Without a weight, nodes are ordered by their [`Ord`] implementation.
+/// With a weight `epsilon`, they are ordered by their cost plus `epsilon` times their A* lower bound,
+/// and ties are broken by their [`Ord`] implementation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AStarNodeComparator {
+    epsilon: f64,
+}
+
+/// An A* search.
+///
+/// The search reports its events to the `Observer`, which by default ignores them.
 #[derive(Debug)]
-pub struct AStar<Context: AStarContext> {
+pub struct AStar<
+    Context: AStarContext,
+    Observer = (),
+    OpenList = DefaultOpenList<<Context as AStarContext>::Node>,
+> {
     state: AStarState<<Context::Node as AStarNode>::Identifier, <Context::Node as AStarNode>::Cost>,
     context: Context,
-    closed_list: HashMap<
-        <Context::Node as AStarNode>::Identifier,
-        Context::Node,
-        DeterministicDefaultHasher,
-    >,
-    open_list: BinaryHeap<Context::Node, MinComparator>,
+    closed_list: ClosedList<Context::Node>,
+    open_list: OpenList,
     performance_counters: AStarPerformanceCounters,
+    /// The weight of the lower bounds, if the search is weighted.
+    ///
+    /// In a weighted search, nodes may be closed with a suboptimal cost.
+    epsilon: Option<f64>,
+    /// The cost limit set via [`set_cost_limit`](Self::set_cost_limit), which overrides the cost limit of the context.
+    cost_limit: Option<<Context::Node as AStarNode>::Cost>,
+    /// The nodes that exceeded the cost limit set via [`set_cost_limit`](Self::set_cost_limit).
+    ///
+    /// They are kept such that the search can be continued after raising the cost limit.
+    pruned_nodes: Vec<Context::Node>,
+    /// The sum of the heap sizes of all nodes in the open list and in the pruned nodes.
+    ///
+    /// The heap sizes of closed nodes are accounted by the closed list.
+    node_heap_size: usize,
+    observer: Observer,
 }
 
 #[derive(Debug)]
-pub struct AStarBuffers<NodeIdentifier, Node> {
-    closed_list: HashMap<NodeIdentifier, Node, DeterministicDefaultHasher>,
-    open_list: BinaryHeap<Node, MinComparator>,
+pub struct AStarBuffers<Node: AStarNode, OpenList = DefaultOpenList<Node>> {
+    closed_list: ClosedList<Node>,
+    open_list: OpenList,
 }
 
 #[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq, Hash)]
@@ -135,6 +239,14 @@ pub enum AStarResult<NodeIdentifier, Cost> {
         #[cfg_attr(feature = "serde", serde(skip))]
         identifier: NodeIdentifier,
         cost: Cost,
+        /// A proven lower bound on the cost of an optimal target.
+        ///
+        /// This is `None` if the search was exact, i.e. if `cost` is optimal.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default = "Option::default", skip_serializing_if = "Option::is_none")
+        )]
+        cost_lower_bound: Option<Cost>,
     },
 
     /// The algorithm terminated before finding a target because the cost limit was reached.
@@ -146,43 +258,78 @@ pub enum AStarResult<NodeIdentifier, Cost> {
         max_cost: Cost,
     },
 
+    /// The algorithm terminated before finding a target because the deadline was reached.
+    ExceededTimeLimit {
+        /// The maximum cost reached before reaching the deadline.
+        max_cost: Cost,
+    },
+
+    /// The algorithm terminated before finding a target because it was cancelled.
+    Cancelled {
+        /// The maximum cost reached before the search was cancelled.
+        max_cost: Cost,
+    },
+
+    /// The algorithm terminated before finding a target because its observer stopped it.
+    Stopped {
+        /// The maximum cost reached before the search was stopped.
+        max_cost: Cost,
+    },
+
     /// The algorithm terminated, but did not find a target.
     NoTarget,
 }
 
-struct BacktrackingIterator<'a_star, Context: AStarContext> {
-    a_star: &'a_star AStar<Context>,
-    current: <Context::Node as AStarNode>::Identifier,
-}
+impl<Context: AStarContext> AStar<Context> {
+    pub fn new(context: Context) -> Self {
+        Self::new_with_observer(context, ())
+    }
 
-struct BacktrackingIteratorWithCost<'a_star, Context: AStarContext> {
-    a_star: &'a_star AStar<Context>,
-    current: <Context::Node as AStarNode>::Identifier,
+    pub fn new_with_buffers(context: Context, buffers: AStarBuffers<Context::Node>) -> Self {
+        Self::new_with_buffers_and_observer(context, buffers, ())
+    }
 }
 
-impl<Context: AStarContext> AStar<Context> {
-    pub fn new(context: Context) -> Self {
+impl<
+    Context: AStarContext,
+    Observer: AStarObserver<Context::Node>,
+    OpenList: AStarOpenList<Context::Node>,
+> AStar<Context, Observer, OpenList>
+{
+    pub fn new_with_observer(context: Context, observer: Observer) -> Self {
         Self {
             state: AStarState::Empty,
             context,
             closed_list: Default::default(),
-            open_list: BinaryHeap::new_min(),
+            open_list: Default::default(),
             performance_counters: Default::default(),
+            epsilon: None,
+            cost_limit: None,
+            pruned_nodes: Vec::new(),
+            node_heap_size: 0,
+            observer,
         }
     }
 
-    pub fn new_with_buffers(
+    pub fn new_with_buffers_and_observer(
         context: Context,
-        mut buffers: AStarBuffers<<Context::Node as AStarNode>::Identifier, Context::Node>,
+        mut buffers: AStarBuffers<Context::Node, OpenList>,
+        observer: Observer,
     ) -> Self {
         buffers.closed_list.clear();
         buffers.open_list.clear();
+        buffers.open_list.replace_comparator(Default::default());
         Self {
             state: AStarState::Empty,
             context,
             closed_list: buffers.closed_list,
             open_list: buffers.open_list,
             performance_counters: Default::default(),
+            epsilon: None,
+            cost_limit: None,
+            pruned_nodes: Vec::new(),
+            node_heap_size: 0,
+            observer,
         }
     }
 
@@ -201,9 +348,19 @@ impl<Context: AStarContext> AStar<Context> {
         self.context
     }
 
-    pub fn into_buffers(
-        self,
-    ) -> AStarBuffers<<Context::Node as AStarNode>::Identifier, Context::Node> {
+    pub fn observer(&self) -> &Observer {
+        &self.observer
+    }
+
+    pub fn observer_mut(&mut self) -> &mut Observer {
+        &mut self.observer
+    }
+
+    pub fn into_observer(self) -> Observer {
+        self.observer
+    }
+
+    pub fn into_buffers(self) -> AStarBuffers<Context::Node, OpenList> {
         AStarBuffers {
             closed_list: self.closed_list,
             open_list: self.open_list,
@@ -213,10 +370,34 @@ impl<Context: AStarContext> AStar<Context> {
     pub fn closed_node(
         &self,
         node_identifier: &<Context::Node as AStarNode>::Identifier,
-    ) -> Option<&Context::Node> {
+    ) -> Option<ClosedNode<'_, Context::Node>> {
         self.closed_list.get(node_identifier)
     }
 
+    /// Iterates over the identifiers of all closed nodes, together with the nodes.
+    pub fn closed_nodes(
+        &self,
+    ) -> impl Iterator<
+        Item = (
+            &<Context::Node as AStarNode>::Identifier,
+            ClosedNode<'_, Context::Node>,
+        ),
+    > {
+        self.closed_list.iter()
+    }
+
+    pub fn closed_list_mode(&self) -> ClosedListMode {
+        self.closed_list.mode()
+    }
+
+    /// Set how the closed list stores its nodes.
+    ///
+    /// **Panics** if the search was already initialised.
+    pub fn set_closed_list_mode(&mut self, mode: ClosedListMode) {
+        assert_eq!(self.state, AStarState::Empty);
+        self.closed_list.reset(mode);
+    }
+
     pub fn performance_counters(&self) -> &AStarPerformanceCounters {
         &self.performance_counters
     }
@@ -226,7 +407,12 @@ impl<Context: AStarContext> AStar<Context> {
         self.context.reset();
         self.closed_list.clear();
         self.open_list.clear();
+        self.open_list.replace_comparator(Default::default());
         self.performance_counters = Default::default();
+        self.epsilon = None;
+        self.cost_limit = None;
+        self.pruned_nodes.clear();
+        self.node_heap_size = 0;
     }
 
     pub fn initialise(&mut self) {
@@ -234,10 +420,60 @@ impl<Context: AStarContext> AStar<Context> {
     }
 
     pub fn initialise_with(&mut self, node: impl FnOnce(&Context) -> Context::Node) {
+        self.initialise_with_roots(|context| [node(context)]);
+    }
+
+    /// Initialise the search with multiple root nodes, each with its own start cost.
+    ///
+    /// Backtracking stops at whichever root a path starts from.
+    /// Further roots declared by [`AStarContext::next_root`] are still created lazily while searching.
+    pub fn initialise_with_roots<Roots: IntoIterator<Item = Context::Node>>(
+        &mut self,
+        roots: impl FnOnce(&Context) -> Roots,
+    ) {
         assert_eq!(self.state, AStarState::Empty);
 
         self.state = AStarState::Init;
-        self.open_list.push(node(&self.context));
+        for root in roots(&self.context) {
+            self.node_heap_size += root.heap_size();
+            self.open_list.push(root);
+        }
+    }
+
+    /// Override the cost limit of the context with `cost_limit`.
+    ///
+    /// Nodes exceeding the cost limit are kept in a side buffer instead of being dropped.
+    /// Hence, if the search terminated with [`AStarResult::ExceededCostLimit`], the cost limit can be raised,
+    /// and the search can be continued by e.g. [`search`](Self::search) without repeating the work done so far.
+    ///
+    /// **Panics** if the search was started without calling this method before,
+    /// or if it did not terminate because of the cost limit.
+    pub fn set_cost_limit(&mut self, cost_limit: <Context::Node as AStarNode>::Cost) {
+        match &self.state {
+            AStarState::Empty | AStarState::Init => {}
+            AStarState::Terminated {
+                result: AStarResult::ExceededCostLimit { .. },
+            } => assert!(
+                self.cost_limit.is_some(),
+                "Cannot raise the cost limit, since the pruned nodes were not kept"
+            ),
+            state => panic!("Cannot set the cost limit in state {state:?}"),
+        }
+
+        self.cost_limit = Some(cost_limit);
+
+        let mut index = 0;
+        while index < self.pruned_nodes.len() {
+            let node = &self.pruned_nodes[index];
+            if node.cost() + node.a_star_lower_bound() <= cost_limit {
+                let node = self.pruned_nodes.swap_remove(index);
+                self.observer.on_node_opened(&node);
+                self.open_list.push(node);
+                self.performance_counters.opened_nodes += 1;
+            } else {
+                index += 1;
+            }
+        }
     }
 
     pub fn search(
@@ -248,8 +484,60 @@ impl<Context: AStarContext> AStar<Context> {
     }
 
     pub fn search_until(
+        &mut self,
+        is_target: impl FnMut(&Context, &Context::Node) -> bool,
+    ) -> AStarResult<<Context::Node as AStarNode>::Identifier, <Context::Node as AStarNode>::Cost>
+    {
+        self.search_internal(is_target, None)
+    }
+
+    /// Search for a target node with the A* lower bounds inflated by the factor `epsilon`.
+    ///
+    /// The cost of the returned target is at most `epsilon` times the optimal cost,
+    /// and the result contains a proven lower bound on the optimal cost that may be tighter.
+    ///
+    /// If a target was already found, then the search continues from where it stopped and returns an improved target if one exists.
+    /// Calling this method repeatedly with decreasing `epsilon` hence results in an anytime search in the style of ARA*.
+    /// If the memory or cost limit is reached after a target was found, then the best target found so far is returned.
+    pub fn search_weighted(
+        &mut self,
+        epsilon: f64,
+    ) -> AStarResult<<Context::Node as AStarNode>::Identifier, <Context::Node as AStarNode>::Cost>
+    {
+        assert!(
+            epsilon >= 1.0,
+            "The weight of the lower bound must be at least one, but is {epsilon}"
+        );
+
+        let incumbent = if let AStarState::Terminated {
+            result: AStarResult::FoundTarget {
+                identifier, cost, ..
+            },
+        } = &self.state
+        {
+            Some((identifier.clone(), *cost))
+        } else {
+            None
+        };
+
+        self.epsilon = Some(epsilon);
+        self.open_list
+            .replace_comparator(AStarNodeComparator { epsilon });
+        self.search_internal(|context, node| context.is_target(node), incumbent)
+    }
+
+    /// The main search loop.
+    ///
+    /// If `incumbent` is given, then it is a target found by a previous weighted search,
+    /// and the search terminates once no cheaper target can be found.
+    #[allow(clippy::type_complexity)]
+    fn search_internal(
         &mut self,
         mut is_target: impl FnMut(&Context, &Context::Node) -> bool,
+        incumbent: Option<(
+            <Context::Node as AStarNode>::Identifier,
+            <Context::Node as AStarNode>::Cost,
+        )>,
     ) -> AStarResult<<Context::Node as AStarNode>::Identifier, <Context::Node as AStarNode>::Cost>
     {
         assert!(matches!(
@@ -258,30 +546,46 @@ impl<Context: AStarContext> AStar<Context> {
         ));
 
         let cost_limit = self
-            .context
-            .cost_limit()
+            .cost_limit
+            .or_else(|| self.context.cost_limit())
             .unwrap_or(<Context::Node as AStarNode>::Cost::max_value());
-        let mut applied_cost_limit = false;
+        // Nodes pruned by a previous search with a lower cost limit may still exceed the current one.
+        let mut applied_cost_limit = !self.pruned_nodes.is_empty();
         let memory_limit = self.context.memory_limit().unwrap_or(usize::MAX);
-        // The factor of 2.3 is determined empirically.
-        let node_count_limit =
-            (memory_limit as f64 / std::mem::size_of::<Context::Node>() as f64 / 2.3).round()
-                as usize;
 
-        if self.open_list.is_empty() {
+        if self.open_list.is_empty() && incumbent.is_none() {
+            if applied_cost_limit {
+                self.state = AStarState::Terminated {
+                    result: AStarResult::ExceededCostLimit { cost_limit },
+                };
+                return AStarResult::ExceededCostLimit { cost_limit };
+            }
+
             return AStarResult::NoTarget;
         }
 
         self.state = AStarState::Searching;
 
         let mut last_node = None;
+        let mut iterations = 0;
 
         let target_identifier = loop {
+            if let Some((incumbent_identifier, incumbent_cost)) = &incumbent {
+                // No node in the open list can lead to a target cheaper than the incumbent.
+                if self.open_list.peek().is_none_or(|node| {
+                    incumbent_cost.as_f64() <= weighted_cost(node, self.epsilon.unwrap_or(1.0))
+                }) {
+                    break incumbent_identifier.clone();
+                }
+            }
+
             let Some(node) = self.open_list.pop() else {
                 if last_node.is_none() {
                     unreachable!("Open list was empty.");
                 };
                 if applied_cost_limit {
+                    self.observer
+                        .on_limit_reached(&AStarResult::ExceededCostLimit { cost_limit });
                     self.state = AStarState::Terminated {
                         result: AStarResult::ExceededCostLimit { cost_limit },
                     };
@@ -293,16 +597,47 @@ impl<Context: AStarContext> AStar<Context> {
                     return AStarResult::NoTarget;
                 }
             };
+            self.node_heap_size -= node.heap_size();
 
-            // Nodes are ordered by cost plus lower bound.
             if node.cost() + node.a_star_lower_bound() > cost_limit {
+                if self.cost_limit.is_some() {
+                    self.node_heap_size += node.heap_size();
+                    self.pruned_nodes.push(node);
+                }
+
+                if self.epsilon.is_some() {
+                    // Nodes are ordered by cost plus weighted lower bound, so there may be cheaper nodes left.
+                    applied_cost_limit = true;
+                    continue;
+                }
+
+                // Nodes are ordered by cost plus lower bound.
+                self.observer
+                    .on_limit_reached(&AStarResult::ExceededCostLimit { cost_limit });
                 self.state = AStarState::Terminated {
                     result: AStarResult::ExceededCostLimit { cost_limit },
                 };
                 return AStarResult::ExceededCostLimit { cost_limit };
             }
 
-            if self.closed_list.len() + self.open_list.len() > node_count_limit {
+            let memory_usage = self.memory_usage();
+            self.performance_counters.peak_memory_usage = self
+                .performance_counters
+                .peak_memory_usage
+                .max(memory_usage);
+            if memory_usage > memory_limit {
+                if let Some((incumbent_identifier, _)) = &incumbent {
+                    // Keep the best target found so far.
+                    // The node is put back such that it is considered when computing the lower bound.
+                    self.node_heap_size += node.heap_size();
+                    self.open_list.push(node);
+                    break incumbent_identifier.clone();
+                }
+
+                self.observer
+                    .on_limit_reached(&AStarResult::ExceededMemoryLimit {
+                        max_cost: node.cost(),
+                    });
                 self.state = AStarState::Terminated {
                     result: AStarResult::ExceededMemoryLimit {
                         max_cost: node.cost(),
@@ -313,83 +648,173 @@ impl<Context: AStarContext> AStar<Context> {
                 };
             }
 
+            if let Some(result) = interruption(&self.context, &mut iterations, node.cost()) {
+                // The node is put back such that the search can be continued.
+                self.node_heap_size += node.heap_size();
+                self.open_list.push(node);
+
+                if let Some((incumbent_identifier, _)) = &incumbent {
+                    // Keep the best target found so far.
+                    break incumbent_identifier.clone();
+                }
+
+                self.observer.on_limit_reached(&result);
+                self.state = AStarState::Terminated {
+                    result: result.clone(),
+                };
+                return result;
+            }
+
             last_node = Some(node.identifier().clone());
 
-            if let Some(previous_visit) = self.closed_list.get(node.identifier()) {
-                if self.context.is_label_setting() {
-                    // In label-setting mode, if we have already visited the node, we now must be visiting it with a higher cost.
-                    debug_assert!(
-                        previous_visit.cost() + previous_visit.a_star_lower_bound()
-                            <= node.cost() + node.a_star_lower_bound(),
-                        "{}",
-                        {
-                            use std::fmt::Write;
-                            let mut previous_visit = previous_visit;
-                            let mut node = &node;
-                            let mut out = String::new();
-
-                            writeln!(out, "previous_visit:").unwrap();
-                            while let Some(predecessor) = previous_visit.predecessor() {
-                                writeln!(out, "{previous_visit}").unwrap();
-                                previous_visit = self.closed_list.get(predecessor).unwrap();
-                            }
+            if node.predecessor().is_none() {
+                let open_nodes_without_next_root = self.open_list.len();
+                OpenListExtend::<_, _, _> {
+                    open_list: &mut self.open_list,
+                    pruned_nodes: self.cost_limit.is_some().then_some(&mut self.pruned_nodes),
+                    cost_limit,
+                    applied_cost_limit: &mut applied_cost_limit,
+                    node_heap_size: &mut self.node_heap_size,
+                    observer: &mut self.observer,
+                }
+                .extend(self.context.next_root(&node));
+                self.performance_counters.opened_nodes +=
+                    self.open_list.len() - open_nodes_without_next_root;
+            }
 
-                            writeln!(out, "\nnode:").unwrap();
-                            while let Some(predecessor) = node.predecessor() {
-                                writeln!(out, "{node}").unwrap();
-                                node = self.closed_list.get(predecessor).unwrap();
+            if let Some(previous_visit) = self.closed_list.get(node.identifier()) {
+                if (self.epsilon.is_some() || !self.context.is_label_setting())
+                    && node.cost() < previous_visit.cost()
+                {
+                    // In a weighted search or if the context is not label setting, nodes may have been closed with a suboptimal cost.
+                    // Hence, we reopen them if we find a cheaper path to them.
+                    self.performance_counters.reopened_nodes += 1;
+                } else {
+                    // Compactly stored nodes lack the lower bound, so this can only be checked for fully stored nodes.
+                    if let Some(previous_visit) = previous_visit
+                        .node()
+                        .filter(|_| self.context.is_label_setting() && self.epsilon.is_none())
+                    {
+                        // In label-setting mode, if we have already visited the node, we now must be visiting it with a higher cost.
+                        debug_assert!(
+                            previous_visit.cost() + previous_visit.a_star_lower_bound()
+                                <= node.cost() + node.a_star_lower_bound(),
+                            "{}",
+                            {
+                                use std::fmt::Write;
+                                let closed_node = |identifier| {
+                                    self.closed_list.get(identifier).unwrap().node().unwrap()
+                                };
+                                let mut previous_visit = previous_visit;
+                                let mut node = &node;
+                                let mut out = String::new();
+
+                                writeln!(out, "previous_visit:").unwrap();
+                                while let Some(predecessor) = previous_visit.predecessor() {
+                                    writeln!(out, "{previous_visit}").unwrap();
+                                    previous_visit = closed_node(predecessor);
+                                }
+
+                                writeln!(out, "\nnode:").unwrap();
+                                while let Some(predecessor) = node.predecessor() {
+                                    writeln!(out, "{node}").unwrap();
+                                    node = closed_node(predecessor);
+                                }
+
+                                out
                             }
+                        );
+                    }
 
-                            out
-                        }
-                    );
+                    self.performance_counters.suboptimal_opened_nodes += 1;
+                    self.observer.on_suboptimal_node(&node);
+                    continue;
                 }
+            }
 
-                self.performance_counters.suboptimal_opened_nodes += 1;
-                continue;
+            if self.observer.on_node_closed(&node).is_break() {
+                // The node is put back such that the search can be continued.
+                self.node_heap_size += node.heap_size();
+                let max_cost = node.cost();
+                self.open_list.push(node);
+                self.state = AStarState::Terminated {
+                    result: AStarResult::Stopped { max_cost },
+                };
+                return AStarResult::Stopped { max_cost };
             }
 
             let open_nodes_without_new_successors = self.open_list.len();
             self.context.generate_successors(
                 &node,
-                &mut ExtendFilter::new(&mut self.open_list, |node| {
-                    let result = node.cost() + node.a_star_lower_bound() <= cost_limit;
-                    applied_cost_limit = applied_cost_limit || !result;
-                    result
-                }),
+                &mut OpenListExtend::<_, _, _> {
+                    open_list: &mut self.open_list,
+                    pruned_nodes: self.cost_limit.is_some().then_some(&mut self.pruned_nodes),
+                    cost_limit,
+                    applied_cost_limit: &mut applied_cost_limit,
+                    node_heap_size: &mut self.node_heap_size,
+                    observer: &mut self.observer,
+                },
             );
             self.performance_counters.opened_nodes +=
                 self.open_list.len() - open_nodes_without_new_successors;
 
             if is_target(&self.context, &node) {
+                self.observer.on_target_found(&node);
                 let identifier = node.identifier().clone();
-                let previous_visit = self.closed_list.insert(node.identifier().clone(), node);
+                let previous_visit = self.closed_list.insert(node);
                 self.performance_counters.closed_nodes += 1;
-                debug_assert!(previous_visit.is_none() || !self.context.is_label_setting());
+                debug_assert!(
+                    !previous_visit || !self.context.is_label_setting() || self.epsilon.is_some()
+                );
                 break identifier;
             }
 
-            let previous_visit = self.closed_list.insert(node.identifier().clone(), node);
+            let previous_visit = self.closed_list.insert(node);
             self.performance_counters.closed_nodes += 1;
-            debug_assert!(previous_visit.is_none() || !self.context.is_label_setting());
+            debug_assert!(
+                !previous_visit || !self.context.is_label_setting() || self.epsilon.is_some()
+            );
         };
 
         let cost = self.closed_list.get(&target_identifier).unwrap().cost();
+        // In a weighted search, the cheapest lower bound in the open list bounds the cost of an optimal target.
+        let cost_lower_bound = self.epsilon.map(|_| {
+            self.open_list
+                .iter()
+                .map(|node| node.cost() + node.a_star_lower_bound())
+                .fold(cost, |cost_lower_bound, lower_bound| {
+                    cost_lower_bound.min(lower_bound)
+                })
+        });
         self.state = AStarState::Terminated {
             result: AStarResult::FoundTarget {
                 identifier: target_identifier.clone(),
                 cost,
+                cost_lower_bound,
             },
         };
         AStarResult::FoundTarget {
             identifier: target_identifier,
             cost,
+            cost_lower_bound,
         }
     }
 
+    /// Returns the approximate number of bytes currently used by the search.
+    ///
+    /// This includes the open and closed lists as well as the heap memory reported by the context.
+    pub fn memory_usage(&self) -> usize {
+        self.open_list.heap_size()
+            + memory::vec_heap_size::<Context::Node>(self.pruned_nodes.capacity())
+            + self.closed_list.heap_size()
+            + self.node_heap_size
+            + self.context.heap_size()
+    }
+
     pub fn backtrack(
         &self,
-    ) -> impl use<'_, Context> + Iterator<Item = <Context::Node as AStarNode>::EdgeType> {
+    ) -> impl use<'_, Context, Observer, OpenList>
+    + Iterator<Item = <Context::Node as AStarNode>::EdgeType> {
         let AStarState::Terminated {
             result: AStarResult::FoundTarget { identifier, .. },
         } = &self.state
@@ -406,7 +831,7 @@ impl<Context: AStarContext> AStar<Context> {
     /// The cost of the first node is never returned.
     pub fn backtrack_with_costs(
         &self,
-    ) -> impl use<'_, Context>
+    ) -> impl use<'_, Context, Observer, OpenList>
     + Iterator<
         Item = (
             <Context::Node as AStarNode>::EdgeType,
@@ -426,16 +851,28 @@ impl<Context: AStarContext> AStar<Context> {
     pub fn backtrack_from(
         &self,
         identifier: &<Context::Node as AStarNode>::Identifier,
-    ) -> Option<impl use<'_, Context> + Iterator<Item = <Context::Node as AStarNode>::EdgeType>>
-    {
-        if self.closed_list.contains_key(identifier) {
-            Some(BacktrackingIterator {
-                a_star: self,
-                current: identifier.clone(),
-            })
-        } else {
-            None
-        }
+    ) -> Option<
+        impl use<'_, Context, Observer, OpenList>
+        + Iterator<Item = <Context::Node as AStarNode>::EdgeType>,
+    > {
+        Some(
+            self.closed_list
+                .backtrack(identifier)?
+                .map(|(edge_type, _)| edge_type),
+        )
+    }
+
+    /// Returns the root node that the path to the node with this identifier starts from.
+    ///
+    /// Backtracking does not return the edge type of the root, as it has no predecessor.
+    /// With [multiple roots](AStarContext::next_root), the edge type of the root can be used to distinguish them.
+    ///
+    /// Returns `None` if the node is not closed.
+    pub fn backtrack_root_from(
+        &self,
+        identifier: &<Context::Node as AStarNode>::Identifier,
+    ) -> Option<ClosedNode<'_, Context::Node>> {
+        self.closed_list.root(identifier)
     }
 
     /// Backtrack from a node to a root node.
@@ -447,7 +884,7 @@ impl<Context: AStarContext> AStar<Context> {
         &self,
         identifier: &<Context::Node as AStarNode>::Identifier,
     ) -> Option<
-        impl use<'_, Context>
+        impl use<'_, Context, Observer, OpenList>
         + Iterator<
             Item = (
                 <Context::Node as AStarNode>::EdgeType,
@@ -455,17 +892,96 @@ impl<Context: AStarContext> AStar<Context> {
             ),
         >,
     > {
-        if self.closed_list.contains_key(identifier) {
-            Some(BacktrackingIteratorWithCost {
-                a_star: self,
-                current: identifier.clone(),
-            })
+        self.closed_list.backtrack(identifier)
+    }
+}
+
+impl<Node: AStarNode> Compare<Node> for AStarNodeComparator {
+    fn compare(&self, l: &Node, r: &Node) -> Ordering {
+        // The binary heap is a max heap, so the ordering is reversed.
+        if self.epsilon == 1.0 {
+            r.cmp(l)
         } else {
-            None
+            weighted_cost(r, self.epsilon)
+                .total_cmp(&weighted_cost(l, self.epsilon))
+                .then_with(|| r.cmp(l))
         }
     }
 }
 
+impl Default for AStarNodeComparator {
+    fn default() -> Self {
+        Self { epsilon: 1.0 }
+    }
+}
+
+/// Querying the clock is comparatively expensive, so the deadline is checked only once per this many iterations.
+const DEADLINE_CHECK_INTERVAL: usize = 1024;
+
+/// Returns the result that a search should terminate with if it was cancelled or its deadline was reached.
+///
+/// This should be called once per iteration of the search, with the maximum cost reached so far.
+fn interruption<Context: AStarContext, NodeIdentifier>(
+    context: &Context,
+    iterations: &mut usize,
+    max_cost: <Context::Node as AStarNode>::Cost,
+) -> Option<AStarResult<NodeIdentifier, <Context::Node as AStarNode>::Cost>> {
+    if context
+        .cancellation_token()
+        .is_some_and(CancellationToken::is_cancelled)
+    {
+        return Some(AStarResult::Cancelled { max_cost });
+    }
+
+    *iterations += 1;
+    if *iterations % DEADLINE_CHECK_INTERVAL == 1
+        && context
+            .deadline()
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    {
+        return Some(AStarResult::ExceededTimeLimit { max_cost });
+    }
+
+    None
+}
+
+/// Inserts generated nodes into the open list if they do not exceed the cost limit.
+///
+/// Nodes exceeding the cost limit are dropped, or stored in `pruned_nodes` if it is given.
+struct OpenListExtend<'a_star, Node: AStarNode, Observer, OpenList> {
+    open_list: &'a_star mut OpenList,
+    pruned_nodes: Option<&'a_star mut Vec<Node>>,
+    cost_limit: Node::Cost,
+    applied_cost_limit: &'a_star mut bool,
+    node_heap_size: &'a_star mut usize,
+    observer: &'a_star mut Observer,
+}
+
+impl<Node: AStarNode, Observer: AStarObserver<Node>, OpenList: AStarOpenList<Node>> Extend<Node>
+    for OpenListExtend<'_, Node, Observer, OpenList>
+{
+    fn extend<T: IntoIterator<Item = Node>>(&mut self, iter: T) {
+        for node in iter {
+            if node.cost() + node.a_star_lower_bound() <= self.cost_limit {
+                *self.node_heap_size += node.heap_size();
+                self.observer.on_node_opened(&node);
+                self.open_list.push(node);
+            } else {
+                *self.applied_cost_limit = true;
+                if let Some(pruned_nodes) = &mut self.pruned_nodes {
+                    *self.node_heap_size += node.heap_size();
+                    pruned_nodes.push(node);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the cost of the node plus its A* lower bound weighted by `epsilon`.
+fn weighted_cost<Node: AStarNode>(node: &Node, epsilon: f64) -> f64 {
+    node.cost().as_f64() + epsilon * node.a_star_lower_bound().as_f64()
+}
+
 impl<NodeIdentifier, Cost: Copy> AStarResult<NodeIdentifier, Cost> {
     /// Returns the maximum cost of closed nodes reached during alignment.
     ///
@@ -474,19 +990,53 @@ impl<NodeIdentifier, Cost: Copy> AStarResult<NodeIdentifier, Cost> {
         match self {
             Self::FoundTarget { cost, .. } => *cost,
             Self::ExceededCostLimit { cost_limit } => *cost_limit,
-            Self::ExceededMemoryLimit { max_cost } => *max_cost,
+            Self::ExceededMemoryLimit { max_cost }
+            | Self::ExceededTimeLimit { max_cost }
+            | Self::Cancelled { max_cost }
+            | Self::Stopped { max_cost } => *max_cost,
             Self::NoTarget => panic!("AStarResult has no costs"),
         }
     }
 
+    /// Returns the factor by which the cost of the found target is proven to be at most larger than the optimal cost.
+    ///
+    /// This is `1.0` if the target is optimal or no target was found.
+    pub fn suboptimality_bound(&self) -> f64
+    where
+        Cost: AStarCost,
+    {
+        match self {
+            Self::FoundTarget {
+                cost,
+                cost_lower_bound: Some(cost_lower_bound),
+                ..
+            } => {
+                if cost_lower_bound.is_zero() {
+                    if cost.is_zero() { 1.0 } else { f64::INFINITY }
+                } else {
+                    cost.as_f64() / cost_lower_bound.as_f64()
+                }
+            }
+            _ => 1.0,
+        }
+    }
+
     pub fn without_node_identifier(&self) -> AStarResult<(), Cost> {
         match *self {
-            Self::FoundTarget { cost, .. } => AStarResult::FoundTarget {
+            Self::FoundTarget {
+                cost,
+                cost_lower_bound,
+                ..
+            } => AStarResult::FoundTarget {
                 identifier: (),
                 cost,
+                cost_lower_bound,
             },
             Self::ExceededCostLimit { cost_limit } => AStarResult::ExceededCostLimit { cost_limit },
             Self::ExceededMemoryLimit { max_cost } => AStarResult::ExceededMemoryLimit { max_cost },
+            Self::ExceededTimeLimit { max_cost } => AStarResult::ExceededTimeLimit { max_cost },
+            Self::Cancelled { max_cost } => AStarResult::Cancelled { max_cost },
+            Self::Stopped { max_cost } => AStarResult::Stopped { max_cost },
             Self::NoTarget => AStarResult::NoTarget,
         }
     }
@@ -498,9 +1048,14 @@ impl<NodeIdentifier: Clone, Cost> AStarResult<NodeIdentifier, Cost> {
         transform: impl Fn(&Cost) -> TargetCost,
     ) -> AStarResult<NodeIdentifier, TargetCost> {
         match self {
-            AStarResult::FoundTarget { identifier, cost } => AStarResult::FoundTarget {
+            AStarResult::FoundTarget {
+                identifier,
+                cost,
+                cost_lower_bound,
+            } => AStarResult::FoundTarget {
                 identifier: identifier.clone(),
                 cost: transform(cost),
+                cost_lower_bound: cost_lower_bound.as_ref().map(&transform),
             },
             AStarResult::ExceededCostLimit { cost_limit } => AStarResult::ExceededCostLimit {
                 cost_limit: transform(cost_limit),
@@ -508,52 +1063,34 @@ impl<NodeIdentifier: Clone, Cost> AStarResult<NodeIdentifier, Cost> {
             AStarResult::ExceededMemoryLimit { max_cost } => AStarResult::ExceededMemoryLimit {
                 max_cost: transform(max_cost),
             },
+            AStarResult::ExceededTimeLimit { max_cost } => AStarResult::ExceededTimeLimit {
+                max_cost: transform(max_cost),
+            },
+            AStarResult::Cancelled { max_cost } => AStarResult::Cancelled {
+                max_cost: transform(max_cost),
+            },
+            AStarResult::Stopped { max_cost } => AStarResult::Stopped {
+                max_cost: transform(max_cost),
+            },
             AStarResult::NoTarget => AStarResult::NoTarget,
         }
     }
 }
 
-impl<Context: AStarContext> Iterator for BacktrackingIterator<'_, Context> {
-    type Item = <Context::Node as AStarNode>::EdgeType;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let current = self.a_star.closed_list.get(&self.current).unwrap();
-
-        if let Some(predecessor) = current.predecessor().cloned() {
-            let predecessor_edge_type = current.predecessor_edge_type().unwrap();
-            self.current = predecessor;
-            Some(predecessor_edge_type)
-        } else {
-            None
-        }
-    }
-}
-
-impl<Context: AStarContext> Iterator for BacktrackingIteratorWithCost<'_, Context> {
-    type Item = (
-        <Context::Node as AStarNode>::EdgeType,
-        <Context::Node as AStarNode>::Cost,
-    );
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let current = self.a_star.closed_list.get(&self.current).unwrap();
-        let cost = current.cost();
-
-        if let Some(predecessor) = current.predecessor().cloned() {
-            let predecessor_edge_type = current.predecessor_edge_type().unwrap();
-            self.current = predecessor;
-            Some((predecessor_edge_type, cost))
-        } else {
-            None
-        }
+impl<Node: AStarNode, OpenList: AStarOpenList<Node>> AStarBuffers<Node, OpenList> {
+    /// Returns the approximate number of bytes allocated by the buffers.
+    ///
+    /// Heap memory owned by nodes stored in the open list is not included.
+    pub fn heap_size(&self) -> usize {
+        self.open_list.heap_size() + self.closed_list.heap_size()
     }
 }
 
-impl<NodeIdentifier, Node: Ord> Default for AStarBuffers<NodeIdentifier, Node> {
+impl<Node: AStarNode, OpenList: AStarOpenList<Node>> Default for AStarBuffers<Node, OpenList> {
     fn default() -> Self {
         Self {
             closed_list: Default::default(),
-            open_list: BinaryHeap::new_min(),
+            open_list: Default::default(),
         }
     }
 }
@@ -561,7 +1098,19 @@ impl<NodeIdentifier, Node: Ord> Default for AStarBuffers<NodeIdentifier, Node> {
 impl<NodeIdentifier, Cost: Display> Display for AStarResult<NodeIdentifier, Cost> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            AStarResult::FoundTarget { cost, .. } => write!(f, "Reached target with cost {cost}"),
+            AStarResult::FoundTarget {
+                cost,
+                cost_lower_bound: None,
+                ..
+            } => write!(f, "Reached target with cost {cost}"),
+            AStarResult::FoundTarget {
+                cost,
+                cost_lower_bound: Some(cost_lower_bound),
+                ..
+            } => write!(
+                f,
+                "Reached target with cost {cost}, where the optimal cost is at least {cost_lower_bound}"
+            ),
             AStarResult::ExceededCostLimit { cost_limit } => {
                 write!(f, "Exceeded cost limit of {cost_limit}")
             }
@@ -569,6 +1118,17 @@ impl<NodeIdentifier, Cost: Display> Display for AStarResult<NodeIdentifier, Cost
                 f,
                 "Exceeded memory limit, but reached a maximum cost of {max_cost}"
             ),
+            AStarResult::ExceededTimeLimit { max_cost } => write!(
+                f,
+                "Exceeded time limit, but reached a maximum cost of {max_cost}"
+            ),
+            AStarResult::Cancelled { max_cost } => {
+                write!(f, "Cancelled, but reached a maximum cost of {max_cost}")
+            }
+            AStarResult::Stopped { max_cost } => write!(
+                f,
+                "Stopped by observer, but reached a maximum cost of {max_cost}"
+            ),
             AStarResult::NoTarget => write!(f, "Found no target"),
         }
     }
@@ -0,0 +1,20 @@
+//! Estimates of the heap memory used by common data structures.
+//!
+//! These are used to implement [`AStarNode::heap_size`](crate::AStarNode::heap_size) and [`AStarContext::heap_size`](crate::AStarContext::heap_size).
+
+/// Returns the number of heap bytes used by a [`Vec`] with the given capacity.
+pub fn vec_heap_size<T>(capacity: usize) -> usize {
+    capacity * size_of::<T>()
+}
+
+/// Returns the approximate number of heap bytes used by a [`HashMap`](std::collections::HashMap) with the given capacity.
+///
+/// The standard library hash map has a load factor of 7/8, a power of two number of buckets and one control byte per bucket.
+pub fn hash_map_heap_size<Key, Value>(capacity: usize) -> usize {
+    if capacity == 0 {
+        return 0;
+    }
+
+    let buckets = (capacity * 8 / 7).next_power_of_two();
+    buckets * (size_of::<(Key, Value)>() + 1)
+}
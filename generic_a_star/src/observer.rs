@@ -0,0 +1,126 @@
+use std::ops::ControlFlow;
+
+use crate::{AStarNode, AStarResult};
+
+/// Receives events from an [`AStar`](crate::AStar) search.
+///
+/// All methods have empty default implementations, and the unit type `()` implements this trait without overriding any of them.
+/// Since the search is generic over the observer, a search without observer has no overhead.
+pub trait AStarObserver<Node: AStarNode> {
+    /// Called when a node is inserted into the open list.
+    fn on_node_opened(&mut self, _node: &Node) {}
+
+    /// Called when a node is closed, before its successors are generated.
+    ///
+    /// If this returns [`ControlFlow::Break`], then the search terminates with [`AStarResult::Stopped`].
+    fn on_node_closed(&mut self, _node: &Node) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called when a node is popped from the open list, but skipped because it was already closed with a lower cost.
+    fn on_suboptimal_node(&mut self, _node: &Node) {}
+
+    /// Called when a target node is found.
+    fn on_target_found(&mut self, _node: &Node) {}
+
+    /// Called when the search terminates because of a limit or because it was cancelled.
+    fn on_limit_reached(&mut self, _result: &AStarResult<Node::Identifier, Node::Cost>) {}
+}
+
+impl<Node: AStarNode> AStarObserver<Node> for () {}
+
+impl<Node: AStarNode, Observer: AStarObserver<Node> + ?Sized> AStarObserver<Node>
+    for &mut Observer
+{
+    fn on_node_opened(&mut self, node: &Node) {
+        (**self).on_node_opened(node)
+    }
+
+    fn on_node_closed(&mut self, node: &Node) -> ControlFlow<()> {
+        (**self).on_node_closed(node)
+    }
+
+    fn on_suboptimal_node(&mut self, node: &Node) {
+        (**self).on_suboptimal_node(node)
+    }
+
+    fn on_target_found(&mut self, node: &Node) {
+        (**self).on_target_found(node)
+    }
+
+    fn on_limit_reached(&mut self, result: &AStarResult<Node::Identifier, Node::Cost>) {
+        (**self).on_limit_reached(result)
+    }
+}
+
+/// Forwards all events to the contained observer, if any.
+impl<Node: AStarNode, Observer: AStarObserver<Node>> AStarObserver<Node> for Option<Observer> {
+    fn on_node_opened(&mut self, node: &Node) {
+        if let Some(observer) = self {
+            observer.on_node_opened(node)
+        }
+    }
+
+    fn on_node_closed(&mut self, node: &Node) -> ControlFlow<()> {
+        if let Some(observer) = self {
+            observer.on_node_closed(node)
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    fn on_suboptimal_node(&mut self, node: &Node) {
+        if let Some(observer) = self {
+            observer.on_suboptimal_node(node)
+        }
+    }
+
+    fn on_target_found(&mut self, node: &Node) {
+        if let Some(observer) = self {
+            observer.on_target_found(node)
+        }
+    }
+
+    fn on_limit_reached(&mut self, result: &AStarResult<Node::Identifier, Node::Cost>) {
+        if let Some(observer) = self {
+            observer.on_limit_reached(result)
+        }
+    }
+}
+
+/// Forwards all events to both observers.
+///
+/// The search is stopped if any of the observers requests it.
+impl<Node: AStarNode, First: AStarObserver<Node>, Second: AStarObserver<Node>> AStarObserver<Node>
+    for (First, Second)
+{
+    fn on_node_opened(&mut self, node: &Node) {
+        self.0.on_node_opened(node);
+        self.1.on_node_opened(node);
+    }
+
+    fn on_node_closed(&mut self, node: &Node) -> ControlFlow<()> {
+        let first = self.0.on_node_closed(node);
+        let second = self.1.on_node_closed(node);
+        if first.is_break() || second.is_break() {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    fn on_suboptimal_node(&mut self, node: &Node) {
+        self.0.on_suboptimal_node(node);
+        self.1.on_suboptimal_node(node);
+    }
+
+    fn on_target_found(&mut self, node: &Node) {
+        self.0.on_target_found(node);
+        self.1.on_target_found(node);
+    }
+
+    fn on_limit_reached(&mut self, result: &AStarResult<Node::Identifier, Node::Cost>) {
+        self.0.on_limit_reached(result);
+        self.1.on_limit_reached(result);
+    }
+}
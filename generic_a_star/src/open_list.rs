@@ -0,0 +1,373 @@
+//! Implementations of the open list of an [`AStar`](crate::AStar) search.
+//!
+//! The open list used by default is selected by the [cost type](AStarCost::OpenList) of the nodes.
+
+use std::{collections::VecDeque, fmt::Debug};
+
+use binary_heap_plus::BinaryHeap;
+
+use crate::{AStarNode, AStarNodeComparator, cost::AStarCost, memory, weighted_cost};
+
+/// The open list that is used for nodes of type `Node` if no other open list is specified.
+pub type DefaultOpenList<Node> = <<Node as AStarNode>::Cost as AStarCost>::OpenList<Node>;
+
+/// A priority queue of nodes, ordered by an [`AStarNodeComparator`].
+///
+/// Nodes that compare equal may be popped in any order.
+/// Hence, tie-breaking relies on the [`Ord`] implementation of the nodes only.
+pub trait AStarOpenList<Node: AStarNode>: Debug + Default + Extend<Node> {
+    fn push(&mut self, node: Node);
+
+    /// Removes and returns the first node according to the comparator.
+    fn pop(&mut self) -> Option<Node>;
+
+    /// Returns the first node according to the comparator.
+    fn peek(&self) -> Option<&Node>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn clear(&mut self);
+
+    /// Iterates over all nodes in arbitrary order.
+    fn iter<'open_list>(&'open_list self) -> impl Iterator<Item = &'open_list Node> + Clone
+    where
+        Node: 'open_list;
+
+    /// Removes all nodes and returns them in arbitrary order.
+    fn drain(&mut self) -> impl Iterator<Item = Node>;
+
+    /// Replaces the comparator and reorders the nodes accordingly.
+    fn replace_comparator(&mut self, comparator: AStarNodeComparator);
+
+    /// Returns the number of bytes allocated by the open list.
+    ///
+    /// Heap memory owned by the nodes is not included.
+    fn heap_size(&self) -> usize;
+}
+
+/// An open list implemented as a binary heap.
+///
+/// Pushing and popping take logarithmic time.
+/// This works with any cost type.
+#[derive(Debug)]
+pub struct BinaryHeapOpenList<Node> {
+    heap: BinaryHeap<Node, AStarNodeComparator>,
+}
+
+/// An open list implemented as a bucket queue in the style of Dial's algorithm.
+///
+/// Nodes are put into buckets by their cost plus (weighted) A* lower bound rounded down to an integer.
+/// As long as all nodes of a bucket compare equal, the bucket is a plain stack, so pushing and popping take constant time.
+/// This is the case in an unweighted search if the [`Ord`] implementation of the nodes has no tie-break beyond the cost plus lower bound.
+///
+/// Otherwise, the bucket is turned into a binary heap, so ties are broken like in a [`BinaryHeapOpenList`].
+/// With a weight, the keys are rounded down, so a bucket may mix nodes of different weighted costs,
+/// and popping them out of order would weaken the suboptimality bound of a weighted search.
+/// Since the buckets contain only few nodes, pushing and popping is still faster than with a single binary heap.
+///
+/// This is efficient if the costs are small integers.
+/// Nodes whose bucket would be too far away from the first bucket are kept in a separate binary heap.
+#[derive(Debug)]
+pub struct BucketQueueOpenList<Node> {
+    /// The buckets, where the bucket at index `i` contains the nodes with key `offset + i`.
+    ///
+    /// The first bucket is never empty.
+    buckets: VecDeque<Bucket<Node>>,
+    /// The key of the first bucket.
+    offset: i64,
+    /// The nodes whose key is too far away from `offset`.
+    overflow: BinaryHeap<Node, AStarNodeComparator>,
+    comparator: AStarNodeComparator,
+    len: usize,
+    /// The sum of the capacities of all buckets.
+    bucket_capacity: usize,
+}
+
+/// A bucket of a [`BucketQueueOpenList`].
+#[derive(Debug)]
+enum Bucket<Node> {
+    /// All nodes compare equal, so they can be popped in any order.
+    Equal(Vec<Node>),
+    /// The nodes are ordered by the comparator.
+    Ordered(BinaryHeap<Node, AStarNodeComparator>),
+}
+
+/// The maximum number of buckets in a [`BucketQueueOpenList`].
+const MAX_BUCKETS: usize = 1 << 16;
+
+impl<Node: AStarNode> AStarOpenList<Node> for BinaryHeapOpenList<Node> {
+    fn push(&mut self, node: Node) {
+        self.heap.push(node);
+    }
+
+    fn pop(&mut self) -> Option<Node> {
+        self.heap.pop()
+    }
+
+    fn peek(&self) -> Option<&Node> {
+        self.heap.peek()
+    }
+
+    fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    fn clear(&mut self) {
+        self.heap.clear();
+    }
+
+    fn iter<'open_list>(&'open_list self) -> impl Iterator<Item = &'open_list Node> + Clone
+    where
+        Node: 'open_list,
+    {
+        self.heap.iter()
+    }
+
+    fn drain(&mut self) -> impl Iterator<Item = Node> {
+        self.heap.drain()
+    }
+
+    fn replace_comparator(&mut self, comparator: AStarNodeComparator) {
+        self.heap.replace_cmp(comparator);
+    }
+
+    fn heap_size(&self) -> usize {
+        memory::vec_heap_size::<Node>(self.heap.capacity())
+    }
+}
+
+impl<Node: AStarNode> Bucket<Node> {
+    fn push(&mut self, node: Node, comparator: AStarNodeComparator) {
+        match self {
+            Self::Equal(nodes) => {
+                if nodes
+                    .last()
+                    .is_none_or(|last| compare::Compare::compares_eq(&comparator, last, &node))
+                {
+                    nodes.push(node);
+                } else {
+                    let mut heap = BinaryHeap::from_vec_cmp(std::mem::take(nodes), comparator);
+                    heap.push(node);
+                    *self = Self::Ordered(heap);
+                }
+            }
+            Self::Ordered(heap) => heap.push(node),
+        }
+    }
+
+    fn pop(&mut self) -> Option<Node> {
+        match self {
+            Self::Equal(nodes) => nodes.pop(),
+            Self::Ordered(heap) => heap.pop(),
+        }
+    }
+
+    fn peek(&self) -> Option<&Node> {
+        match self {
+            Self::Equal(nodes) => nodes.last(),
+            Self::Ordered(heap) => heap.peek(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            Self::Equal(nodes) => nodes.is_empty(),
+            Self::Ordered(heap) => heap.is_empty(),
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        match self {
+            Self::Equal(nodes) => nodes.capacity(),
+            Self::Ordered(heap) => heap.capacity(),
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Node> + Clone {
+        let (nodes, heap) = match self {
+            Self::Equal(nodes) => (Some(nodes.iter()), None),
+            Self::Ordered(heap) => (None, Some(heap.iter())),
+        };
+        nodes
+            .into_iter()
+            .flatten()
+            .chain(heap.into_iter().flatten())
+    }
+
+    fn into_vec(self) -> Vec<Node> {
+        match self {
+            Self::Equal(nodes) => nodes,
+            Self::Ordered(heap) => heap.into_vec(),
+        }
+    }
+}
+
+impl<Node: AStarNode> BucketQueueOpenList<Node> {
+    fn key(&self, node: &Node) -> i64 {
+        weighted_cost(node, self.comparator.epsilon).floor() as i64
+    }
+
+    /// Removes empty buckets from the front of the bucket queue to restore its invariant.
+    fn skip_empty_buckets(&mut self) {
+        while self.buckets.front().is_some_and(Bucket::is_empty) {
+            let bucket = self.buckets.pop_front().unwrap();
+            self.bucket_capacity -= bucket.capacity();
+            self.offset += 1;
+        }
+    }
+
+    /// Returns true if the first node is in the overflow heap instead of the buckets.
+    fn first_is_overflow(&self) -> bool {
+        match (
+            self.buckets.front().and_then(Bucket::peek),
+            self.overflow.peek(),
+        ) {
+            (Some(bucket_node), Some(overflow_node)) => {
+                // The comparator is reversed, because the binary heap is a max heap.
+                compare::Compare::compares_gt(&self.comparator, overflow_node, bucket_node)
+            }
+            (None, Some(_)) => true,
+            (_, None) => false,
+        }
+    }
+}
+
+impl<Node: AStarNode> AStarOpenList<Node> for BucketQueueOpenList<Node> {
+    fn push(&mut self, node: Node) {
+        self.len += 1;
+        let key = self.key(&node);
+
+        if self.buckets.is_empty() {
+            self.offset = key;
+        }
+
+        if key < self.offset {
+            let missing_buckets = self.offset.abs_diff(key) as usize;
+            if missing_buckets + self.buckets.len() > MAX_BUCKETS {
+                self.overflow.push(node);
+                return;
+            }
+
+            for _ in 0..missing_buckets {
+                self.buckets.push_front(Bucket::Equal(Vec::new()));
+            }
+            self.offset = key;
+        }
+
+        let index = key.abs_diff(self.offset) as usize;
+        if index >= MAX_BUCKETS {
+            self.overflow.push(node);
+            return;
+        }
+
+        while self.buckets.len() <= index {
+            self.buckets.push_back(Bucket::Equal(Vec::new()));
+        }
+
+        let bucket = &mut self.buckets[index];
+        let capacity = bucket.capacity();
+        bucket.push(node, self.comparator);
+        self.bucket_capacity += bucket.capacity() - capacity;
+    }
+
+    fn pop(&mut self) -> Option<Node> {
+        let node = if self.first_is_overflow() {
+            self.overflow.pop()
+        } else {
+            let node = self.buckets.front_mut().and_then(Bucket::pop);
+            self.skip_empty_buckets();
+            node
+        }?;
+        self.len -= 1;
+        Some(node)
+    }
+
+    fn peek(&self) -> Option<&Node> {
+        if self.first_is_overflow() {
+            self.overflow.peek()
+        } else {
+            self.buckets.front().and_then(Bucket::peek)
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn clear(&mut self) {
+        self.buckets.clear();
+        self.overflow.clear();
+        self.len = 0;
+        self.bucket_capacity = 0;
+    }
+
+    fn iter<'open_list>(&'open_list self) -> impl Iterator<Item = &'open_list Node> + Clone
+    where
+        Node: 'open_list,
+    {
+        self.buckets
+            .iter()
+            .flat_map(Bucket::iter)
+            .chain(self.overflow.iter())
+    }
+
+    fn drain(&mut self) -> impl Iterator<Item = Node> {
+        self.len = 0;
+        self.bucket_capacity = 0;
+        self.buckets
+            .drain(..)
+            .flat_map(Bucket::into_vec)
+            .chain(self.overflow.drain())
+    }
+
+    fn replace_comparator(&mut self, comparator: AStarNodeComparator) {
+        let nodes: Vec<_> = self.drain().collect();
+        self.comparator = comparator;
+        self.overflow.replace_cmp(comparator);
+        self.extend(nodes);
+    }
+
+    fn heap_size(&self) -> usize {
+        memory::vec_heap_size::<Bucket<Node>>(self.buckets.capacity())
+            + memory::vec_heap_size::<Node>(self.bucket_capacity + self.overflow.capacity())
+    }
+}
+
+impl<Node: AStarNode> Default for BinaryHeapOpenList<Node> {
+    fn default() -> Self {
+        Self {
+            heap: BinaryHeap::from_vec_cmp(Vec::new(), Default::default()),
+        }
+    }
+}
+
+impl<Node: AStarNode> Default for BucketQueueOpenList<Node> {
+    fn default() -> Self {
+        Self {
+            buckets: Default::default(),
+            offset: 0,
+            overflow: BinaryHeap::from_vec_cmp(Vec::new(), Default::default()),
+            comparator: Default::default(),
+            len: 0,
+            bucket_capacity: 0,
+        }
+    }
+}
+
+impl<Node: AStarNode> Extend<Node> for BinaryHeapOpenList<Node> {
+    fn extend<T: IntoIterator<Item = Node>>(&mut self, iter: T) {
+        self.heap.extend(iter);
+    }
+}
+
+impl<Node: AStarNode> Extend<Node> for BucketQueueOpenList<Node> {
+    fn extend<T: IntoIterator<Item = Node>>(&mut self, iter: T) {
+        for node in iter {
+            self.push(node);
+        }
+    }
+}
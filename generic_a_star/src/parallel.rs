@@ -0,0 +1,498 @@
+//! A parallel A* search that distributes the nodes over worker threads by the hashes of their identifiers.
+
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, Hash, Hasher},
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    },
+    thread,
+    time::Duration,
+};
+
+use binary_heap_plus::{BinaryHeap, MinComparator};
+use deterministic_default_hasher::DeterministicDefaultHasher;
+use num_traits::Bounded;
+
+use crate::{
+    AStarContext, AStarNode, AStarPerformanceCounters, AStarResult, AStarState,
+    DEADLINE_CHECK_INTERVAL, interruption, memory,
+};
+
+/// How long an idle worker waits for new nodes before checking again if the search has terminated.
+const IDLE_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// A parallel A* search in the style of hash distributed A* (HDA*).
+///
+/// Each node is owned by one worker thread, which is determined by the hash of the node's identifier.
+/// Each worker has its own context, open list and closed list, and sends the nodes it generates to the workers owning them.
+/// The contexts of the workers are created within their threads, so the context type does not need to be [`Send`].
+///
+/// Since the workers do not close nodes in a globally ascending order, nodes may be closed with a suboptimal cost.
+/// Hence, closed nodes are reopened if a cheaper path to them is found.
+/// The search terminates once no worker has an open node that may lead to a target cheaper than the cheapest target found so far,
+/// and no nodes are in transit between workers.
+#[derive(Debug)]
+pub struct ParallelAStar<Context: AStarContext> {
+    state: AStarState<<Context::Node as AStarNode>::Identifier, <Context::Node as AStarNode>::Cost>,
+    /// The context used outside of the worker threads, e.g. to create the root node.
+    context: Context,
+    workers: Vec<Worker<Context::Node>>,
+    performance_counters: AStarPerformanceCounters,
+}
+
+/// The open and closed list of a worker thread.
+#[derive(Debug)]
+struct Worker<Node: AStarNode> {
+    closed_list: HashMap<Node::Identifier, Node, DeterministicDefaultHasher>,
+    open_list: BinaryHeap<Node, MinComparator>,
+    /// The sum of the heap sizes of all nodes in the open and closed lists.
+    node_heap_size: usize,
+    performance_counters: AStarPerformanceCounters,
+}
+
+/// The state shared between the workers of a search.
+struct SharedState<Node: AStarNode> {
+    /// The cheapest target closed so far.
+    incumbent: Mutex<Option<(Node::Identifier, Node::Cost)>>,
+    /// Incremented whenever the incumbent changes, such that workers only need to lock it if it has changed.
+    incumbent_version: AtomicUsize,
+    /// The number of workers that are not idle plus the number of node batches that were sent but not yet received.
+    ///
+    /// Once this reaches zero, no worker can generate new nodes anymore.
+    active: AtomicUsize,
+    /// The result of the first worker that aborted the search, e.g. because a limit was reached.
+    interruption: Mutex<Option<AStarResult<Node::Identifier, Node::Cost>>>,
+    /// Set once the search has terminated, either regularly or because it was aborted.
+    terminated: AtomicBool,
+    applied_cost_limit: AtomicBool,
+    /// The memory usage of each worker, as measured by its last memory check.
+    memory_usages: Vec<AtomicUsize>,
+}
+
+impl<Context: AStarContext> ParallelAStar<Context>
+where
+    Context::Node: Send,
+    <Context::Node as AStarNode>::Identifier: Send,
+    <Context::Node as AStarNode>::Cost: Send,
+{
+    /// Create a parallel search with the given number of worker threads.
+    ///
+    /// The `context` is used outside of the worker threads, e.g. to create the root node and to query the limits of the search.
+    ///
+    /// **Panics** if `threads` is zero.
+    pub fn new(context: Context, threads: usize) -> Self {
+        assert!(threads > 0, "A parallel search needs at least one thread");
+
+        Self {
+            state: AStarState::Empty,
+            context,
+            workers: (0..threads)
+                .map(|_| Worker {
+                    closed_list: Default::default(),
+                    open_list: BinaryHeap::new_min(),
+                    node_heap_size: 0,
+                    performance_counters: Default::default(),
+                })
+                .collect(),
+            performance_counters: Default::default(),
+        }
+    }
+
+    pub fn state(
+        &self,
+    ) -> &AStarState<<Context::Node as AStarNode>::Identifier, <Context::Node as AStarNode>::Cost>
+    {
+        &self.state
+    }
+
+    /// Returns the number of worker threads.
+    pub fn threads(&self) -> usize {
+        self.workers.len()
+    }
+
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    pub fn into_context(self) -> Context {
+        self.context
+    }
+
+    /// Returns the performance counters summed over all workers.
+    pub fn performance_counters(&self) -> &AStarPerformanceCounters {
+        &self.performance_counters
+    }
+
+    pub fn reset(&mut self) {
+        self.state = AStarState::Empty;
+        self.context.reset();
+        for worker in &mut self.workers {
+            worker.closed_list.clear();
+            worker.open_list.clear();
+            worker.node_heap_size = 0;
+            worker.performance_counters = Default::default();
+        }
+        self.performance_counters = Default::default();
+    }
+
+    pub fn initialise(&mut self) {
+        assert_eq!(self.state, AStarState::Empty);
+
+        self.state = AStarState::Init;
+        let root = self.context.create_root();
+        let owner = worker_index(root.identifier(), self.workers.len());
+        let worker = &mut self.workers[owner];
+        worker.node_heap_size += root.heap_size();
+        worker.open_list.push(root);
+    }
+
+    /// Search for a target node with all workers in parallel.
+    ///
+    /// Each worker calls `create_context` within its thread to create its own context.
+    /// All created contexts must describe the same A* graph as the context of this search.
+    ///
+    /// Unlike [`AStar::search`](crate::AStar::search), the search cannot be continued after it terminated.
+    pub fn search(
+        &mut self,
+        create_context: impl Fn() -> Context + Sync,
+    ) -> AStarResult<<Context::Node as AStarNode>::Identifier, <Context::Node as AStarNode>::Cost>
+    {
+        assert_eq!(self.state, AStarState::Init);
+        self.state = AStarState::Searching;
+
+        let cost_limit = self
+            .context
+            .cost_limit()
+            .unwrap_or(<Context::Node as AStarNode>::Cost::max_value());
+        let memory_limit = self.context.memory_limit().unwrap_or(usize::MAX);
+
+        let threads = self.workers.len();
+        let shared = SharedState {
+            incumbent: Mutex::new(None),
+            incumbent_version: AtomicUsize::new(0),
+            active: AtomicUsize::new(threads),
+            interruption: Mutex::new(None),
+            terminated: AtomicBool::new(false),
+            applied_cost_limit: AtomicBool::new(false),
+            memory_usages: (0..threads).map(|_| AtomicUsize::new(0)).collect(),
+        };
+        let (senders, receivers): (Vec<_>, Vec<_>) = (0..threads).map(|_| mpsc::channel()).unzip();
+
+        thread::scope(|scope| {
+            for (index, (worker, receiver)) in self.workers.iter_mut().zip(receivers).enumerate() {
+                let senders = senders.clone();
+                let shared = &shared;
+                let create_context = &create_context;
+                scope.spawn(move || {
+                    worker.run(
+                        &mut create_context(),
+                        index,
+                        shared,
+                        &senders,
+                        receiver,
+                        cost_limit,
+                        memory_limit,
+                    )
+                });
+            }
+        });
+
+        self.performance_counters = Default::default();
+        for worker in &self.workers {
+            let counters = &worker.performance_counters;
+            self.performance_counters.opened_nodes += counters.opened_nodes;
+            self.performance_counters.suboptimal_opened_nodes += counters.suboptimal_opened_nodes;
+            self.performance_counters.closed_nodes += counters.closed_nodes;
+            self.performance_counters.reopened_nodes += counters.reopened_nodes;
+            // Each worker measures the memory usage of all workers.
+            self.performance_counters.peak_memory_usage = self
+                .performance_counters
+                .peak_memory_usage
+                .max(counters.peak_memory_usage);
+        }
+        self.performance_counters.peak_memory_usage = self
+            .performance_counters
+            .peak_memory_usage
+            .max(self.memory_usage());
+
+        let result = if let Some(result) = shared.interruption.into_inner().unwrap() {
+            result
+        } else if let Some((identifier, cost)) = shared.incumbent.into_inner().unwrap() {
+            AStarResult::FoundTarget {
+                identifier,
+                cost,
+                cost_lower_bound: None,
+            }
+        } else if shared.applied_cost_limit.into_inner() {
+            AStarResult::ExceededCostLimit { cost_limit }
+        } else {
+            AStarResult::NoTarget
+        };
+
+        self.state = AStarState::Terminated {
+            result: result.clone(),
+        };
+        result
+    }
+
+    /// Returns the approximate number of bytes currently used by the search.
+    ///
+    /// This includes the open and closed lists of all workers as well as the heap memory reported by the context.
+    /// The contexts of the workers only exist during the search, and are not included.
+    pub fn memory_usage(&self) -> usize {
+        self.workers.iter().map(Worker::memory_usage).sum::<usize>() + self.context.heap_size()
+    }
+
+    /// Backtrack from the target node to the root node.
+    pub fn backtrack(
+        &self,
+    ) -> impl use<'_, Context> + Iterator<Item = <Context::Node as AStarNode>::EdgeType> {
+        self.backtrack_with_costs().map(|(edge_type, _)| edge_type)
+    }
+
+    /// Backtrack from the target node to the root node.
+    ///
+    /// The elements of the iterator are a pair of an edge and the cost of the node that is reached by the edge.
+    /// The cost of the first node is never returned.
+    pub fn backtrack_with_costs(
+        &self,
+    ) -> impl use<'_, Context>
+    + Iterator<
+        Item = (
+            <Context::Node as AStarNode>::EdgeType,
+            <Context::Node as AStarNode>::Cost,
+        ),
+    > {
+        let AStarState::Terminated {
+            result: AStarResult::FoundTarget { identifier, .. },
+        } = &self.state
+        else {
+            panic!("Cannot backtrack since no target was found.")
+        };
+
+        let mut current = self.closed_node(identifier);
+        std::iter::from_fn(move || {
+            let node = current?;
+            let predecessor = node.predecessor()?;
+            let item = (node.predecessor_edge_type().unwrap(), node.cost());
+            current = Some(self.closed_node(predecessor).unwrap());
+            Some(item)
+        })
+    }
+
+    /// Returns the closed node with the given identifier from the closed list of the worker owning it.
+    pub fn closed_node(
+        &self,
+        identifier: &<Context::Node as AStarNode>::Identifier,
+    ) -> Option<&Context::Node> {
+        self.workers[worker_index(identifier, self.workers.len())]
+            .closed_list
+            .get(identifier)
+    }
+}
+
+impl<Node: AStarNode> Worker<Node> {
+    /// Expand nodes until the search terminates.
+    ///
+    /// Generated nodes owned by other workers are sent to them via `senders`, and nodes owned by this worker are received via `receiver`.
+    #[expect(clippy::too_many_arguments)]
+    fn run<Context: AStarContext<Node = Node>>(
+        &mut self,
+        context: &mut Context,
+        index: usize,
+        shared: &SharedState<Node>,
+        senders: &[Sender<Vec<Node>>],
+        receiver: Receiver<Vec<Node>>,
+        cost_limit: Node::Cost,
+        memory_limit: usize,
+    ) {
+        let threads = senders.len();
+        let mut idle = false;
+        let mut incumbent_version = None;
+        let mut incumbent_cost = None;
+        let mut iterations = 0;
+        let mut successors = Vec::new();
+        let mut outboxes: Vec<Vec<Node>> = (0..threads).map(|_| Vec::new()).collect();
+
+        loop {
+            while let Ok(nodes) = receiver.try_recv() {
+                self.receive(nodes, &mut idle, shared);
+            }
+
+            if shared.terminated.load(Ordering::Acquire) {
+                return;
+            }
+
+            let version = shared.incumbent_version.load(Ordering::Acquire);
+            if incumbent_version != Some(version) {
+                incumbent_version = Some(version);
+                incumbent_cost = shared
+                    .incumbent
+                    .lock()
+                    .unwrap()
+                    .as_ref()
+                    .map(|(_, cost)| *cost);
+            }
+            // Only nodes that may lead to a target cheaper than the incumbent need to be expanded.
+            let is_promising = |node: &Node| {
+                incumbent_cost.is_none_or(|incumbent_cost| {
+                    node.cost() + node.a_star_lower_bound() < incumbent_cost
+                })
+            };
+
+            if !self.open_list.peek().is_some_and(is_promising) {
+                if !idle {
+                    idle = true;
+                    if shared.active.fetch_sub(1, Ordering::AcqRel) == 1 {
+                        // All other workers are idle and no nodes are in transit.
+                        shared.terminated.store(true, Ordering::Release);
+                        return;
+                    }
+                }
+
+                match receiver.recv_timeout(IDLE_POLL_INTERVAL) {
+                    Ok(nodes) => self.receive(nodes, &mut idle, shared),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => {
+                        unreachable!("Each worker holds a sender to itself")
+                    }
+                }
+                continue;
+            }
+
+            let node = self.open_list.pop().unwrap();
+            self.node_heap_size -= node.heap_size();
+
+            if iterations % DEADLINE_CHECK_INTERVAL == 0 {
+                shared.memory_usages[index]
+                    .store(self.memory_usage() + context.heap_size(), Ordering::Relaxed);
+                let memory_usage = shared
+                    .memory_usages
+                    .iter()
+                    .map(|memory_usage| memory_usage.load(Ordering::Relaxed))
+                    .sum();
+                self.performance_counters.peak_memory_usage = self
+                    .performance_counters
+                    .peak_memory_usage
+                    .max(memory_usage);
+
+                if memory_usage > memory_limit {
+                    shared.interrupt(AStarResult::ExceededMemoryLimit {
+                        max_cost: node.cost(),
+                    });
+                    return;
+                }
+            }
+
+            if let Some(result) = interruption(context, &mut iterations, node.cost()) {
+                shared.interrupt(result);
+                return;
+            }
+
+            if let Some(previous_visit) = self.closed_list.get(node.identifier()) {
+                if node.cost() < previous_visit.cost() {
+                    // Nodes may have been closed with a suboptimal cost, since the workers do not close them in a globally ascending order.
+                    self.performance_counters.reopened_nodes += 1;
+                } else {
+                    self.performance_counters.suboptimal_opened_nodes += 1;
+                    continue;
+                }
+            }
+
+            if node.predecessor().is_none() {
+                // The next root is only created for roots that are expanded, such that it is sent together with the successors.
+                successors.extend(context.next_root(&node));
+            }
+            if !context.is_target(&node) {
+                context.generate_successors(&node, &mut successors);
+            }
+            for successor in successors.drain(..) {
+                if successor.cost() + successor.a_star_lower_bound() > cost_limit {
+                    shared.applied_cost_limit.store(true, Ordering::Relaxed);
+                    continue;
+                }
+                if !is_promising(&successor) {
+                    continue;
+                }
+
+                self.performance_counters.opened_nodes += 1;
+                let owner = worker_index(successor.identifier(), threads);
+                if owner == index {
+                    self.node_heap_size += successor.heap_size();
+                    self.open_list.push(successor);
+                } else {
+                    outboxes[owner].push(successor);
+                }
+            }
+
+            for (owner, outbox) in outboxes.iter_mut().enumerate() {
+                if !outbox.is_empty() {
+                    shared.active.fetch_add(1, Ordering::AcqRel);
+                    // If the owner has already returned, then the search was aborted and the nodes are not needed anymore.
+                    senders[owner].send(std::mem::take(outbox)).ok();
+                }
+            }
+
+            if context.is_target(&node) {
+                let mut incumbent = shared.incumbent.lock().unwrap();
+                if incumbent
+                    .as_ref()
+                    .is_none_or(|(_, incumbent_cost)| node.cost() < *incumbent_cost)
+                {
+                    *incumbent = Some((node.identifier().clone(), node.cost()));
+                    shared.incumbent_version.fetch_add(1, Ordering::Release);
+                }
+            }
+
+            self.node_heap_size += node.heap_size();
+            let previous_visit = self.closed_list.insert(node.identifier().clone(), node);
+            self.node_heap_size -= previous_visit.as_ref().map_or(0, AStarNode::heap_size);
+            self.performance_counters.closed_nodes += 1;
+        }
+    }
+
+    /// Insert a batch of nodes received from another worker into the open list.
+    fn receive(&mut self, nodes: Vec<Node>, idle: &mut bool, shared: &SharedState<Node>) {
+        if *idle {
+            // Become active before the batch is accounted as received, such that the active count cannot drop to zero in between.
+            shared.active.fetch_add(1, Ordering::AcqRel);
+            *idle = false;
+        }
+
+        for node in nodes {
+            self.node_heap_size += node.heap_size();
+            self.open_list.push(node);
+        }
+        shared.active.fetch_sub(1, Ordering::AcqRel);
+    }
+
+    fn memory_usage(&self) -> usize {
+        memory::vec_heap_size::<Node>(self.open_list.capacity())
+            + memory::hash_map_heap_size::<Node::Identifier, Node>(self.closed_list.capacity())
+            + self.node_heap_size
+    }
+}
+
+impl<Node: AStarNode> SharedState<Node> {
+    /// Abort the search with the given result, unless another worker has aborted it already.
+    fn interrupt(&self, result: AStarResult<Node::Identifier, Node::Cost>) {
+        self.interruption.lock().unwrap().get_or_insert(result);
+        self.terminated.store(true, Ordering::Release);
+    }
+}
+
+/// Returns the index of the worker owning the node with the given identifier.
+fn worker_index<Identifier: Hash>(identifier: &Identifier, threads: usize) -> usize {
+    if threads == 1 {
+        return 0;
+    }
+
+    let mut hasher = DeterministicDefaultHasher.build_hasher();
+    // Salt the hash, such that it is independent of the hashes used by the closed lists of the workers.
+    hasher.write_u8(0xa5);
+    identifier.hash(&mut hasher);
+    (hasher.finish() % threads as u64) as usize
+}
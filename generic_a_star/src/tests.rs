@@ -0,0 +1,303 @@
+use std::{
+    cell::Cell,
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    AStar, AStarContext, AStarNode, AStarResult, DEADLINE_CHECK_INTERVAL,
+    cancellation::CancellationToken,
+    cost::U64Cost,
+    interruption,
+    open_list::{AStarOpenList, BinaryHeapOpenList, BucketQueueOpenList},
+    reset::Reset,
+};
+
+/// A node on a path of `length` edges of cost one.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct PathNode {
+    cost: U64Cost,
+    position: usize,
+    predecessor: Option<usize>,
+}
+
+struct PathContext {
+    length: usize,
+    deadline: Option<Instant>,
+    cancellation_token: Option<CancellationToken>,
+}
+
+impl AStarNode for PathNode {
+    type Identifier = usize;
+    type EdgeType = ();
+    type Cost = U64Cost;
+
+    fn identifier(&self) -> &Self::Identifier {
+        &self.position
+    }
+
+    fn cost(&self) -> Self::Cost {
+        self.cost
+    }
+
+    fn a_star_lower_bound(&self) -> Self::Cost {
+        U64Cost::from(0u64)
+    }
+
+    fn predecessor(&self) -> Option<&Self::Identifier> {
+        self.predecessor.as_ref()
+    }
+
+    fn predecessor_edge_type(&self) -> Option<Self::EdgeType> {
+        self.predecessor.map(|_| ())
+    }
+}
+
+impl Display for PathNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.position, self.cost)
+    }
+}
+
+impl AStarContext for PathContext {
+    type Node = PathNode;
+
+    fn create_root(&self) -> Self::Node {
+        PathNode {
+            cost: U64Cost::from(0u64),
+            position: 0,
+            predecessor: None,
+        }
+    }
+
+    fn generate_successors(&mut self, node: &Self::Node, output: &mut impl Extend<Self::Node>) {
+        if node.position < self.length {
+            output.extend([PathNode {
+                cost: node.cost + U64Cost::from(1u64),
+                position: node.position + 1,
+                predecessor: Some(node.position),
+            }]);
+        }
+    }
+
+    fn is_target(&self, node: &Self::Node) -> bool {
+        node.position == self.length
+    }
+
+    fn cost_limit(&self) -> Option<<Self::Node as AStarNode>::Cost> {
+        None
+    }
+
+    fn memory_limit(&self) -> Option<usize> {
+        None
+    }
+
+    fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    fn cancellation_token(&self) -> Option<&CancellationToken> {
+        self.cancellation_token.as_ref()
+    }
+}
+
+impl Reset for PathContext {
+    fn reset(&mut self) {}
+}
+
+fn path_context(
+    deadline: Option<Instant>,
+    cancellation_token: Option<CancellationToken>,
+) -> PathContext {
+    PathContext {
+        length: 10 * DEADLINE_CHECK_INTERVAL,
+        deadline,
+        cancellation_token,
+    }
+}
+
+fn past_deadline() -> Instant {
+    Instant::now() - Duration::from_secs(1)
+}
+
+#[test]
+fn interruption_checks_cancellation_in_every_iteration() {
+    let cancellation_token = CancellationToken::new();
+    let context = path_context(None, Some(cancellation_token.clone()));
+    let max_cost = U64Cost::from(3u64);
+    let mut iterations = 0;
+
+    for _ in 0..2 * DEADLINE_CHECK_INTERVAL {
+        assert!(interruption::<_, usize>(&context, &mut iterations, max_cost).is_none());
+    }
+
+    cancellation_token.cancel();
+    assert!(matches!(
+        interruption::<_, usize>(&context, &mut iterations, max_cost),
+        Some(AStarResult::Cancelled { max_cost: cost }) if cost == max_cost
+    ));
+}
+
+#[test]
+fn interruption_checks_deadline_once_per_interval() {
+    let context = path_context(Some(past_deadline()), None);
+    let max_cost = U64Cost::from(3u64);
+
+    // The deadline is checked in the first iteration.
+    let mut iterations = 0;
+    assert!(matches!(
+        interruption::<_, usize>(&context, &mut iterations, max_cost),
+        Some(AStarResult::ExceededTimeLimit { max_cost: cost }) if cost == max_cost
+    ));
+
+    // After that, it is checked only once every interval.
+    for _ in 1..DEADLINE_CHECK_INTERVAL {
+        assert!(interruption::<_, usize>(&context, &mut iterations, max_cost).is_none());
+    }
+    assert!(matches!(
+        interruption::<_, usize>(&context, &mut iterations, max_cost),
+        Some(AStarResult::ExceededTimeLimit { .. })
+    ));
+}
+
+#[test]
+fn search_stops_at_deadline() {
+    let mut a_star = AStar::new(path_context(Some(past_deadline()), None));
+    a_star.initialise();
+    assert!(matches!(
+        a_star.search(),
+        AStarResult::ExceededTimeLimit { .. }
+    ));
+
+    let mut a_star = AStar::new(path_context(
+        Some(Instant::now() + Duration::from_secs(3600)),
+        None,
+    ));
+    a_star.initialise();
+    assert!(matches!(a_star.search(), AStarResult::FoundTarget { .. }));
+}
+
+#[test]
+fn search_stops_when_cancelled() {
+    let cancellation_token = CancellationToken::new();
+    cancellation_token.cancel();
+    let mut a_star = AStar::new(path_context(None, Some(cancellation_token)));
+    a_star.initialise();
+    assert!(matches!(a_star.search(), AStarResult::Cancelled { .. }));
+
+    let mut a_star = AStar::new(path_context(None, Some(CancellationToken::new())));
+    a_star.initialise();
+    assert!(matches!(a_star.search(), AStarResult::FoundTarget { .. }));
+}
+
+thread_local! {
+    static COMPARISONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A node that counts how often nodes are compared, ordered by its cost and then by `tie_break`.
+#[derive(Debug, PartialEq, Eq)]
+struct CountingNode {
+    cost: U64Cost,
+    tie_break: usize,
+}
+
+impl AStarNode for CountingNode {
+    type Identifier = usize;
+    type EdgeType = ();
+    type Cost = U64Cost;
+
+    fn identifier(&self) -> &Self::Identifier {
+        &self.tie_break
+    }
+
+    fn cost(&self) -> Self::Cost {
+        self.cost
+    }
+
+    fn a_star_lower_bound(&self) -> Self::Cost {
+        U64Cost::from(0u64)
+    }
+
+    fn predecessor(&self) -> Option<&Self::Identifier> {
+        None
+    }
+
+    fn predecessor_edge_type(&self) -> Option<Self::EdgeType> {
+        None
+    }
+}
+
+impl Ord for CountingNode {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        COMPARISONS.with(|comparisons| comparisons.set(comparisons.get() + 1));
+        self.cost
+            .cmp(&other.cost)
+            .then_with(|| self.tie_break.cmp(&other.tie_break))
+    }
+}
+
+impl PartialOrd for CountingNode {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for CountingNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.tie_break, self.cost)
+    }
+}
+
+/// Pushes the nodes into the open list and pops them all again.
+///
+/// Returns the popped nodes and the number of comparisons made.
+fn push_and_pop<OpenList: AStarOpenList<CountingNode>>(
+    nodes: impl IntoIterator<Item = CountingNode>,
+) -> (Vec<CountingNode>, usize) {
+    let mut open_list = OpenList::default();
+    COMPARISONS.with(|comparisons| comparisons.set(0));
+    open_list.extend(nodes);
+    let popped = std::iter::from_fn(|| open_list.pop()).collect();
+    (popped, COMPARISONS.with(Cell::get))
+}
+
+#[test]
+fn bucket_queue_pops_equal_nodes_in_constant_time() {
+    const NODES: usize = 1024;
+    let nodes = || {
+        (0..NODES).map(|_| CountingNode {
+            cost: U64Cost::from(5u64),
+            tie_break: 0,
+        })
+    };
+
+    let (popped, bucket_queue_comparisons) = push_and_pop::<BucketQueueOpenList<_>>(nodes());
+    assert_eq!(popped.len(), NODES);
+    // Each pushed node except the first is compared once to the last node of its bucket.
+    assert_eq!(bucket_queue_comparisons, NODES - 1);
+
+    let (popped, binary_heap_comparisons) = push_and_pop::<BinaryHeapOpenList<_>>(nodes());
+    assert_eq!(popped.len(), NODES);
+    // Popping from a binary heap sifts down through all levels of the heap.
+    assert!(binary_heap_comparisons > 5 * bucket_queue_comparisons);
+}
+
+#[test]
+fn bucket_queue_breaks_ties_like_binary_heap() {
+    const NODES: usize = 100;
+    // A deterministic permutation of the nodes, with a few different costs and many ties.
+    let nodes = || {
+        (0..NODES).map(|index| {
+            let index = index * 37 % NODES;
+            CountingNode {
+                cost: U64Cost::from((index % 3) as u64),
+                tie_break: index,
+            }
+        })
+    };
+
+    let (bucket_queue, _) = push_and_pop::<BucketQueueOpenList<_>>(nodes());
+    let (binary_heap, _) = push_and_pop::<BinaryHeapOpenList<_>>(nodes());
+    assert_eq!(bucket_queue, binary_heap);
+    assert!(bucket_queue.is_sorted());
+}
@@ -0,0 +1,288 @@
+//! Verifying the A* lower bounds of a search.
+//!
+//! An admissible lower bound never overestimates the cost to reach a target, and a consistent lower bound decreases along an edge by at most the cost of the edge.
+//! A search with an inadmissible lower bound may return suboptimal targets, and a search with an inconsistent lower bound may close nodes with a suboptimal cost.
+//! Both usually go unnoticed, so the [`HeuristicChecker`] can be used to detect them.
+
+use std::{collections::HashMap, fmt::Display, ops::ControlFlow};
+
+use deterministic_default_hasher::DeterministicDefaultHasher;
+use num_traits::{Bounded, CheckedAdd};
+
+use crate::{AStarNode, observer::AStarObserver};
+
+/// The maximum number of violations per kind that are listed when displaying a [`HeuristicReport`].
+const DISPLAYED_VIOLATIONS: usize = 10;
+
+/// An [observer](AStarObserver) that checks the A* lower bounds of a search for admissibility and consistency.
+///
+/// It stores the identifier, cost, lower bound and predecessor of each closed node, so it roughly doubles the memory usage of the search.
+/// The violations found are collected in a [`HeuristicReport`].
+///
+/// Consistency is checked for every opened edge, but admissibility is only checked for the closed nodes on the path to a found target,
+/// since only for those the cost of the target reached from them is known.
+/// An overestimating lower bound of a node off that path goes unnoticed.
+#[derive(Debug)]
+pub struct HeuristicChecker<Node: AStarNode> {
+    closed_nodes: HashMap<Node::Identifier, CheckedNode<Node>, DeterministicDefaultHasher>,
+    report: HeuristicReport<Node::Identifier, Node::Cost>,
+}
+
+#[derive(Debug)]
+struct CheckedNode<Node: AStarNode> {
+    cost: Node::Cost,
+    a_star_lower_bound: Node::Cost,
+    predecessor: Option<Node::Identifier>,
+}
+
+/// The violations of admissibility and consistency found by a [`HeuristicChecker`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HeuristicReport<Identifier, Cost> {
+    /// Nodes that were reached with a lower cost after they were closed.
+    ///
+    /// This happens only if the search is weighted, or if the lower bound is inconsistent or differs between nodes with the same identifier.
+    pub reopened_nodes: Vec<ReopenedNode<Identifier, Cost>>,
+
+    /// Edges along which the lower bound decreases by more than the cost of the edge.
+    pub inconsistent_edges: Vec<InconsistentEdge<Identifier, Cost>>,
+
+    /// Nodes on the path to a target whose cost plus lower bound exceeds the cost of the target.
+    pub overestimating_nodes: Vec<OverestimatingNode<Identifier, Cost>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReopenedNode<Identifier, Cost> {
+    pub identifier: Identifier,
+    /// The cost with which the node was closed.
+    pub closed_cost: Cost,
+    /// The lower cost with which the node was reached later.
+    pub cost: Cost,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InconsistentEdge<Identifier, Cost> {
+    pub predecessor: Identifier,
+    pub successor: Identifier,
+    /// The cost plus lower bound of the predecessor.
+    pub predecessor_estimate: Cost,
+    /// The cost plus lower bound of the successor, which is lower than that of the predecessor.
+    pub successor_estimate: Cost,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OverestimatingNode<Identifier, Cost> {
+    pub identifier: Identifier,
+    pub cost: Cost,
+    pub a_star_lower_bound: Cost,
+    /// The cost of the target that is reached via this node.
+    pub target_cost: Cost,
+}
+
+impl<Node: AStarNode> HeuristicChecker<Node> {
+    pub fn new() -> Self {
+        Self {
+            closed_nodes: Default::default(),
+            report: Default::default(),
+        }
+    }
+
+    pub fn report(&self) -> &HeuristicReport<Node::Identifier, Node::Cost> {
+        &self.report
+    }
+
+    pub fn into_report(self) -> HeuristicReport<Node::Identifier, Node::Cost> {
+        self.report
+    }
+}
+
+impl<Identifier, Cost> HeuristicReport<Identifier, Cost> {
+    /// Returns true if no node was reopened and no inconsistent edge was found.
+    pub fn is_consistent(&self) -> bool {
+        self.reopened_nodes.is_empty() && self.inconsistent_edges.is_empty()
+    }
+
+    /// Returns true if no node on the path to a target overestimated the cost of the target.
+    pub fn is_admissible(&self) -> bool {
+        self.overestimating_nodes.is_empty()
+    }
+}
+
+/// Returns the cost plus the lower bound, saturating at the maximum cost.
+fn estimate<Cost: CheckedAdd + Bounded>(cost: Cost, a_star_lower_bound: Cost) -> Cost {
+    cost.checked_add(&a_star_lower_bound)
+        .unwrap_or_else(Cost::max_value)
+}
+
+impl<Node: AStarNode> AStarObserver<Node> for HeuristicChecker<Node> {
+    fn on_node_opened(&mut self, node: &Node) {
+        let Some((predecessor, checked_predecessor)) = node
+            .predecessor()
+            .and_then(|predecessor| Some((predecessor, self.closed_nodes.get(predecessor)?)))
+        else {
+            return;
+        };
+
+        let predecessor_estimate = estimate(
+            checked_predecessor.cost,
+            checked_predecessor.a_star_lower_bound,
+        );
+        let successor_estimate = estimate(node.cost(), node.a_star_lower_bound());
+        if successor_estimate < predecessor_estimate {
+            self.report.inconsistent_edges.push(InconsistentEdge {
+                predecessor: predecessor.clone(),
+                successor: node.identifier().clone(),
+                predecessor_estimate,
+                successor_estimate,
+            });
+        }
+    }
+
+    fn on_node_closed(&mut self, node: &Node) -> ControlFlow<()> {
+        let previous_visit = self.closed_nodes.insert(
+            node.identifier().clone(),
+            CheckedNode {
+                cost: node.cost(),
+                a_star_lower_bound: node.a_star_lower_bound(),
+                predecessor: node.predecessor().cloned(),
+            },
+        );
+
+        if let Some(previous_visit) = previous_visit {
+            self.report.reopened_nodes.push(ReopenedNode {
+                identifier: node.identifier().clone(),
+                closed_cost: previous_visit.cost,
+                cost: node.cost(),
+            });
+        }
+
+        ControlFlow::Continue(())
+    }
+
+    fn on_suboptimal_node(&mut self, node: &Node) {
+        let Some(closed_cost) = self
+            .closed_nodes
+            .get(node.identifier())
+            .map(|checked_node| checked_node.cost)
+        else {
+            return;
+        };
+
+        if node.cost() < closed_cost {
+            self.report.reopened_nodes.push(ReopenedNode {
+                identifier: node.identifier().clone(),
+                closed_cost,
+                cost: node.cost(),
+            });
+        }
+    }
+
+    fn on_target_found(&mut self, node: &Node) {
+        let target_cost = node.cost();
+        let mut identifier = Some(node.identifier());
+
+        while let Some((current, checked_node)) =
+            identifier.and_then(|identifier| Some((identifier, self.closed_nodes.get(identifier)?)))
+        {
+            if estimate(checked_node.cost, checked_node.a_star_lower_bound) > target_cost {
+                self.report.overestimating_nodes.push(OverestimatingNode {
+                    identifier: current.clone(),
+                    cost: checked_node.cost,
+                    a_star_lower_bound: checked_node.a_star_lower_bound,
+                    target_cost,
+                });
+            }
+            identifier = checked_node.predecessor.as_ref();
+        }
+    }
+}
+
+impl<Node: AStarNode> Default for HeuristicChecker<Node> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Identifier, Cost> Default for HeuristicReport<Identifier, Cost> {
+    fn default() -> Self {
+        Self {
+            reopened_nodes: Default::default(),
+            inconsistent_edges: Default::default(),
+            overestimating_nodes: Default::default(),
+        }
+    }
+}
+
+impl<Identifier: Display, Cost: Display> Display for HeuristicReport<Identifier, Cost> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "The lower bound is {}admissible and {}consistent.",
+            if self.is_admissible() { "" } else { "NOT " },
+            if self.is_consistent() { "" } else { "NOT " },
+        )?;
+
+        writeln!(f, "Reopened nodes: {}", self.reopened_nodes.len())?;
+        for ReopenedNode {
+            identifier,
+            closed_cost,
+            cost,
+        } in self.reopened_nodes.iter().take(DISPLAYED_VIOLATIONS)
+        {
+            writeln!(
+                f,
+                "    {identifier}: closed with {closed_cost}, reached with {cost}"
+            )?;
+        }
+
+        write_omitted(f, self.reopened_nodes.len())?;
+
+        writeln!(f, "Inconsistent edges: {}", self.inconsistent_edges.len())?;
+        for InconsistentEdge {
+            predecessor,
+            successor,
+            predecessor_estimate,
+            successor_estimate,
+        } in self.inconsistent_edges.iter().take(DISPLAYED_VIOLATIONS)
+        {
+            writeln!(
+                f,
+                "    {predecessor} ({predecessor_estimate}) -> {successor} ({successor_estimate})"
+            )?;
+        }
+
+        write_omitted(f, self.inconsistent_edges.len())?;
+
+        writeln!(
+            f,
+            "Overestimating nodes: {}",
+            self.overestimating_nodes.len()
+        )?;
+        for OverestimatingNode {
+            identifier,
+            cost,
+            a_star_lower_bound,
+            target_cost,
+        } in self.overestimating_nodes.iter().take(DISPLAYED_VIOLATIONS)
+        {
+            writeln!(
+                f,
+                "    {identifier}: cost {cost} + lower bound {a_star_lower_bound} > target cost {target_cost}"
+            )?;
+        }
+        write_omitted(f, self.overestimating_nodes.len())?;
+
+        Ok(())
+    }
+}
+
+/// Writes a line noting how many violations were not displayed, if any.
+fn write_omitted(f: &mut std::fmt::Formatter<'_>, violations: usize) -> std::fmt::Result {
+    if violations > DISPLAYED_VIOLATIONS {
+        writeln!(f, "    ... and {} more", violations - DISPLAYED_VIOLATIONS)?;
+    }
+    Ok(())
+}
@@ -1,15 +1,28 @@
-use std::{fmt::Debug, time::Instant};
+use std::{
+    fmt::{Debug, Display},
+    time::Instant,
+};
 
-use alignment_geometry::AlignmentRange;
+use alignment_geometry::{AlignmentMode, AlignmentRange};
+use alignment_options::AlignmentOptions;
 use alignment_result::{AlignmentResult, IAlignmentType};
 use compact_genome::interface::{alphabet::Alphabet, sequence::GenomeSequence};
-use generic_a_star::{AStar, AStarContext, AStarNode, AStarResult, cost::AStarCost};
+use generic_a_star::{
+    AStar, AStarContext, AStarNode, AStarPerformanceCounters, AStarResult,
+    bidirectional::{BidirectionalAStar, BidirectionalAStarContext},
+    cost::AStarCost,
+    observer::AStarObserver,
+    parallel::ParallelAStar,
+};
 use log::info;
+use num_traits::{Bounded, CheckedAdd, Zero};
 use template_switch_distance::{
     context::Memory,
     strategies::{
-        AlignmentStrategySelector, chaining::ChainingStrategy,
-        primary_match::AllowPrimaryMatchStrategy, shortcut::NoShortcutStrategy,
+        AlignmentStrategySelector,
+        chaining::{ChainingStrategy, SeedingMethod},
+        primary_match::AllowPrimaryMatchStrategy,
+        shortcut::NoShortcutStrategy,
         template_switch_count::TemplateSwitchCountStrategy,
     },
 };
@@ -17,8 +30,16 @@ use traitsequence::interface::Sequence;
 
 use crate::config;
 
+pub use generic_a_star::{
+    closed_list::ClosedListMode,
+    verification::{HeuristicChecker, HeuristicReport},
+};
+
 pub mod alignment_geometry;
+pub mod alignment_options;
 pub mod alignment_result;
+#[cfg(feature = "serde")]
+mod checkpoint;
 pub mod gap_affine_edit_distance;
 pub mod template_switch_distance;
 #[cfg(test)]
@@ -42,10 +63,23 @@ pub trait AlignmentContext: AStarContext {
     fn query_name(&self) -> &str;
 
     fn range(&self) -> &AlignmentRange;
+
+    /// Returns the reference and query indices up to which a path ending in the node with this identifier aligns the sequences.
+    ///
+    /// Returns `None` if an alignment cannot end in this node, e.g. because it is inside a template switch.
+    /// This is used to select the node from which a partial alignment is backtracked if the search was aborted.
+    fn partial_alignment_end(
+        &self,
+        identifier: &<Self::Node as AStarNode>::Identifier,
+    ) -> Option<(usize, usize)>;
 }
 
 fn a_star_align<Context: AStarContext + AlignmentContext>(
     context: Context,
+    epsilon: Option<f64>,
+    initial_cost_limit: Option<<Context::Node as AStarNode>::Cost>,
+    closed_list_mode: ClosedListMode,
+    observer: impl AStarObserver<Context::Node>,
 ) -> AlignmentResult<Context::AlignmentType, <<Context as AStarContext>::Node as AStarNode>::Cost>
 where
     <Context::Node as AStarNode>::EdgeType: IAlignmentType,
@@ -55,74 +89,392 @@ where
     let start_time = Instant::now();
 
     // Perform forwards search.
+    let mut a_star = AStar::new_with_observer(context, observer);
+    a_star.set_closed_list_mode(closed_list_mode);
+    a_star.initialise();
+    let result = if let Some(epsilon) = epsilon {
+        anytime_search(&mut a_star, epsilon)
+    } else if let Some(initial_cost_limit) = initial_cost_limit {
+        cost_limit_doubling_search(&mut a_star, initial_cost_limit)
+    } else {
+        a_star.search()
+    };
+    let has_target = matches!(result, AStarResult::FoundTarget { .. });
+
+    let alignment = has_target.then(|| {
+        let AStarResult::FoundTarget { identifier, .. } = &result else {
+            unreachable!()
+        };
+        collect_alignment_with_root::<Context, _>(&a_star, identifier)
+    });
+    let partial_alignment = (!has_target && !matches!(result, AStarResult::NoTarget))
+        .then(|| backtrack_partial_alignment(&a_star))
+        .flatten();
+
+    let end_time = Instant::now();
+    let duration = (end_time - start_time).as_secs_f64();
+
+    if let Some((alignment, partial_cost, (reference_end, query_end))) = partial_alignment {
+        return create_alignment_result(
+            a_star.context(),
+            Some(alignment),
+            result,
+            duration,
+            a_star.performance_counters(),
+        )
+        .into_partial(partial_cost, reference_end, query_end);
+    }
+
+    create_alignment_result(
+        a_star.context(),
+        alignment,
+        result,
+        duration,
+        a_star.performance_counters(),
+    )
+}
+
+/// Backtrack a partial alignment from the closed node that got furthest along the anti-diagonal of the alignment matrix.
+///
+/// Of multiple such nodes, the one with the lowest cost plus lower bound is selected.
+/// Returns the alignment, its cost and its end coordinates, or `None` if no node qualifies as the end of an alignment.
+#[allow(clippy::type_complexity)]
+fn backtrack_partial_alignment<
+    Context: AlignmentContext,
+    Observer: AStarObserver<Context::Node>,
+>(
+    a_star: &AStar<Context, Observer>,
+) -> Option<(
+    Vec<(usize, Context::AlignmentType)>,
+    <Context::Node as AStarNode>::Cost,
+    (usize, usize),
+)> {
+    let context = a_star.context();
+    let (identifier, closed_node, end) = a_star
+        .closed_nodes()
+        .filter_map(|(identifier, closed_node)| {
+            Some((
+                identifier,
+                closed_node,
+                context.partial_alignment_end(identifier)?,
+            ))
+        })
+        .min_by_key(|(_, closed_node, (reference_end, query_end))| {
+            // Compactly stored nodes lack the lower bound, so they are only compared by their cost.
+            let lower_bound = closed_node
+                .node()
+                .map(AStarNode::a_star_lower_bound)
+                .unwrap_or_else(<Context::Node as AStarNode>::Cost::zero);
+            (
+                std::cmp::Reverse(reference_end + query_end),
+                closed_node.cost() + lower_bound,
+            )
+        })?;
+
+    info!(
+        "Backtracking partial alignment ending at reference index {} and query index {}",
+        end.0, end.1
+    );
+    let alignment = collect_alignment_with_root::<Context, _>(a_star, identifier);
+    Some((alignment, closed_node.cost(), end))
+}
+
+/// Find the `k` cheapest alignments, or all alignments whose cost exceeds the optimum by at most `max_cost_delta`.
+///
+/// The alignments are ordered by ascending cost.
+/// If no alignment is found, then the result contains a single alignment result without target.
+fn k_best_a_star_align<Context: AStarContext + AlignmentContext>(
+    context: Context,
+    k: usize,
+    max_cost_delta: Option<<Context::Node as AStarNode>::Cost>,
+) -> Vec<
+    AlignmentResult<Context::AlignmentType, <<Context as AStarContext>::Node as AStarNode>::Cost>,
+>
+where
+    <Context::Node as AStarNode>::EdgeType: IAlignmentType,
+    Context::AlignmentType: Display,
+{
+    info!(
+        "Aligning {k} best alignments on subsequence {}",
+        context.range()
+    );
+
+    let start_time = Instant::now();
+
     let mut a_star = AStar::new(context);
     a_star.initialise();
+    // Paths that differ only in internal states of the graph result in the same alignment.
+    let paths = a_star.search_k_best_distinct(k, max_cost_delta, |edge_type| {
+        let alignment_type = Context::AlignmentType::from(edge_type);
+        (!alignment_type.is_internal()).then(|| alignment_type.to_string())
+    });
+
+    let end_time = Instant::now();
+    let duration = (end_time - start_time).as_secs_f64();
+
+    if paths.is_empty() {
+        return vec![create_alignment_result(
+            a_star.context(),
+            None,
+            paths.result().clone(),
+            duration,
+            a_star.performance_counters(),
+        )];
+    }
+
+    (0..paths.len())
+        .map(|index| {
+            create_alignment_result(
+                a_star.context(),
+                Some(collect_alignment::<Context>(paths.backtrack(index))),
+                paths.path_result(index),
+                duration,
+                a_star.performance_counters(),
+            )
+        })
+        .collect()
+}
+
+/// Search with the lower bounds weighted by `epsilon`, and then improve the result until it is optimal or a limit is reached.
+fn anytime_search<Context: AStarContext, Observer: AStarObserver<Context::Node>>(
+    a_star: &mut AStar<Context, Observer>,
+    mut epsilon: f64,
+) -> AStarResult<<Context::Node as AStarNode>::Identifier, <Context::Node as AStarNode>::Cost> {
+    let mut result = a_star.search_weighted(epsilon);
+    info!("{result} (epsilon: {epsilon})");
+
+    while epsilon > 1.0
+        && matches!(result, AStarResult::FoundTarget { .. })
+        && result.suboptimality_bound() > 1.0
+    {
+        // Halve the excess weight, but do not bother with weights very close to one.
+        epsilon = 1.0 + (epsilon.min(result.suboptimality_bound()) - 1.0) / 2.0;
+        if epsilon < 1.01 {
+            epsilon = 1.0;
+        }
+
+        result = a_star.search_weighted(epsilon);
+        info!("{result} (epsilon: {epsilon})");
+    }
+
+    result
+}
+
+/// Search with `cost_limit`, and double it until a target is found or the cost limit of the context is reached.
+///
+/// Nodes pruned by the cost limit are kept, so raising it continues the search instead of restarting it.
+fn cost_limit_doubling_search<Context: AStarContext, Observer: AStarObserver<Context::Node>>(
+    a_star: &mut AStar<Context, Observer>,
+    mut cost_limit: <Context::Node as AStarNode>::Cost,
+) -> AStarResult<<Context::Node as AStarNode>::Identifier, <Context::Node as AStarNode>::Cost> {
+    let max_cost_limit = a_star
+        .context()
+        .cost_limit()
+        .unwrap_or(<Context::Node as AStarNode>::Cost::max_value());
+
+    loop {
+        cost_limit = cost_limit.min(max_cost_limit);
+        a_star.set_cost_limit(cost_limit);
+        let result = a_star.search();
+        info!("{result}");
+
+        if !matches!(result, AStarResult::ExceededCostLimit { .. }) || cost_limit == max_cost_limit
+        {
+            return result;
+        }
+
+        // Doubling zero would not make progress.
+        let increment = cost_limit.max(<Context::Node as AStarNode>::Cost::from(1u8));
+        cost_limit = cost_limit.checked_add(&increment).unwrap_or(max_cost_limit);
+    }
+}
+
+fn bidirectional_a_star_align<Context: BidirectionalAStarContext + AlignmentContext>(
+    context: Context,
+) -> crate::error::Result<
+    AlignmentResult<Context::AlignmentType, <<Context as AStarContext>::Node as AStarNode>::Cost>,
+>
+where
+    <Context::Node as AStarNode>::EdgeType: IAlignmentType,
+{
+    info!(
+        "Aligning bidirectionally on subsequence {}",
+        context.range()
+    );
+
+    let start_time = Instant::now();
+
+    // Perform bidirectional search.
+    let mut a_star = BidirectionalAStar::new(context);
+    a_star
+        .initialise()
+        .map_err(|_| crate::error::Error::BidirectionalWithNegativeCosts)?;
     let result = a_star.search();
     let has_target = matches!(result, AStarResult::FoundTarget { .. });
 
+    let alignment = has_target.then(|| collect_alignment::<Context>(a_star.backtrack()));
+
+    let end_time = Instant::now();
+    let duration = (end_time - start_time).as_secs_f64();
+
+    Ok(create_alignment_result(
+        a_star.context(),
+        alignment,
+        result,
+        duration,
+        a_star.performance_counters(),
+    ))
+}
+
+/// Align with `threads` worker threads in parallel.
+///
+/// Each worker thread creates its own context with `create_context`, which must be equivalent to `context`.
+fn parallel_a_star_align<Context: AStarContext + AlignmentContext>(
+    context: Context,
+    threads: usize,
+    create_context: impl Fn() -> Context + Sync,
+) -> AlignmentResult<Context::AlignmentType, <<Context as AStarContext>::Node as AStarNode>::Cost>
+where
+    Context::Node: Send,
+    <Context::Node as AStarNode>::Identifier: Send,
+    <Context::Node as AStarNode>::Cost: Send,
+    <Context::Node as AStarNode>::EdgeType: IAlignmentType,
+{
+    info!(
+        "Aligning with {threads} threads on subsequence {}",
+        context.range()
+    );
+
+    let start_time = Instant::now();
+
+    let mut a_star = ParallelAStar::new(context, threads);
+    a_star.initialise();
+    let result = a_star.search(create_context);
+    let has_target = matches!(result, AStarResult::FoundTarget { .. });
+
+    let alignment = has_target.then(|| collect_alignment::<Context>(a_star.backtrack()));
+
+    let end_time = Instant::now();
+    let duration = (end_time - start_time).as_secs_f64();
+
+    create_alignment_result(
+        a_star.context(),
+        alignment,
+        result,
+        duration,
+        a_star.performance_counters(),
+    )
+}
+
+/// Collect the edges of a backtracked path into a run-length encoded alignment.
+///
+/// The edges are expected in backtracking order, i.e. from the target to the root.
+fn collect_alignment<Context: AlignmentContext>(
+    backtrack: impl Iterator<Item = <Context::Node as AStarNode>::EdgeType>,
+) -> Vec<(usize, Context::AlignmentType)> {
     let mut alignment = Vec::new();
 
-    if has_target {
-        // Backtrack.
-        for alignment_type in a_star
-            .backtrack()
-            .map(<Context as AlignmentContext>::AlignmentType::from)
-        {
-            if !alignment_type.is_internal() {
-                if let Some((count, previous_alignment_type)) = alignment.last_mut() {
-                    if alignment_type.is_repeated(previous_alignment_type) {
-                        *count += 1;
-                    } else {
-                        alignment.push((1, alignment_type));
-                    }
+    for alignment_type in backtrack.map(<Context as AlignmentContext>::AlignmentType::from) {
+        if !alignment_type.is_internal() {
+            if let Some((count, previous_alignment_type)) = alignment.last_mut() {
+                if alignment_type.is_repeated(previous_alignment_type) {
+                    *count += 1;
                 } else {
                     alignment.push((1, alignment_type));
                 }
+            } else {
+                alignment.push((1, alignment_type));
             }
         }
-
-        alignment.reverse();
     }
 
-    let end_time = Instant::now();
-    let duration = (end_time - start_time).as_secs_f64();
+    alignment.reverse();
+    alignment
+}
 
-    if has_target {
+/// Collect the edges of the path to the node with the given identifier into a run-length encoded alignment, including the edge type of its root.
+///
+/// With [multiple roots](AStarContext::next_root), the edge type of a root tells what was skipped to reach it, e.g. the characters clipped before a local alignment.
+fn collect_alignment_with_root<
+    Context: AlignmentContext,
+    Observer: AStarObserver<Context::Node>,
+>(
+    a_star: &AStar<Context, Observer>,
+    identifier: &<Context::Node as AStarNode>::Identifier,
+) -> Vec<(usize, Context::AlignmentType)> {
+    let root_edge_type = a_star
+        .backtrack_root_from(identifier)
+        .unwrap()
+        .predecessor_edge_type();
+    collect_alignment::<Context>(
+        a_star
+            .backtrack_from(identifier)
+            .unwrap()
+            .chain(root_edge_type),
+    )
+}
+
+fn create_alignment_result<Context: AlignmentContext>(
+    context: &Context,
+    alignment: Option<Vec<(usize, Context::AlignmentType)>>,
+    result: AStarResult<
+        <Context::Node as AStarNode>::Identifier,
+        <Context::Node as AStarNode>::Cost,
+    >,
+    duration: f64,
+    performance_counters: &AStarPerformanceCounters,
+) -> AlignmentResult<Context::AlignmentType, <<Context as AStarContext>::Node as AStarNode>::Cost> {
+    if let Some(alignment) = alignment {
         AlignmentResult::new_with_target(
             alignment,
-            a_star.context().reference(),
-            a_star.context().query(),
-            a_star.context().reference_name(),
-            a_star.context().query_name(),
-            a_star.context().range().reference_offset(),
-            a_star.context().range().query_offset(),
+            context.reference(),
+            context.query(),
+            context.reference_name(),
+            context.query_name(),
+            context.range().reference_offset(),
+            context.range().query_offset(),
             result.without_node_identifier(),
             duration,
-            a_star.performance_counters().opened_nodes,
-            a_star.performance_counters().closed_nodes,
-            a_star.performance_counters().suboptimal_opened_nodes,
-            a_star.context().reference().len(),
-            a_star.context().query().len(),
+            performance_counters.opened_nodes,
+            performance_counters.closed_nodes,
+            performance_counters.suboptimal_opened_nodes,
+            performance_counters.peak_memory_usage,
+            context.reference().len(),
+            context.query().len(),
         )
     } else {
         AlignmentResult::new_without_target(
             result.without_node_identifier(),
-            a_star.context().reference(),
-            a_star.context().query(),
-            a_star.context().reference_name(),
-            a_star.context().query_name(),
-            a_star.context().range().reference_offset(),
-            a_star.context().range().query_offset(),
+            context.reference(),
+            context.query(),
+            context.reference_name(),
+            context.query_name(),
+            context.range().reference_offset(),
+            context.range().query_offset(),
             duration,
-            a_star.performance_counters().opened_nodes,
-            a_star.performance_counters().closed_nodes,
-            a_star.performance_counters().suboptimal_opened_nodes,
-            a_star.context().reference().len(),
-            a_star.context().query().len(),
+            performance_counters.opened_nodes,
+            performance_counters.closed_nodes,
+            performance_counters.suboptimal_opened_nodes,
+            performance_counters.peak_memory_usage,
+            context.reference().len(),
+            context.query().len(),
         )
     }
 }
 
+/// Returns an error if the [mode](AlignmentMode::may_clip_everything) allows to clip everything,
+/// but no alignment step has a negative cost, since then the optimal alignment would be empty.
+fn check_alignment_mode(mode: AlignmentMode, has_negative_costs: bool) -> crate::error::Result<()> {
+    if mode.may_clip_everything() && !has_negative_costs {
+        Err(crate::error::Error::ClippingWithoutNegativeCosts { mode })
+    } else {
+        Ok(())
+    }
+}
+
+/// Align `query` against `reference` with gap-affine costs.
+///
+/// Returns an error if the [mode](AlignmentMode::may_clip_everything) allows to clip everything, but the scoring table has no negative costs.
 pub fn gap_affine_edit_distance_a_star_align<
     AlphabetType: Alphabet,
     Cost: AStarCost,
@@ -131,15 +483,74 @@ pub fn gap_affine_edit_distance_a_star_align<
     reference: &SubsequenceType,
     query: &SubsequenceType,
     scoring_table: gap_affine_edit_distance::ScoringTable<Cost>,
-) -> AlignmentResult<gap_affine_edit_distance::AlignmentType, Cost> {
-    a_star_align(gap_affine_edit_distance::Context::new(
+    mode: AlignmentMode,
+) -> crate::error::Result<AlignmentResult<gap_affine_edit_distance::AlignmentType, Cost>> {
+    let context = gap_affine_edit_distance::Context::new(reference, query, scoring_table, mode);
+    check_alignment_mode(mode, context.has_negative_costs())?;
+
+    Ok(a_star_align(context, None, None, ClosedListMode::Full, ()))
+}
+
+/// Like [`gap_affine_edit_distance_a_star_align`], but searches bidirectionally.
+///
+/// Returns an error if the scoring table has negative costs.
+///
+/// Template switch alignments cannot be searched bidirectionally,
+/// because their strategies carry state from the root along the path, such as the number of template switches,
+/// which is unknown when searching backwards from the targets.
+/// Their chaining strategy still uses the bidirectional search to compute the chain of anchors.
+pub fn gap_affine_edit_distance_bidirectional_a_star_align<
+    AlphabetType: Alphabet,
+    Cost: AStarCost,
+    SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized,
+>(
+    reference: &SubsequenceType,
+    query: &SubsequenceType,
+    scoring_table: gap_affine_edit_distance::ScoringTable<Cost>,
+) -> crate::error::Result<AlignmentResult<gap_affine_edit_distance::AlignmentType, Cost>> {
+    bidirectional_a_star_align(gap_affine_edit_distance::Context::new(
         reference,
         query,
         scoring_table,
+        AlignmentMode::Global,
     ))
 }
 
-#[expect(clippy::too_many_arguments)]
+/// Like [`gap_affine_edit_distance_a_star_align`], but returns the `k` cheapest alignments ordered by ascending cost.
+///
+/// If `max_cost_delta` is given, then only alignments whose cost exceeds the optimum by at most `max_cost_delta` are returned.
+pub fn gap_affine_edit_distance_k_best_a_star_align<
+    AlphabetType: Alphabet,
+    Cost: AStarCost,
+    SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized,
+>(
+    reference: &SubsequenceType,
+    query: &SubsequenceType,
+    scoring_table: gap_affine_edit_distance::ScoringTable<Cost>,
+    k: usize,
+    max_cost_delta: Option<Cost>,
+) -> Vec<AlignmentResult<gap_affine_edit_distance::AlignmentType, Cost>> {
+    k_best_a_star_align(
+        gap_affine_edit_distance::Context::new(
+            reference,
+            query,
+            scoring_table,
+            AlignmentMode::Global,
+        ),
+        k,
+        max_cost_delta,
+    )
+}
+
+/// Align `query` against `reference` with template switches.
+///
+/// The names, range, mode, limits and search parameters of the alignment are given by `options`.
+///
+/// Returns an error if the [mode](AlignmentMode::may_clip_everything) allows to clip everything, but the config has no negative costs,
+/// or if an epsilon is given, but the config has negative costs.
+///
+/// The events of the A* search are reported to `observer`.
+/// Pass `()` to ignore them, or `&mut observer` to inspect the observer after the alignment.
 pub fn template_switch_distance_a_star_align<
     Strategies: AlignmentStrategySelector<
             Shortcut = NoShortcutStrategy<<Strategies as AlignmentStrategySelector>::Cost>,
@@ -149,39 +560,238 @@ pub fn template_switch_distance_a_star_align<
 >(
     reference: &SubsequenceType,
     query: &SubsequenceType,
-    reference_name: &str,
-    query_name: &str,
-    range: Option<AlignmentRange>,
     config: config::TemplateSwitchConfig<
         Strategies::Alphabet,
         <Strategies as AlignmentStrategySelector>::Cost,
     >,
-    cost_limit: Option<Strategies::Cost>,
-    memory_limit: Option<usize>,
+    options: &AlignmentOptions<Strategies::Cost>,
     template_switch_count_memory: <Strategies::TemplateSwitchCount as TemplateSwitchCountStrategy>::Memory,
-) -> AlignmentResult<template_switch_distance::AlignmentType, Strategies::Cost> {
-    let memory = Memory {
-        template_switch_min_length: Default::default(),
-        chaining: <<Strategies as AlignmentStrategySelector>::Chaining as ChainingStrategy<
-            <Strategies as AlignmentStrategySelector>::Cost,
-        >>::initialise_memory(reference, query, &config, 20),
-        template_switch_count: template_switch_count_memory,
-        shortcut: (),
-        primary_match: (),
+    observer: impl AStarObserver<template_switch_distance::Node<Strategies>>,
+) -> crate::error::Result<AlignmentResult<template_switch_distance::AlignmentType, Strategies::Cost>>
+{
+    check_alignment_mode(options.mode, config.has_negative_costs())?;
+    if options.epsilon.is_some() && config.has_negative_costs() {
+        return Err(crate::error::Error::EpsilonWithNegativeCosts);
+    }
+
+    Ok(a_star_align(
+        template_switch_distance_context::<Strategies, _>(
+            reference,
+            query,
+            config,
+            options,
+            options.deadline(),
+            template_switch_count_memory,
+        ),
+        options.epsilon,
+        options.initial_cost_limit,
+        options.closed_list_mode,
+        observer,
+    ))
+}
+
+/// Like [`template_switch_distance_a_star_align`], but regularly writes checkpoints of the search to `checkpoint_file`.
+///
+/// The checkpoints are written every `checkpoint_interval`, and each checkpoint replaces the previous one.
+/// If `resume_file` is given, then the search is continued from the checkpoint stored in that file.
+/// For this, all other arguments must be the same as for the checkpointed alignment, except for the limits.
+///
+/// Returns an error if `options` contain a mode, epsilon, initial cost limit or closed list mode other than the default.
+#[cfg(feature = "serde")]
+#[expect(clippy::too_many_arguments)]
+pub fn template_switch_distance_checkpointed_a_star_align<
+    Strategies: AlignmentStrategySelector<
+            Shortcut = NoShortcutStrategy<<Strategies as AlignmentStrategySelector>::Cost>,
+            PrimaryMatch = AllowPrimaryMatchStrategy,
+        >,
+    SubsequenceType: GenomeSequence<Strategies::Alphabet, SubsequenceType> + ?Sized,
+>(
+    reference: &SubsequenceType,
+    query: &SubsequenceType,
+    config: config::TemplateSwitchConfig<
+        Strategies::Alphabet,
+        <Strategies as AlignmentStrategySelector>::Cost,
+    >,
+    options: &AlignmentOptions<Strategies::Cost>,
+    template_switch_count_memory: <Strategies::TemplateSwitchCount as TemplateSwitchCountStrategy>::Memory,
+    checkpoint_file: Option<&std::path::Path>,
+    checkpoint_interval: std::time::Duration,
+    resume_file: Option<&std::path::Path>,
+    observer: impl AStarObserver<template_switch_distance::Node<Strategies>>,
+) -> crate::error::Result<AlignmentResult<template_switch_distance::AlignmentType, Strategies::Cost>>
+{
+    options.check_only_common_options()?;
+
+    checkpoint::checkpointed_a_star_align(
+        template_switch_distance_context::<Strategies, _>(
+            reference,
+            query,
+            config,
+            options,
+            options.deadline(),
+            template_switch_count_memory,
+        ),
+        checkpoint_file,
+        checkpoint_interval,
+        resume_file,
+        observer,
+    )
+}
+
+/// Like [`template_switch_distance_a_star_align`], but returns the `k` cheapest alignments ordered by ascending cost.
+///
+/// If `max_cost_delta` is given, then only alignments whose cost exceeds the optimum by at most `max_cost_delta` are returned.
+///
+/// Returns an error if `options` contain a mode, epsilon, initial cost limit or closed list mode other than the default.
+pub fn template_switch_distance_k_best_a_star_align<
+    Strategies: AlignmentStrategySelector<
+            Shortcut = NoShortcutStrategy<<Strategies as AlignmentStrategySelector>::Cost>,
+            PrimaryMatch = AllowPrimaryMatchStrategy,
+        >,
+    SubsequenceType: GenomeSequence<Strategies::Alphabet, SubsequenceType> + ?Sized,
+>(
+    reference: &SubsequenceType,
+    query: &SubsequenceType,
+    config: config::TemplateSwitchConfig<
+        Strategies::Alphabet,
+        <Strategies as AlignmentStrategySelector>::Cost,
+    >,
+    options: &AlignmentOptions<Strategies::Cost>,
+    k: usize,
+    max_cost_delta: Option<Strategies::Cost>,
+    template_switch_count_memory: <Strategies::TemplateSwitchCount as TemplateSwitchCountStrategy>::Memory,
+) -> crate::error::Result<
+    Vec<AlignmentResult<template_switch_distance::AlignmentType, Strategies::Cost>>,
+> {
+    options.check_only_common_options()?;
+
+    Ok(k_best_a_star_align(
+        template_switch_distance_context::<Strategies, _>(
+            reference,
+            query,
+            config,
+            options,
+            options.deadline(),
+            template_switch_count_memory,
+        ),
+        k,
+        max_cost_delta,
+    ))
+}
+
+/// Like [`template_switch_distance_a_star_align`], but searches with `threads` worker threads in parallel.
+///
+/// Each worker thread has its own context, so the memory of the strategies is initialised once per thread.
+///
+/// Returns an error if `options` contain a mode, epsilon, initial cost limit or closed list mode other than the default.
+pub fn template_switch_distance_parallel_a_star_align<
+    Strategies: AlignmentStrategySelector<
+            Shortcut = NoShortcutStrategy<<Strategies as AlignmentStrategySelector>::Cost>,
+            PrimaryMatch = AllowPrimaryMatchStrategy,
+        >,
+    SubsequenceType: GenomeSequence<Strategies::Alphabet, SubsequenceType> + ?Sized + Sync,
+>(
+    reference: &SubsequenceType,
+    query: &SubsequenceType,
+    config: config::TemplateSwitchConfig<
+        Strategies::Alphabet,
+        <Strategies as AlignmentStrategySelector>::Cost,
+    >,
+    options: &AlignmentOptions<Strategies::Cost>,
+    threads: usize,
+    template_switch_count_memory: <Strategies::TemplateSwitchCount as TemplateSwitchCountStrategy>::Memory,
+) -> crate::error::Result<AlignmentResult<template_switch_distance::AlignmentType, Strategies::Cost>>
+where
+    Strategies::Alphabet: Sync,
+{
+    options.check_only_common_options()?;
+
+    let deadline = options.deadline();
+    let create_context = || {
+        template_switch_distance_context::<Strategies, _>(
+            reference,
+            query,
+            config.clone(),
+            options,
+            deadline,
+            template_switch_count_memory.clone(),
+        )
     };
 
-    a_star_align(template_switch_distance::Context::<
-        SubsequenceType,
-        Strategies,
-    >::new(
+    Ok(parallel_a_star_align(
+        create_context(),
+        threads,
+        create_context,
+    ))
+}
+
+/// Create the context of a template switch alignment, where the time limit of `options` is replaced by `deadline`.
+fn template_switch_distance_context<
+    'reference,
+    'query,
+    Strategies: AlignmentStrategySelector<
+            Shortcut = NoShortcutStrategy<<Strategies as AlignmentStrategySelector>::Cost>,
+            PrimaryMatch = AllowPrimaryMatchStrategy,
+        >,
+    SubsequenceType: GenomeSequence<Strategies::Alphabet, SubsequenceType> + ?Sized,
+>(
+    reference: &'reference SubsequenceType,
+    query: &'query SubsequenceType,
+    config: config::TemplateSwitchConfig<
+        Strategies::Alphabet,
+        <Strategies as AlignmentStrategySelector>::Cost,
+    >,
+    options: &AlignmentOptions<Strategies::Cost>,
+    deadline: Option<Instant>,
+    template_switch_count_memory: <Strategies::TemplateSwitchCount as TemplateSwitchCountStrategy>::Memory,
+) -> template_switch_distance::Context<'reference, 'query, SubsequenceType, Strategies> {
+    let memory = template_switch_distance_memory::<Strategies, _>(
         reference,
         query,
-        reference_name,
-        query_name,
-        range,
+        &config,
+        &options.seeding_method,
+        template_switch_count_memory,
+    );
+
+    template_switch_distance::Context::new(
+        reference,
+        query,
+        &options.reference_name,
+        &options.query_name,
+        options.range.clone(),
+        options.mode,
         config,
         memory,
-        cost_limit,
-        memory_limit,
-    ))
+        options.cost_limit,
+        options.memory_limit,
+        deadline,
+        options.cancellation_token.clone(),
+    )
+}
+
+fn template_switch_distance_memory<
+    Strategies: AlignmentStrategySelector<
+            Shortcut = NoShortcutStrategy<<Strategies as AlignmentStrategySelector>::Cost>,
+            PrimaryMatch = AllowPrimaryMatchStrategy,
+        >,
+    SubsequenceType: GenomeSequence<Strategies::Alphabet, SubsequenceType> + ?Sized,
+>(
+    reference: &SubsequenceType,
+    query: &SubsequenceType,
+    config: &config::TemplateSwitchConfig<
+        Strategies::Alphabet,
+        <Strategies as AlignmentStrategySelector>::Cost,
+    >,
+    seeding_method: &SeedingMethod,
+    template_switch_count_memory: <Strategies::TemplateSwitchCount as TemplateSwitchCountStrategy>::Memory,
+) -> Memory<Strategies> {
+    Memory {
+        template_switch_min_length: Default::default(),
+        chaining: <<Strategies as AlignmentStrategySelector>::Chaining as ChainingStrategy<
+            <Strategies as AlignmentStrategySelector>::Cost,
+        >>::initialise_memory(reference, query, config, seeding_method),
+        template_switch_count: template_switch_count_memory,
+        shortcut: (),
+        primary_match: (),
+    }
 }
@@ -6,6 +6,38 @@ pub struct AlignmentRange {
     limit: AlignmentCoordinates,
 }
 
+/// Determines which parts of an [`AlignmentRange`] must be aligned.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum AlignmentMode {
+    /// The range is aligned completely, from its offset to its limit.
+    #[default]
+    Global,
+    /// The alignment may start and end anywhere in the range, like in the Smith-Waterman algorithm.
+    ///
+    /// The characters before the start and after the end of the alignment are clipped without cost.
+    /// Hence, clipping is only avoided if some alignment steps have negative costs.
+    Local,
+    /// The query range is aligned completely, but the alignment may start and end anywhere in the reference range.
+    ///
+    /// The characters of the reference before the start and after the end of the alignment are clipped without cost.
+    SemiGlobal,
+    /// The alignment starts at the beginning of the reference or query range and ends at the end of the reference or query range,
+    /// like in the overlap of two contig ends.
+    ///
+    /// The characters before the start and after the end of the alignment are clipped without cost.
+    /// Hence, clipping is only avoided if some alignment steps have negative costs.
+    Overlap,
+}
+
+impl AlignmentMode {
+    /// Returns true if the mode allows to clip both sequences completely.
+    ///
+    /// Then the empty alignment is optimal, unless some alignment steps have negative costs.
+    pub fn may_clip_everything(&self) -> bool {
+        matches!(self, Self::Local | Self::Overlap)
+    }
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct AlignmentCoordinates {
     reference: usize,
@@ -49,6 +81,56 @@ impl AlignmentRange {
     }
 }
 
+impl AlignmentMode {
+    /// Returns true if the alignment does not need to start and end at the offset and limit of the range.
+    pub fn is_clipping(&self) -> bool {
+        *self != Self::Global
+    }
+
+    /// Returns the coordinates of the root following the root at the given coordinates, or `None` if there is none.
+    ///
+    /// The root at the offset of the range is the first root.
+    /// The following roots are enumerated row by row, i.e. the query index increases fastest.
+    pub fn next_root(
+        &self,
+        range: &AlignmentRange,
+        reference_index: usize,
+        query_index: usize,
+    ) -> Option<(usize, usize)> {
+        let query_continues = match self {
+            Self::Global => return None,
+            Self::Local => true,
+            Self::SemiGlobal => false,
+            Self::Overlap => reference_index == range.reference_offset(),
+        };
+
+        if query_continues && query_index < range.query_limit() {
+            Some((reference_index, query_index + 1))
+        } else if reference_index < range.reference_limit() {
+            Some((reference_index + 1, range.query_offset()))
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if the alignment may end at the given coordinates, clipping all characters after them.
+    pub fn can_clip_end(
+        &self,
+        range: &AlignmentRange,
+        reference_index: usize,
+        query_index: usize,
+    ) -> bool {
+        match self {
+            Self::Global => false,
+            Self::Local => true,
+            Self::SemiGlobal => query_index == range.query_limit(),
+            Self::Overlap => {
+                reference_index == range.reference_limit() || query_index == range.query_limit()
+            }
+        }
+    }
+}
+
 impl AlignmentCoordinates {
     pub fn new(reference: usize, query: usize) -> Self {
         Self { reference, query }
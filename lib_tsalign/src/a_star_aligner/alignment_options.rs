@@ -0,0 +1,147 @@
+use std::time::{Duration, Instant};
+
+use generic_a_star::{cancellation::CancellationToken, closed_list::ClosedListMode};
+
+use crate::error::{Error, Result};
+
+use super::{
+    alignment_geometry::{AlignmentMode, AlignmentRange},
+    template_switch_distance::strategies::chaining::SeedingMethod,
+};
+
+/// The options of a template switch alignment.
+///
+/// The default options align the complete sequences globally without any limits,
+/// naming them `reference` and `query`.
+#[derive(Debug, Clone)]
+pub struct AlignmentOptions<Cost> {
+    pub(super) reference_name: String,
+    pub(super) query_name: String,
+    pub(super) range: Option<AlignmentRange>,
+    pub(super) mode: AlignmentMode,
+    pub(super) seeding_method: SeedingMethod,
+    pub(super) cost_limit: Option<Cost>,
+    pub(super) memory_limit: Option<usize>,
+    pub(super) time_limit: Option<Duration>,
+    pub(super) cancellation_token: Option<CancellationToken>,
+    pub(super) epsilon: Option<f64>,
+    pub(super) initial_cost_limit: Option<Cost>,
+    pub(super) closed_list_mode: ClosedListMode,
+}
+
+impl<Cost> Default for AlignmentOptions<Cost> {
+    fn default() -> Self {
+        Self {
+            reference_name: "reference".to_string(),
+            query_name: "query".to_string(),
+            range: None,
+            mode: AlignmentMode::default(),
+            seeding_method: SeedingMethod::default(),
+            cost_limit: None,
+            memory_limit: None,
+            time_limit: None,
+            cancellation_token: None,
+            epsilon: None,
+            initial_cost_limit: None,
+            closed_list_mode: ClosedListMode::default(),
+        }
+    }
+}
+
+impl<Cost> AlignmentOptions<Cost> {
+    /// The names of the sequences that are reported in the alignment result.
+    pub fn with_names(mut self, reference_name: &str, query_name: &str) -> Self {
+        self.reference_name = reference_name.to_string();
+        self.query_name = query_name.to_string();
+        self
+    }
+
+    /// Align only the given range of the sequences instead of the complete sequences.
+    pub fn with_range(mut self, range: Option<AlignmentRange>) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// With an [`AlignmentMode`] other than [`Global`](AlignmentMode::Global),
+    /// the clipped characters before and after the alignment are reported as [`AlignmentType::PrimaryClip`](super::template_switch_distance::AlignmentType::PrimaryClip),
+    /// which is displayed as soft clip in the CIGAR string.
+    pub fn with_mode(mut self, mode: AlignmentMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// If the chaining strategy computes a chain of anchors, then the anchors are computed with this seeding method.
+    pub fn with_seeding_method(mut self, seeding_method: SeedingMethod) -> Self {
+        self.seeding_method = seeding_method;
+        self
+    }
+
+    /// Nodes with a higher cost are not explored.
+    pub fn with_cost_limit(mut self, cost_limit: Option<Cost>) -> Self {
+        self.cost_limit = cost_limit;
+        self
+    }
+
+    /// The alignment is aborted once the search uses more than this many bytes.
+    pub fn with_memory_limit(mut self, memory_limit: Option<usize>) -> Self {
+        self.memory_limit = memory_limit;
+        self
+    }
+
+    /// The alignment is aborted once it has been running for this long.
+    pub fn with_time_limit(mut self, time_limit: Option<Duration>) -> Self {
+        self.time_limit = time_limit;
+        self
+    }
+
+    /// The alignment is aborted if this token is cancelled, e.g. from another thread.
+    pub fn with_cancellation_token(
+        mut self,
+        cancellation_token: Option<CancellationToken>,
+    ) -> Self {
+        self.cancellation_token = cancellation_token;
+        self
+    }
+
+    /// The lower bounds are weighted by `epsilon`, and the alignment is improved until it is optimal.
+    ///
+    /// This is not supported with negative costs.
+    pub fn with_epsilon(mut self, epsilon: Option<f64>) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// Unless an `epsilon` is given, the search starts with this cost limit and doubles it until an alignment is found.
+    ///
+    /// The doubling stops at the [cost limit](Self::with_cost_limit).
+    pub fn with_initial_cost_limit(mut self, initial_cost_limit: Option<Cost>) -> Self {
+        self.initial_cost_limit = initial_cost_limit;
+        self
+    }
+
+    /// With [`ClosedListMode::Compact`], the closed list uses much less memory,
+    /// allowing to align longer sequences under the same memory limit.
+    pub fn with_closed_list_mode(mut self, closed_list_mode: ClosedListMode) -> Self {
+        self.closed_list_mode = closed_list_mode;
+        self
+    }
+
+    /// The point in time at which the alignment is aborted if it starts now.
+    pub(super) fn deadline(&self) -> Option<Instant> {
+        self.time_limit
+            .map(|time_limit| Instant::now() + time_limit)
+    }
+
+    /// Returns an error if options are set that only [`template_switch_distance_a_star_align`](super::template_switch_distance_a_star_align) supports.
+    pub(super) fn check_only_common_options(&self) -> Result<()> {
+        if self.mode == AlignmentMode::Global
+            && self.epsilon.is_none()
+            && self.initial_cost_limit.is_none()
+            && self.closed_list_mode == ClosedListMode::Full
+        {
+            Ok(())
+        } else {
+            Err(Error::UnsupportedAlignmentOptions)
+        }
+    }
+}
@@ -36,6 +36,22 @@ pub enum AlignmentResult<AlignmentType, Cost> {
         #[cfg_attr(feature = "serde", serde(flatten))]
         statistics: AlignmentStatistics<Cost>,
     },
+
+    /// The search was aborted, but the closed node that got furthest was backtracked into an alignment of a prefix of the sequences.
+    Partial {
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        alignment: Alignment<AlignmentType>,
+
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        statistics: AlignmentStatistics<Cost>,
+
+        /// The cost of the partial alignment.
+        partial_cost: R64,
+        /// The reference index after the last reference character of the partial alignment.
+        reference_end: usize,
+        /// The query index after the last query character of the partial alignment.
+        query_end: usize,
+    },
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
@@ -58,15 +74,22 @@ pub struct AlignmentStatistics<Cost> {
     pub suboptimal_opened_nodes_ratio: R64,
     pub template_switch_amount: R64,
 
+    /// The factor by which the cost is proven to be at most larger than the optimal cost.
+    ///
+    /// This is one if the search was exact.
+    #[cfg_attr(feature = "serde", serde(default = "default_suboptimality_bound"))]
+    pub suboptimality_bound: R64,
+
     /// Runtime in seconds.
     ///
     /// To be filled by some other tool, not collected by tsalign.
     #[cfg_attr(feature = "serde", serde(default))]
     pub runtime: R64,
 
-    /// Memory in bytes.
+    /// Peak memory in bytes.
     ///
-    /// To be filled by some other tool, not collected by tsalign.
+    /// This is the peak memory usage of the search as accounted by the aligner.
+    /// It may be overwritten by some other tool that measures the memory of the whole process.
     #[cfg_attr(feature = "serde", serde(default))]
     pub memory: R64,
 }
@@ -81,6 +104,7 @@ macro_rules! each_statistic {
         $action!(suboptimal_opened_nodes);
         $action!(suboptimal_opened_nodes_ratio);
         $action!(template_switch_amount);
+        $action!(suboptimality_bound);
         $action!(runtime);
         $action!(memory);
     }};
@@ -104,6 +128,7 @@ impl<AlignmentType: IAlignmentType, Cost: AStarCost> AlignmentResult<AlignmentTy
         opened_nodes: usize,
         closed_nodes: usize,
         suboptimal_opened_nodes: usize,
+        peak_memory_usage: usize,
         reference_length: usize,
         query_length: usize,
     ) -> Self {
@@ -120,6 +145,7 @@ impl<AlignmentType: IAlignmentType, Cost: AStarCost> AlignmentResult<AlignmentTy
             opened_nodes,
             closed_nodes,
             suboptimal_opened_nodes,
+            peak_memory_usage,
             reference_length,
             query_length,
         )
@@ -141,6 +167,7 @@ impl<AlignmentType: IAlignmentType, Cost: AStarCost> AlignmentResult<AlignmentTy
         opened_nodes: usize,
         closed_nodes: usize,
         suboptimal_opened_nodes: usize,
+        peak_memory_usage: usize,
         reference_length: usize,
         query_length: usize,
     ) -> Self {
@@ -157,11 +184,33 @@ impl<AlignmentType: IAlignmentType, Cost: AStarCost> AlignmentResult<AlignmentTy
             opened_nodes,
             closed_nodes,
             suboptimal_opened_nodes,
+            peak_memory_usage,
             reference_length,
             query_length,
         )
     }
 
+    /// Turn a result with target into the result of an aborted search that contains the alignment up to the given end coordinates.
+    ///
+    /// Panics if the result has no target.
+    pub fn into_partial(self, partial_cost: Cost, reference_end: usize, query_end: usize) -> Self {
+        let Self::WithTarget {
+            alignment,
+            statistics,
+        } = self
+        else {
+            panic!("Only a result with target can be turned into a partial result")
+        };
+
+        Self::Partial {
+            alignment,
+            statistics,
+            partial_cost: partial_cost.as_f64().try_into().unwrap(),
+            reference_end,
+            query_end,
+        }
+    }
+
     #[expect(clippy::too_many_arguments)]
     fn new<
         AlphabetType: Alphabet,
@@ -179,10 +228,12 @@ impl<AlignmentType: IAlignmentType, Cost: AStarCost> AlignmentResult<AlignmentTy
         opened_nodes: usize,
         closed_nodes: usize,
         suboptimal_opened_nodes: usize,
+        peak_memory_usage: usize,
         reference_length: usize,
         query_length: usize,
     ) -> Self {
         let cost = result.cost();
+        let suboptimality_bound = result.suboptimality_bound().min(f64::MAX);
         let statistics = AlignmentStatistics {
             result,
             sequences: SequencePair::new(reference, query, reference_name, query_name),
@@ -197,10 +248,13 @@ impl<AlignmentType: IAlignmentType, Cost: AStarCost> AlignmentResult<AlignmentTy
             opened_nodes: (opened_nodes as f64).try_into().unwrap(),
             closed_nodes: (closed_nodes as f64).try_into().unwrap(),
             suboptimal_opened_nodes: (suboptimal_opened_nodes as f64).try_into().unwrap(),
-            suboptimal_opened_nodes_ratio: (suboptimal_opened_nodes as f64
-                / (opened_nodes - suboptimal_opened_nodes) as f64)
-                .try_into()
-                .unwrap(),
+            // The search may be aborted before opening any node.
+            suboptimal_opened_nodes_ratio: if opened_nodes > suboptimal_opened_nodes {
+                r64(suboptimal_opened_nodes as f64
+                    / (opened_nodes - suboptimal_opened_nodes) as f64)
+            } else {
+                r64(0.0)
+            },
             template_switch_amount: r64(alignment
                 .as_ref()
                 .map(|alignment| {
@@ -210,8 +264,9 @@ impl<AlignmentType: IAlignmentType, Cost: AStarCost> AlignmentResult<AlignmentTy
                         .count() as f64
                 })
                 .unwrap_or(0.0)),
+            suboptimality_bound: r64(suboptimality_bound),
             runtime: r64(0.0),
-            memory: r64(0.0),
+            memory: r64(peak_memory_usage as f64),
         };
 
         if let Some(alignment) = alignment {
@@ -230,6 +285,7 @@ impl<AlignmentType, Cost> AlignmentResult<AlignmentType, Cost> {
         match self {
             AlignmentResult::WithTarget { statistics, .. } => statistics,
             AlignmentResult::WithoutTarget { statistics } => statistics,
+            AlignmentResult::Partial { statistics, .. } => statistics,
         }
     }
 
@@ -237,6 +293,16 @@ impl<AlignmentType, Cost> AlignmentResult<AlignmentType, Cost> {
         match self {
             AlignmentResult::WithTarget { statistics, .. } => statistics,
             AlignmentResult::WithoutTarget { statistics } => statistics,
+            AlignmentResult::Partial { statistics, .. } => statistics,
+        }
+    }
+
+    /// Returns the alignment, which is either complete or [partial](Self::Partial).
+    pub fn alignment(&self) -> Option<&Alignment<AlignmentType>> {
+        match self {
+            AlignmentResult::WithTarget { alignment, .. }
+            | AlignmentResult::Partial { alignment, .. } => Some(alignment),
+            AlignmentResult::WithoutTarget { .. } => None,
         }
     }
 }
@@ -255,7 +321,7 @@ impl<AlignmentType: IAlignmentType, Cost> AlignmentResult<AlignmentType, Cost> {
     where
         AlignmentType: Display,
     {
-        let Self::WithTarget { alignment, .. } = self else {
+        let Some(alignment) = self.alignment() else {
             return Ok(());
         };
 
@@ -389,18 +455,30 @@ impl<AlignmentType: Display + IAlignmentType, Cost: Display> Display
     for AlignmentResult<AlignmentType, Cost>
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        if let AlignmentResult::WithTarget { .. } = self {
-            write!(f, "CIGAR: ")?;
-            self.write_cigar(f)?;
-            writeln!(f)?;
-        } else {
-            writeln!(f, "No alignment found")?;
+        match self {
+            AlignmentResult::WithTarget { .. } => {
+                write!(f, "CIGAR: ")?;
+                self.write_cigar(f)?;
+                writeln!(f)?;
+            }
+            AlignmentResult::WithoutTarget { .. } => writeln!(f, "No alignment found")?,
+            AlignmentResult::Partial {
+                partial_cost,
+                reference_end,
+                query_end,
+                ..
+            } => {
+                write!(f, "Partial CIGAR: ")?;
+                self.write_cigar(f)?;
+                writeln!(f)?;
+                writeln!(
+                    f,
+                    "Partial alignment ends at reference index {reference_end} and query index {query_end} with cost {partial_cost}"
+                )?;
+            }
         }
 
-        let (AlignmentResult::WithTarget { statistics, .. }
-        | AlignmentResult::WithoutTarget { statistics }) = self;
-
-        statistics.fmt(f)
+        self.statistics().fmt(f)
     }
 }
 
@@ -420,6 +498,9 @@ impl<Cost: Display> Display for AlignmentStatistics<Cost> {
             "Suboptimal openend nodes per optimal opened node: {:.2}",
             self.suboptimal_opened_nodes_ratio
         )?;
+        if self.suboptimality_bound != 1.0 {
+            writeln!(f, "Suboptimality bound: {:.3}", self.suboptimality_bound)?;
+        }
         write!(f, "Duration: {:.2}s", self.duration_seconds)?;
 
         Ok(())
@@ -441,8 +522,14 @@ impl<Cost> Default for AlignmentStatistics<Cost> {
             suboptimal_opened_nodes: Default::default(),
             suboptimal_opened_nodes_ratio: Default::default(),
             template_switch_amount: Default::default(),
+            suboptimality_bound: Default::default(),
             runtime: Default::default(),
             memory: Default::default(),
         }
     }
 }
+
+#[cfg(feature = "serde")]
+fn default_suboptimality_bound() -> R64 {
+    r64(1.0)
+}
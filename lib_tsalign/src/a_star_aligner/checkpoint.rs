@@ -0,0 +1,167 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    ops::ControlFlow,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use bincode::Options;
+use generic_a_star::{
+    AStar, AStarContext, AStarNode, AStarResult, checkpoint::CheckpointContext,
+    observer::AStarObserver,
+};
+use log::info;
+use serde::{Serialize, de::DeserializeOwned};
+
+use super::{
+    AlignmentContext, alignment_result::AlignmentResult, alignment_result::IAlignmentType,
+    collect_alignment, create_alignment_result,
+};
+use crate::error::Result;
+
+/// Querying the clock is comparatively expensive, so it is checked only once per this many closed nodes.
+const CLOCK_CHECK_INTERVAL: usize = 1024;
+
+/// Stops the search whenever a checkpoint is due, and forwards all other events to the wrapped observer.
+struct CheckpointTimer<Observer> {
+    observer: Observer,
+    /// The checkpoint interval, or `None` if no checkpoints are written.
+    interval: Option<Duration>,
+    next_checkpoint: Instant,
+    closed_nodes: usize,
+    checkpoint_due: bool,
+}
+
+/// Like [`a_star_align`](super::a_star_align), but regularly writes checkpoints of the search to `checkpoint_file`.
+///
+/// The checkpoints are written every `checkpoint_interval`, and each checkpoint replaces the previous one.
+/// If `resume_file` is given, then the search is continued from the checkpoint stored in that file.
+/// For this, the context must have been constructed in the same way as the context of the checkpointed search.
+pub(super) fn checkpointed_a_star_align<Context: CheckpointContext + AlignmentContext>(
+    context: Context,
+    checkpoint_file: Option<&Path>,
+    checkpoint_interval: Duration,
+    resume_file: Option<&Path>,
+    observer: impl AStarObserver<Context::Node>,
+) -> Result<
+    AlignmentResult<Context::AlignmentType, <<Context as AStarContext>::Node as AStarNode>::Cost>,
+>
+where
+    Context::Node: Serialize + DeserializeOwned,
+    <Context::Node as AStarNode>::EdgeType: IAlignmentType,
+{
+    info!("Aligning on subsequence {}", context.range());
+
+    let start_time = Instant::now();
+
+    let mut a_star = AStar::new_with_observer(
+        context,
+        CheckpointTimer {
+            observer,
+            interval: checkpoint_file.map(|_| checkpoint_interval),
+            next_checkpoint: start_time + checkpoint_interval,
+            closed_nodes: 0,
+            checkpoint_due: false,
+        },
+    );
+
+    if let Some(resume_file) = resume_file {
+        info!("Resuming from checkpoint {resume_file:?}");
+        let checkpoint = bincode::deserialize_from(BufReader::new(File::open(resume_file)?))?;
+        a_star.resume(checkpoint);
+    } else {
+        a_star.initialise();
+    }
+
+    let result = loop {
+        let result = a_star.search();
+
+        if matches!(result, AStarResult::Stopped { .. }) && a_star.observer().checkpoint_due {
+            let checkpoint_file = checkpoint_file.unwrap();
+            info!("Writing checkpoint {checkpoint_file:?}");
+            write_checkpoint(&a_star, checkpoint_file)?;
+
+            let timer = a_star.observer_mut();
+            timer.checkpoint_due = false;
+            timer.next_checkpoint = Instant::now() + checkpoint_interval;
+            continue;
+        }
+
+        break result;
+    };
+    let has_target = matches!(result, AStarResult::FoundTarget { .. });
+
+    let alignment = has_target.then(|| collect_alignment::<Context>(a_star.backtrack()));
+
+    let end_time = Instant::now();
+    let duration = (end_time - start_time).as_secs_f64();
+
+    Ok(create_alignment_result(
+        a_star.context(),
+        alignment,
+        result,
+        duration,
+        a_star.performance_counters(),
+    ))
+}
+
+/// Write the checkpoint to a temporary file first, such that the previous checkpoint stays intact if writing fails.
+fn write_checkpoint<Context: CheckpointContext, Observer: AStarObserver<Context::Node>>(
+    a_star: &AStar<Context, Observer>,
+    checkpoint_file: &Path,
+) -> Result<()>
+where
+    Context::Node: Serialize,
+{
+    let mut temporary_file = checkpoint_file.as_os_str().to_owned();
+    temporary_file.push(".tmp");
+
+    let mut writer = BufWriter::new(File::create(&temporary_file)?);
+    a_star.write_checkpoint(&mut bincode::Serializer::new(
+        &mut writer,
+        bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes(),
+    ))?;
+    writer.flush()?;
+    drop(writer);
+
+    std::fs::rename(temporary_file, checkpoint_file)?;
+    Ok(())
+}
+
+impl<Node: AStarNode, Observer: AStarObserver<Node>> AStarObserver<Node>
+    for CheckpointTimer<Observer>
+{
+    fn on_node_opened(&mut self, node: &Node) {
+        self.observer.on_node_opened(node);
+    }
+
+    fn on_node_closed(&mut self, node: &Node) -> ControlFlow<()> {
+        if self.interval.is_some() {
+            self.closed_nodes += 1;
+            if self.closed_nodes % CLOCK_CHECK_INTERVAL == 0
+                && Instant::now() >= self.next_checkpoint
+            {
+                // The node is not forwarded, since the search closes it again after writing the checkpoint.
+                self.checkpoint_due = true;
+                return ControlFlow::Break(());
+            }
+        }
+
+        self.observer.on_node_closed(node)
+    }
+
+    fn on_suboptimal_node(&mut self, node: &Node) {
+        self.observer.on_suboptimal_node(node);
+    }
+
+    fn on_target_found(&mut self, node: &Node) {
+        self.observer.on_target_found(node);
+    }
+
+    fn on_limit_reached(&mut self, result: &AStarResult<Node::Identifier, Node::Cost>) {
+        self.observer.on_limit_reached(result);
+    }
+}
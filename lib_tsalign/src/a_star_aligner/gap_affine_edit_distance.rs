@@ -1,10 +1,17 @@
 use std::marker::PhantomData;
 
 use compact_genome::interface::{alphabet::Alphabet, sequence::GenomeSequence};
-use generic_a_star::{AStarContext, AStarNode, cost::AStarCost, reset::Reset};
+use generic_a_star::{
+    AStarContext, AStarNode, bidirectional::BidirectionalAStarContext, cost::AStarCost,
+    reset::Reset,
+};
+
+use crate::costs::remaining_characters_lower_bound;
 
 use super::{
-    AlignmentContext, alignment_geometry::AlignmentRange, alignment_result::IAlignmentType,
+    AlignmentContext,
+    alignment_geometry::{AlignmentMode, AlignmentRange},
+    alignment_result::IAlignmentType,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -13,6 +20,7 @@ pub struct Node<Cost> {
     predecessor: Option<Identifier>,
     predecessor_edge_type: AlignmentType,
     cost: Cost,
+    a_star_lower_bound: Cost,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -42,6 +50,13 @@ pub enum AlignmentType {
     Match,
     /// This node is the root node, hence it was not generated via alignment.
     Root,
+    /// Characters that are not aligned, because they are before the start or after the end of a non-global alignment.
+    ///
+    /// These are displayed as soft clips.
+    Clip {
+        reference_length: usize,
+        query_length: usize,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -63,6 +78,7 @@ pub struct Context<
     reference: &'reference SubsequenceType,
     query: &'query SubsequenceType,
     range: AlignmentRange,
+    mode: AlignmentMode,
 
     scoring_table: ScoringTable<Cost>,
     phantom_data: PhantomData<AlphabetType>,
@@ -84,7 +100,7 @@ impl<Cost: AStarCost> AStarNode for Node<Cost> {
     }
 
     fn a_star_lower_bound(&self) -> Cost {
-        Cost::zero()
+        self.a_star_lower_bound
     }
 
     fn predecessor(&self) -> Option<&Self::Identifier> {
@@ -105,22 +121,81 @@ impl<
     type Node = Node<Cost>;
 
     fn create_root(&self) -> Self::Node {
+        let identifier = Identifier::new(0, 0, GapType::None);
         Self::Node {
-            identifier: Identifier::new(0, 0, GapType::None),
+            identifier,
             predecessor: None,
             predecessor_edge_type: AlignmentType::Root,
             cost: Cost::zero(),
+            a_star_lower_bound: self.lower_bound(&identifier),
         }
     }
 
+    /// In modes other than [global](AlignmentMode::Global), the cells where the alignment may start are roots, clipping all characters before them.
+    /// The roots are created row by row (see [`AlignmentMode::next_root`]), and they all get the lower bound of the first root,
+    /// such that they are created in ascending order of their cost plus lower bound.
+    ///
+    /// In [local](AlignmentMode::Local) mode without negative costs, clipping everything is optimal, so then no further roots are created.
+    fn next_root(&self, root: &Self::Node) -> Option<Self::Node> {
+        if self.mode == AlignmentMode::Local && !self.has_negative_costs() {
+            return None;
+        }
+
+        let (reference_index, query_index) = self.mode.next_root(
+            &self.range,
+            root.identifier.reference_index,
+            root.identifier.query_index,
+        )?;
+        let identifier = Identifier::new(reference_index, query_index, GapType::None);
+
+        Some(Self::Node {
+            identifier,
+            predecessor: None,
+            predecessor_edge_type: AlignmentType::Clip {
+                reference_length: identifier.reference_index,
+                query_length: identifier.query_index,
+            },
+            cost: Cost::zero(),
+            a_star_lower_bound: root.a_star_lower_bound,
+        })
+    }
+
     fn generate_successors(&mut self, node: &Self::Node, output: &mut impl Extend<Self::Node>) {
+        if self.mode.can_clip_end(
+            &self.range,
+            node.identifier.reference_index,
+            node.identifier.query_index,
+        ) {
+            // Clip the remaining characters.
+            let reference_length = self.reference.len() - node.identifier.reference_index;
+            let query_length = self.query.len() - node.identifier.query_index;
+
+            if reference_length > 0 || query_length > 0 {
+                output.extend([Self::Node {
+                    identifier: Identifier::new(
+                        self.reference.len(),
+                        self.query.len(),
+                        GapType::None,
+                    ),
+                    predecessor: Some(node.identifier),
+                    predecessor_edge_type: AlignmentType::Clip {
+                        reference_length,
+                        query_length,
+                    },
+                    cost: node.cost,
+                    a_star_lower_bound: Cost::zero(),
+                }]);
+            }
+        }
+
         if node.identifier.reference_index < self.reference.len()
             && node.identifier.query_index < self.query.len()
         {
             let is_match = self.reference[node.identifier.reference_index]
                 == self.query[node.identifier.query_index];
+            let identifier = node.identifier.increment_both();
             output.extend([Self::Node {
-                identifier: node.identifier.increment_both(),
+                identifier,
                 predecessor: Some(node.identifier),
                 predecessor_edge_type: if is_match {
                     AlignmentType::Match
@@ -133,12 +208,14 @@ impl<
                     } else {
                         self.scoring_table.substitution_cost
                     },
+                a_star_lower_bound: self.lower_bound(&identifier),
             }]);
         }
 
         if node.identifier.reference_index < self.reference.len() {
+            let identifier = node.identifier.increment_reference();
             output.extend([Self::Node {
-                identifier: node.identifier.increment_reference(),
+                identifier,
                 predecessor: Some(node.identifier),
                 predecessor_edge_type: AlignmentType::Deletion,
                 cost: node.cost
@@ -151,12 +228,14 @@ impl<
                     } else {
                         self.scoring_table.gap_open_cost
                     },
+                a_star_lower_bound: self.lower_bound(&identifier),
             }]);
         }
 
         if node.identifier.query_index < self.query.len() {
+            let identifier = node.identifier.increment_query();
             output.extend([Self::Node {
-                identifier: node.identifier.increment_query(),
+                identifier,
                 predecessor: Some(node.identifier),
                 predecessor_edge_type: AlignmentType::Insertion,
                 cost: node.cost
@@ -169,6 +248,7 @@ impl<
                     } else {
                         self.scoring_table.gap_open_cost
                     },
+                a_star_lower_bound: self.lower_bound(&identifier),
             }]);
         }
     }
@@ -185,6 +265,105 @@ impl<
     fn memory_limit(&self) -> Option<usize> {
         None
     }
+
+    /// With negative costs, a node may be reached with a lower cost after it was closed.
+    fn is_label_setting(&self) -> bool {
+        !self.has_negative_costs()
+    }
+}
+
+impl<
+    AlphabetType: Alphabet,
+    Cost: AStarCost,
+    SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized,
+> BidirectionalAStarContext for Context<'_, '_, AlphabetType, Cost, SubsequenceType>
+{
+    fn create_reverse_roots(&self, output: &mut impl Extend<Self::Node>) {
+        output.extend(
+            [GapType::None, GapType::Deletion, GapType::Insertion]
+                .into_iter()
+                .map(|gap_type| Identifier::new(self.reference.len(), self.query.len(), gap_type))
+                .filter(Identifier::is_valid)
+                .map(|identifier| Self::Node {
+                    identifier,
+                    predecessor: None,
+                    predecessor_edge_type: AlignmentType::Root,
+                    cost: Cost::zero(),
+                    a_star_lower_bound: Cost::zero(),
+                }),
+        );
+    }
+
+    fn generate_reverse_successors(
+        &mut self,
+        node: &Self::Node,
+        output: &mut impl Extend<Self::Node>,
+    ) {
+        let Identifier {
+            reference_index,
+            query_index,
+            gap_type,
+        } = node.identifier;
+
+        // The gap type of a node is determined by the edge that leads into it.
+        let (predecessor_reference_index, predecessor_query_index, alignment_type) = match gap_type
+        {
+            GapType::None => {
+                if reference_index == 0 || query_index == 0 {
+                    // This is the root node.
+                    return;
+                }
+
+                if self.reference[reference_index - 1] == self.query[query_index - 1] {
+                    (reference_index - 1, query_index - 1, AlignmentType::Match)
+                } else {
+                    (
+                        reference_index - 1,
+                        query_index - 1,
+                        AlignmentType::Substitution,
+                    )
+                }
+            }
+            GapType::Deletion => (reference_index - 1, query_index, AlignmentType::Deletion),
+            GapType::Insertion => (reference_index, query_index - 1, AlignmentType::Insertion),
+        };
+
+        output.extend(
+            [GapType::None, GapType::Deletion, GapType::Insertion]
+                .into_iter()
+                .map(|predecessor_gap_type| {
+                    Identifier::new(
+                        predecessor_reference_index,
+                        predecessor_query_index,
+                        predecessor_gap_type,
+                    )
+                })
+                .filter(Identifier::is_valid)
+                .map(|predecessor| Self::Node {
+                    identifier: predecessor,
+                    predecessor: Some(node.identifier),
+                    predecessor_edge_type: alignment_type,
+                    cost: node.cost
+                        + match alignment_type {
+                            AlignmentType::Match => self.scoring_table.match_cost,
+                            AlignmentType::Substitution => self.scoring_table.substitution_cost,
+                            AlignmentType::Deletion | AlignmentType::Insertion => {
+                                if predecessor.gap_type == gap_type {
+                                    self.scoring_table.gap_extend_cost
+                                } else {
+                                    self.scoring_table.gap_open_cost
+                                }
+                            }
+                            AlignmentType::Root | AlignmentType::Clip { .. } => unreachable!(),
+                        },
+                    a_star_lower_bound: Cost::zero(),
+                }),
+        );
+    }
+
+    fn is_reverse_target(&self, node: &Self::Node) -> bool {
+        node.identifier == Identifier::new(0, 0, GapType::None)
+    }
 }
 
 impl<
@@ -227,6 +406,10 @@ impl<
     fn range(&self) -> &AlignmentRange {
         &self.range
     }
+
+    fn partial_alignment_end(&self, identifier: &Identifier) -> Option<(usize, usize)> {
+        Some((identifier.reference_index, identifier.query_index))
+    }
 }
 
 impl<
@@ -241,26 +424,67 @@ impl<
         reference: &'reference SubsequenceType,
         query: &'query SubsequenceType,
         scoring_table: ScoringTable<Cost>,
+        mode: AlignmentMode,
     ) -> Self {
         Self {
             reference,
             query,
             range: AlignmentRange::new_complete(reference.len(), query.len()),
+            mode,
             scoring_table,
             phantom_data: PhantomData,
         }
     }
+
+    /// Returns true if some alignment steps have negative costs.
+    ///
+    /// Then the search is not [label setting](AStarContext::is_label_setting).
+    pub fn has_negative_costs(&self) -> bool {
+        self.min_negative_diagonal_cost() < Cost::zero()
+            || self.min_negative_gap_cost() < Cost::zero()
+    }
+
+    fn min_negative_diagonal_cost(&self) -> Cost {
+        self.scoring_table
+            .match_cost
+            .min(self.scoring_table.substitution_cost)
+            .min(Cost::zero())
+    }
+
+    fn min_negative_gap_cost(&self) -> Cost {
+        self.scoring_table
+            .gap_open_cost
+            .min(self.scoring_table.gap_extend_cost)
+            .min(Cost::zero())
+    }
+
+    /// Returns a lower bound on the cost of reaching the target from a node with the given identifier.
+    ///
+    /// This is zero if no alignment step has a negative cost, and otherwise bounds the remaining characters by [`remaining_characters_lower_bound`].
+    /// The bounds of the bidirectional search are always zero, so it does not support negative costs.
+    fn lower_bound(&self, identifier: &Identifier) -> Cost {
+        if !self.has_negative_costs() {
+            return Cost::zero();
+        }
+
+        remaining_characters_lower_bound(
+            self.min_negative_diagonal_cost(),
+            self.min_negative_gap_cost(),
+            self.reference.len() - identifier.reference_index,
+            self.query.len() - identifier.query_index,
+        )
+    }
 }
 
-impl<Cost: Ord> PartialOrd for Node<Cost> {
+impl<Cost: AStarCost> PartialOrd for Node<Cost> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl<Cost: Ord> Ord for Node<Cost> {
+impl<Cost: AStarCost> Ord for Node<Cost> {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        match self.cost.cmp(&other.cost) {
+        match (self.cost + self.a_star_lower_bound).cmp(&(other.cost + other.a_star_lower_bound)) {
             // This secondary ordering may make things actually slower.
             // While it does reduce the number of visited nodes a little bit,
             // it also makes heap operations more expensive.
@@ -281,13 +505,15 @@ impl<Cost: std::fmt::Display> std::fmt::Display for Node<Cost> {
             predecessor,
             predecessor_edge_type,
             cost,
+            a_star_lower_bound,
         } = self;
         write!(f, "{identifier}; ")?;
         if let Some(predecessor) = predecessor {
             write!(f, "predecessor: {predecessor}; ")?;
         }
         write!(f, "alignment_type: {predecessor_edge_type}")?;
-        write!(f, "cost: {cost}")
+        write!(f, "cost: {cost}; ")?;
+        write!(f, "lower bound: {a_star_lower_bound}")
     }
 }
 
@@ -324,6 +550,15 @@ impl Identifier {
         }
     }
 
+    /// Returns true if this identifier can be reached from the root node.
+    const fn is_valid(&self) -> bool {
+        match self.gap_type {
+            GapType::None => (self.reference_index == 0) == (self.query_index == 0),
+            GapType::Deletion => self.reference_index > 0,
+            GapType::Insertion => self.query_index > 0,
+        }
+    }
+
     const fn anti_diagonal(&self) -> usize {
         self.reference_index + self.query_index
     }
@@ -337,6 +572,10 @@ impl std::fmt::Display for AlignmentType {
             AlignmentType::Substitution => write!(f, "S"),
             AlignmentType::Match => write!(f, "M"),
             AlignmentType::Root => Ok(()),
+            AlignmentType::Clip {
+                reference_length,
+                query_length,
+            } => write!(f, "[SC:R{reference_length}Q{query_length}]"),
         }
     }
 }
@@ -363,11 +602,11 @@ impl std::fmt::Display for Identifier {
 
 impl IAlignmentType for AlignmentType {
     fn is_repeatable(&self) -> bool {
-        true
+        !matches!(self, Self::Clip { .. })
     }
 
     fn is_repeated(&self, previous: &Self) -> bool {
-        self == previous
+        self.is_repeatable() && self == previous
     }
 
     fn is_internal(&self) -> bool {
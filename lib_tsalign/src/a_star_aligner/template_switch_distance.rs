@@ -15,15 +15,26 @@ pub mod context;
 pub mod display;
 mod identifier;
 pub mod lower_bounds;
+pub mod search_dump;
 pub mod strategies;
 
 pub use alignment_type::AlignmentType;
 pub use context::Context;
-pub use identifier::{Identifier, TemplateSwitchPrimary, TemplateSwitchSecondary};
+pub use identifier::{
+    Identifier, TemplateSwitchDirection, TemplateSwitchPrimary, TemplateSwitchSecondary,
+};
 
 use crate::config::BaseCost;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "Strategies::Cost: serde::Serialize, <Strategies::PrimaryMatch as PrimaryMatchStrategy<Strategies::Cost>>::IdentifierPrimaryExtraData: serde::Serialize, AlignmentStrategiesNodeMemory<Strategies>: serde::Serialize",
+        deserialize = "Strategies::Cost: serde::Deserialize<'de>, <Strategies::PrimaryMatch as PrimaryMatchStrategy<Strategies::Cost>>::IdentifierPrimaryExtraData: serde::Deserialize<'de>, AlignmentStrategiesNodeMemory<Strategies>: serde::Deserialize<'de>"
+    ))
+)]
 pub struct Node<Strategies: AlignmentStrategySelector> {
     node_data: NodeData<
         <<Strategies as AlignmentStrategySelector>::PrimaryMatch as PrimaryMatchStrategy<
@@ -35,6 +46,7 @@ pub struct Node<Strategies: AlignmentStrategySelector> {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NodeData<PrimaryExtraData: Copy, Cost> {
     identifier: Identifier<PrimaryExtraData>,
     predecessor: Option<Identifier<PrimaryExtraData>>,
@@ -83,16 +95,20 @@ impl<Strategies: AlignmentStrategySelector> Node<Strategies> {
         query_index: usize,
         context: &Context<'_, '_, SubsequenceType, Strategies>,
     ) -> Self {
+        let mut node_data = NodeData::create_root(Identifier::Primary {
+            reference_index,
+            query_index,
+            gap_type: GapType::None,
+            flank_index: 0,
+            data:
+                <<Strategies as AlignmentStrategySelector>::PrimaryMatch as PrimaryMatchStrategy<
+                    <Strategies as AlignmentStrategySelector>::Cost,
+                >>::create_root_identifier_primary_extra_data(context),
+        });
+        node_data.a_star_lower_bound = context.negative_cost_lower_bound(&node_data.identifier);
+
         Self {
-            node_data: NodeData::create_root(Identifier::Primary {
-                reference_index,
-                query_index,
-                gap_type: GapType::None,
-                flank_index: 0,
-                data: <<Strategies as AlignmentStrategySelector>::PrimaryMatch as PrimaryMatchStrategy<
-                <Strategies as AlignmentStrategySelector>::Cost,
-            >>::create_root_identifier_primary_extra_data( context),
-            }),
+            node_data,
             strategies: AlignmentStrategiesNodeMemory::create_root(context),
         }
     }
@@ -231,6 +247,7 @@ impl<Strategies: AlignmentStrategySelector> Node<Strategies> {
                 let Identifier::TemplateSwitchEntrance {
                     template_switch_primary,
                     template_switch_secondary,
+                    template_switch_direction,
                     template_switch_first_offset,
                     ..
                 } = &identifier
@@ -238,18 +255,11 @@ impl<Strategies: AlignmentStrategySelector> Node<Strategies> {
                     unreachable!("This closure is only called on template switch entrances.")
                 };
 
-                let base_cost = match (template_switch_primary, template_switch_secondary) {
-                    (TemplateSwitchPrimary::Reference, TemplateSwitchSecondary::Reference) => {
-                        base_cost.rr
-                    }
-                    (TemplateSwitchPrimary::Reference, TemplateSwitchSecondary::Query) => {
-                        base_cost.rq
-                    }
-                    (TemplateSwitchPrimary::Query, TemplateSwitchSecondary::Reference) => {
-                        base_cost.qr
-                    }
-                    (TemplateSwitchPrimary::Query, TemplateSwitchSecondary::Query) => base_cost.qq,
-                };
+                let base_cost = base_cost.get(
+                    *template_switch_primary,
+                    *template_switch_secondary,
+                    *template_switch_direction,
+                );
 
                 (base_cost != Strategies::Cost::max_value()).then(|| {
                     self.generate_successor(
@@ -258,6 +268,7 @@ impl<Strategies: AlignmentStrategySelector> Node<Strategies> {
                         AlignmentType::TemplateSwitchEntrance {
                             primary: *template_switch_primary,
                             secondary: *template_switch_secondary,
+                            direction: *template_switch_direction,
                             first_offset: *template_switch_first_offset,
                         },
                         context,
@@ -283,6 +294,7 @@ impl<Strategies: AlignmentStrategySelector> Node<Strategies> {
             entrance_query_index,
             template_switch_primary,
             template_switch_secondary,
+            template_switch_direction,
             ..
         } = self.node_data.identifier
         else {
@@ -295,12 +307,14 @@ impl<Strategies: AlignmentStrategySelector> Node<Strategies> {
                 entrance_query_index,
                 template_switch_primary,
                 template_switch_secondary,
+                template_switch_direction,
                 template_switch_first_offset: successor_template_switch_first_offset,
             },
             cost_increment,
             AlignmentType::TemplateSwitchEntrance {
                 primary: template_switch_primary,
                 secondary: template_switch_secondary,
+                direction: template_switch_direction,
                 first_offset: successor_template_switch_first_offset,
             },
             context,
@@ -326,6 +340,7 @@ impl<Strategies: AlignmentStrategySelector> Node<Strategies> {
             entrance_query_index,
             template_switch_primary,
             template_switch_secondary,
+            template_switch_direction,
             template_switch_first_offset,
         } = self.node_data.identifier
         else {
@@ -358,6 +373,7 @@ impl<Strategies: AlignmentStrategySelector> Node<Strategies> {
                 entrance_query_index,
                 template_switch_primary,
                 template_switch_secondary,
+                template_switch_direction,
                 length: 0,
                 primary_index,
                 secondary_index,
@@ -468,6 +484,7 @@ impl<Strategies: AlignmentStrategySelector> Node<Strategies> {
             entrance_query_index,
             template_switch_primary,
             template_switch_secondary,
+            template_switch_direction,
             primary_index,
             length,
             ..
@@ -482,6 +499,7 @@ impl<Strategies: AlignmentStrategySelector> Node<Strategies> {
                 entrance_query_index,
                 template_switch_primary,
                 template_switch_secondary,
+                template_switch_direction,
                 primary_index,
                 anti_primary_gap: length.try_into().unwrap(),
             },
@@ -510,6 +528,7 @@ impl<Strategies: AlignmentStrategySelector> Node<Strategies> {
             entrance_query_index,
             template_switch_primary,
             template_switch_secondary,
+            template_switch_direction,
             primary_index,
             ..
         } = self.node_data.identifier
@@ -523,6 +542,7 @@ impl<Strategies: AlignmentStrategySelector> Node<Strategies> {
                 entrance_query_index,
                 template_switch_primary,
                 template_switch_secondary,
+                template_switch_direction,
                 primary_index,
                 anti_primary_gap: successor_anti_primary_gap,
             },
@@ -652,6 +672,69 @@ impl<Strategies: AlignmentStrategySelector> Node<Strategies> {
         ))
     }
 
+    /// Generates the target node by clipping all characters after this node.
+    ///
+    /// This is how alignments end in modes other than [global](crate::a_star_aligner::alignment_geometry::AlignmentMode::Global).
+    /// Returns `None` if this node is not a primary node outside of a flank, if the [mode](crate::a_star_aligner::alignment_geometry::AlignmentMode::can_clip_end) does not allow to end here,
+    /// or if there is nothing to clip.
+    fn generate_primary_clip_successor<
+        SubsequenceType: GenomeSequence<Strategies::Alphabet, SubsequenceType> + ?Sized,
+    >(
+        &self,
+        context: &Context<SubsequenceType, Strategies>,
+    ) -> Option<Self> {
+        let identifier @ (Identifier::Primary {
+            reference_index,
+            query_index,
+            flank_index: 0,
+            ..
+        }
+        | Identifier::PrimaryReentry {
+            reference_index,
+            query_index,
+            flank_index: 0,
+            ..
+        }) = self.node_data.identifier
+        else {
+            return None;
+        };
+
+        let reference_length = context
+            .range
+            .reference_limit()
+            .checked_sub(reference_index)?;
+        let query_length = context.range.query_limit().checked_sub(query_index)?;
+        if (reference_length == 0 && query_length == 0)
+            || !context
+                .mode
+                .can_clip_end(&context.range, reference_index, query_index)
+        {
+            return None;
+        }
+
+        let alignment_type = AlignmentType::PrimaryClip {
+            reference_length,
+            query_length,
+        };
+        let mut successor = self.generate_successor(
+            Identifier::Primary {
+                reference_index: context.range.reference_limit(),
+                query_index: context.range.query_limit(),
+                gap_type: GapType::None,
+                flank_index: 0,
+                data: <<Strategies as AlignmentStrategySelector>::PrimaryMatch as PrimaryMatchStrategy<
+                <Strategies as AlignmentStrategySelector>::Cost,
+            >>::generate_successor_identifier_primary_extra_data(identifier, alignment_type, context),
+            },
+            Strategies::Cost::zero(),
+            alignment_type,
+            context,
+        );
+        // The target has nothing left to align, so its lower bound must not be inherited from this node.
+        successor.node_data.a_star_lower_bound = Strategies::Cost::zero();
+        Some(successor)
+    }
+
     fn generate_successor<
         SubsequenceType: GenomeSequence<Strategies::Alphabet, SubsequenceType> + ?Sized,
     >(
@@ -665,12 +748,17 @@ impl<Strategies: AlignmentStrategySelector> Node<Strategies> {
         alignment_type: AlignmentType,
         context: &Context<SubsequenceType, Strategies>,
     ) -> Self {
+        let mut node_data =
+            self.node_data
+                .generate_successor(identifier, cost_increment, alignment_type);
+        // With negative costs, the lower bound inherited from the predecessor decreases along negative edges,
+        // so it may get far below the lower bound derived from the remaining characters.
+        node_data.a_star_lower_bound = node_data
+            .a_star_lower_bound
+            .max(context.negative_cost_lower_bound(&identifier));
+
         Self {
-            node_data: self.node_data.generate_successor(
-                identifier,
-                cost_increment,
-                alignment_type,
-            ),
+            node_data,
             strategies: self
                 .strategies
                 .generate_successor(identifier, alignment_type, context),
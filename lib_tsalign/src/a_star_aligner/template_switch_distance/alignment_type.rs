@@ -1,6 +1,6 @@
 use crate::a_star_aligner::alignment_result::IAlignmentType;
 
-use super::identifier::{TemplateSwitchPrimary, TemplateSwitchSecondary};
+use super::identifier::{TemplateSwitchDirection, TemplateSwitchPrimary, TemplateSwitchSecondary};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -41,6 +41,11 @@ pub enum AlignmentType {
     TemplateSwitchEntrance {
         primary: TemplateSwitchPrimary,
         secondary: TemplateSwitchSecondary,
+        /// The direction in which the secondary is traversed.
+        ///
+        /// Alignments serialised before forward template switches existed contain only reverse template switches.
+        #[cfg_attr(feature = "serde", serde(default))]
+        direction: TemplateSwitchDirection,
         first_offset: isize,
     },
     /// A template switch exit.
@@ -66,6 +71,13 @@ pub enum AlignmentType {
         delta_reference: isize,
         delta_query: isize,
     },
+    /// Characters of the alignment range that are not aligned, because they are before the start or after the end of a non-global alignment.
+    ///
+    /// These are displayed as soft clips.
+    PrimaryClip {
+        reference_length: usize,
+        query_length: usize,
+    },
 }
 
 impl IAlignmentType for AlignmentType {
@@ -88,7 +100,8 @@ impl IAlignmentType for AlignmentType {
             | Self::PrimaryReentry => true,
             Self::TemplateSwitchEntrance { .. }
             | Self::TemplateSwitchExit { .. }
-            | Self::PrimaryShortcut { .. } => false,
+            | Self::PrimaryShortcut { .. }
+            | Self::PrimaryClip { .. } => false,
         }
     }
 
@@ -118,16 +131,19 @@ impl IAlignmentType for AlignmentType {
                 Self::TemplateSwitchEntrance {
                     primary: primary_a,
                     secondary: secondary_a,
+                    direction: direction_a,
                     ..
                 },
                 Self::TemplateSwitchEntrance {
                     primary: primary_b,
                     secondary: secondary_b,
+                    direction: direction_b,
                     ..
                 },
-            ) => primary_a == primary_b && secondary_a == secondary_b,
+            ) => primary_a == primary_b && secondary_a == secondary_b && direction_a == direction_b,
             (Self::TemplateSwitchExit { .. }, Self::TemplateSwitchExit { .. }) => true,
             (Self::PrimaryShortcut { .. }, Self::PrimaryShortcut { .. }) => false,
+            (Self::PrimaryClip { .. }, Self::PrimaryClip { .. }) => false,
             (a, b) => a == b,
         }
     }
@@ -156,10 +172,12 @@ impl AlignmentType {
             Self::TemplateSwitchEntrance {
                 primary,
                 secondary,
+                direction,
                 first_offset,
             } => Self::TemplateSwitchEntrance {
                 primary: primary.inverted(),
                 secondary: secondary.inverted(),
+                direction: *direction,
                 first_offset: *first_offset,
             },
             Self::PrimaryShortcut {
@@ -169,6 +187,13 @@ impl AlignmentType {
                 delta_reference: *delta_query,
                 delta_query: *delta_reference,
             },
+            Self::PrimaryClip {
+                reference_length,
+                query_length,
+            } => Self::PrimaryClip {
+                reference_length: *query_length,
+                query_length: *reference_length,
+            },
 
             symmetric @ (Self::PrimarySubstitution
             | Self::PrimaryMatch
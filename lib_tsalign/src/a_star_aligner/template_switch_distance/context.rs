@@ -1,18 +1,26 @@
 use std::fmt::Display;
+use std::time::Instant;
 
 use compact_genome::interface::alphabet::AlphabetCharacter;
 use compact_genome::interface::sequence::GenomeSequence;
 use extend_map::ExtendMap;
+use generic_a_star::cancellation::CancellationToken;
+#[cfg(feature = "serde")]
+use generic_a_star::checkpoint::CheckpointContext;
 use generic_a_star::reset::Reset;
-use generic_a_star::{AStarBuffers, AStarContext};
+use generic_a_star::{AStarBuffers, AStarContext, AStarNode};
 use num_traits::{Bounded, Zero};
 
+use crate::a_star_aligner::alignment_geometry::AlignmentMode;
 use crate::a_star_aligner::template_switch_distance::Node;
 use crate::a_star_aligner::template_switch_distance::strategies::primary_range::PrimaryRangeStrategy;
 use crate::a_star_aligner::{AlignmentContext, AlignmentRange};
 use crate::config::TemplateSwitchConfig;
+use crate::costs::remaining_characters_lower_bound;
 
-use super::identifier::{GapType, TemplateSwitchPrimary, TemplateSwitchSecondary};
+use super::identifier::{
+    GapType, TemplateSwitchDirection, TemplateSwitchPrimary, TemplateSwitchSecondary,
+};
 use super::strategies::chaining::ChainingStrategy;
 use super::strategies::primary_match::PrimaryMatchStrategy;
 use super::strategies::secondary_deletion::SecondaryDeletionStrategy;
@@ -34,22 +42,23 @@ pub struct Context<
     pub query_name: String,
 
     pub range: AlignmentRange,
+    pub mode: AlignmentMode,
 
     pub config: TemplateSwitchConfig<Strategies::Alphabet, Strategies::Cost>,
 
-    #[allow(clippy::type_complexity)]
-    pub a_star_buffers: AStarBuffers<
-        Identifier<
-            <<Strategies as AlignmentStrategySelector>::PrimaryMatch as PrimaryMatchStrategy<
-                <Strategies as AlignmentStrategySelector>::Cost,
-            >>::IdentifierPrimaryExtraData,
-        >,
-        Node<Strategies>,
-    >,
+    pub a_star_buffers: AStarBuffers<Node<Strategies>>,
     pub memory: Memory<Strategies>,
 
     cost_limit: Option<Strategies::Cost>,
     memory_limit: Option<usize>,
+    deadline: Option<Instant>,
+    cancellation_token: Option<CancellationToken>,
+
+    /// The minimum cost of a match or substitution outside of template switches, or zero if no such step has a negative cost.
+    min_negative_diagonal_cost: Strategies::Cost,
+    /// The minimum cost of a gap character outside of template switches, or zero if no such step has a negative cost.
+    min_negative_gap_cost: Strategies::Cost,
+    max_template_switch_rewind: usize,
 }
 
 pub struct Memory<Strategies: AlignmentStrategySelector> {
@@ -76,24 +85,137 @@ impl<
         reference_name: &str,
         query_name: &str,
         range: Option<AlignmentRange>,
+        mode: AlignmentMode,
         config: TemplateSwitchConfig<Strategies::Alphabet, Strategies::Cost>,
         memory: Memory<Strategies>,
         cost_limit: Option<Strategies::Cost>,
         memory_limit: Option<usize>,
+        deadline: Option<Instant>,
+        cancellation_token: Option<CancellationToken>,
     ) -> Self {
+        debug_assert!(config.check_negative_costs().is_ok());
+
         Self {
+            min_negative_diagonal_cost: config.min_negative_diagonal_cost(),
+            min_negative_gap_cost: config.min_negative_gap_cost(),
+            max_template_switch_rewind: config.max_template_switch_rewind(),
             reference,
             query,
             reference_name: reference_name.to_owned(),
             query_name: query_name.to_owned(),
             range: range
                 .unwrap_or_else(|| AlignmentRange::new_complete(reference.len(), query.len())),
+            mode,
             config,
             a_star_buffers: Default::default(),
             memory,
             cost_limit,
             memory_limit,
+            deadline,
+            cancellation_token,
+        }
+    }
+
+    /// Returns true if some alignment steps have negative costs.
+    ///
+    /// Then the search is not [label setting](AStarContext::is_label_setting).
+    pub fn has_negative_costs(&self) -> bool {
+        self.min_negative_diagonal_cost < Strategies::Cost::zero()
+            || self.min_negative_gap_cost < Strategies::Cost::zero()
+    }
+
+    /// Returns a lower bound on the cost of reaching the target from a node with the given identifier that accounts for negative costs.
+    ///
+    /// The remaining reference and query characters are bounded by [`remaining_characters_lower_bound`].
+    /// Inside a template switch, the characters of the anti-primary sequence are counted from its entrance index, plus the number of characters by which the template switch may rewind.
+    /// Further template switches cost at least as much as they rewind, which is checked by [`TemplateSwitchConfig::check_negative_costs`].
+    ///
+    /// Returns zero if no alignment step has a negative cost.
+    pub fn negative_cost_lower_bound<PrimaryExtraData>(
+        &self,
+        identifier: &Identifier<PrimaryExtraData>,
+    ) -> Strategies::Cost {
+        if !self.has_negative_costs() {
+            return Strategies::Cost::zero();
         }
+
+        let remaining_characters = |reference_index: usize, query_index: usize| {
+            (
+                self.range.reference_limit().saturating_sub(reference_index),
+                self.range.query_limit().saturating_sub(query_index),
+            )
+        };
+        // The anti-primary sequence may be rewound when exiting the template switch.
+        let template_switch_remaining_characters =
+            |template_switch_primary, reference_index, query_index| {
+                let (remaining_reference, remaining_query) =
+                    remaining_characters(reference_index, query_index);
+                match template_switch_primary {
+                    TemplateSwitchPrimary::Reference => (
+                        remaining_reference,
+                        remaining_query + self.max_template_switch_rewind,
+                    ),
+                    TemplateSwitchPrimary::Query => (
+                        remaining_reference + self.max_template_switch_rewind,
+                        remaining_query,
+                    ),
+                }
+            };
+
+        let (remaining_reference, remaining_query) = match *identifier {
+            Identifier::Primary {
+                reference_index,
+                query_index,
+                ..
+            }
+            | Identifier::PrimaryReentry {
+                reference_index,
+                query_index,
+                ..
+            } => remaining_characters(reference_index, query_index),
+            Identifier::TemplateSwitchEntrance {
+                entrance_reference_index,
+                entrance_query_index,
+                template_switch_primary,
+                ..
+            } => template_switch_remaining_characters(
+                template_switch_primary,
+                entrance_reference_index,
+                entrance_query_index,
+            ),
+            Identifier::Secondary {
+                entrance_reference_index,
+                entrance_query_index,
+                template_switch_primary,
+                primary_index,
+                ..
+            }
+            | Identifier::TemplateSwitchExit {
+                entrance_reference_index,
+                entrance_query_index,
+                template_switch_primary,
+                primary_index,
+                ..
+            } => match template_switch_primary {
+                TemplateSwitchPrimary::Reference => template_switch_remaining_characters(
+                    template_switch_primary,
+                    primary_index,
+                    entrance_query_index,
+                ),
+                TemplateSwitchPrimary::Query => template_switch_remaining_characters(
+                    template_switch_primary,
+                    entrance_reference_index,
+                    primary_index,
+                ),
+            },
+        };
+
+        remaining_characters_lower_bound(
+            self.min_negative_diagonal_cost,
+            self.min_negative_gap_cost,
+            remaining_reference,
+            remaining_query,
+        )
     }
 }
 
@@ -105,18 +227,59 @@ impl<
     type Node = Node<Strategies>;
 
     fn create_root(&self) -> Self::Node {
+        let identifier = Identifier::new_primary(
+            self.range.reference_offset(),
+            self.range.query_offset(),
+            0,
+            GapType::None,
+            <<Strategies as AlignmentStrategySelector>::PrimaryMatch as PrimaryMatchStrategy<
+                <Strategies as AlignmentStrategySelector>::Cost,
+            >>::create_root_identifier_primary_extra_data(self),
+        );
+
         Self::Node {
             node_data: NodeData {
-                identifier: Identifier::new_primary(self.range.reference_offset(), self.range.query_offset(), 0, GapType::None, <<Strategies as AlignmentStrategySelector>::PrimaryMatch as PrimaryMatchStrategy<<Strategies as AlignmentStrategySelector>::Cost>>::create_root_identifier_primary_extra_data(self)),
+                identifier,
                 predecessor: None,
                 predecessor_edge_type: AlignmentType::Root,
                 cost: Strategies::Cost::zero(),
-                a_star_lower_bound: Strategies::Cost::zero(),
+                a_star_lower_bound: self.negative_cost_lower_bound(&identifier),
             },
             strategies: AlignmentStrategiesNodeMemory::create_root(self),
         }
     }
 
+    /// In modes other than [global](AlignmentMode::Global), the primary nodes where the alignment may start are roots, clipping all characters before them.
+    /// The roots are created row by row, starting from the root at the offset of the range (see [`AlignmentMode::next_root`]).
+    /// They all get the lower bound of the first root, such that they are created in ascending order of their cost plus lower bound.
+    ///
+    /// In [local](AlignmentMode::Local) mode without negative costs, clipping everything is optimal, so then no further roots are created.
+    fn next_root(&self, root: &Self::Node) -> Option<Self::Node> {
+        if self.mode == AlignmentMode::Local && !self.has_negative_costs() {
+            return None;
+        }
+
+        let Identifier::Primary {
+            reference_index,
+            query_index,
+            ..
+        } = root.node_data.identifier
+        else {
+            unreachable!("Roots are primary nodes.")
+        };
+        let (reference_index, query_index) =
+            self.mode
+                .next_root(&self.range, reference_index, query_index)?;
+
+        let mut next_root = Node::new_root_at(reference_index, query_index, self);
+        next_root.node_data.predecessor_edge_type = AlignmentType::PrimaryClip {
+            reference_length: reference_index - self.range.reference_offset(),
+            query_length: query_index - self.range.query_offset(),
+        };
+        next_root.node_data.a_star_lower_bound = root.node_data.a_star_lower_bound;
+        Some(next_root)
+    }
+
     fn generate_successors(
         &mut self,
         node: &Self::Node,
@@ -349,9 +512,10 @@ impl<
             Identifier::TemplateSwitchEntrance {
                 entrance_reference_index,
                 entrance_query_index,
+                template_switch_primary,
                 template_switch_secondary,
+                template_switch_direction,
                 template_switch_first_offset,
-                ..
             } => {
                 debug_assert!(
                     node.strategies
@@ -367,9 +531,39 @@ impl<
                 };
                 let secondary_index =
                     secondary_entrance_index as isize + template_switch_first_offset;
-
-                if template_switch_first_offset >= 0 && secondary_index < secondary_length as isize
-                {
+                let min_length = self.config.min_length as isize;
+                let secondary_length = secondary_length as isize;
+
+                // The secondary root needs at least `min_length` characters in the direction of the template switch.
+                let (can_increase_offset, can_decrease_offset, can_enter_secondary) =
+                    match template_switch_direction {
+                        TemplateSwitchDirection::Reverse => (
+                            secondary_index < secondary_length,
+                            secondary_index > min_length,
+                            secondary_index >= min_length && secondary_index <= secondary_length,
+                        ),
+                        TemplateSwitchDirection::Forward => (
+                            secondary_index + min_length < secondary_length,
+                            secondary_index > 0,
+                            secondary_index >= 0
+                                && secondary_index + min_length <= secondary_length
+                                // Without an offset, a forward template switch within the same sequence
+                                // would align the primary against itself.
+                                && !(template_switch_first_offset == 0
+                                    && matches!(
+                                        (template_switch_primary, template_switch_secondary),
+                                        (
+                                            TemplateSwitchPrimary::Reference,
+                                            TemplateSwitchSecondary::Reference
+                                        ) | (
+                                            TemplateSwitchPrimary::Query,
+                                            TemplateSwitchSecondary::Query
+                                        )
+                                    )),
+                        ),
+                    };
+
+                if template_switch_first_offset >= 0 && can_increase_offset {
                     let new_cost = config
                         .offset_costs
                         .evaluate(&(&template_switch_first_offset + 1));
@@ -389,9 +583,7 @@ impl<
                     }
                 }
 
-                if template_switch_first_offset <= 0
-                    && secondary_index > self.config.min_length as isize
-                {
+                if template_switch_first_offset <= 0 && can_decrease_offset {
                     let new_cost = config
                         .offset_costs
                         .evaluate(&(&template_switch_first_offset - 1));
@@ -411,9 +603,7 @@ impl<
                     }
                 }
 
-                if secondary_index >= self.config.min_length as isize
-                    && secondary_index <= secondary_length as isize
-                {
+                if can_enter_secondary {
                     // Temporarily unpack opened_nodes_output because it borrows self,
                     // but generating the secondary root node wants to borrow self as mutable.
                     let opened_nodes_direct_output = opened_nodes_output.into_inner();
@@ -432,6 +622,7 @@ impl<
             Identifier::Secondary {
                 template_switch_primary,
                 template_switch_secondary,
+                template_switch_direction,
                 length,
                 primary_index,
                 secondary_index,
@@ -452,43 +643,51 @@ impl<
                     TemplateSwitchSecondary::Query => self.query,
                 };
 
+                // The next character of the secondary, as seen from the primary.
+                let next_secondary_character = match template_switch_direction {
+                    TemplateSwitchDirection::Reverse => {
+                        if secondary_index > secondary_sequence.len() {
+                            panic!("Secondary index out of bounds for node {node}");
+                        }
+
+                        (secondary_index > 0)
+                            .then(|| secondary_sequence[secondary_index - 1].complement())
+                    }
+                    TemplateSwitchDirection::Forward => (secondary_index
+                        < secondary_sequence.len())
+                    .then(|| secondary_sequence[secondary_index].clone()),
+                };
+
                 // Only generate secondary successors if they can ever exit the template switch based on their length.
                 let min_length_cost = config.length_costs.min(length..).unwrap();
                 if min_length_cost != Strategies::Cost::max_value() {
-                    if primary_index < primary_sequence.len() && secondary_index > 0 {
-                        // Diagonal characters
-                        let p = primary_sequence[primary_index].clone();
-                        let s = secondary_sequence[secondary_index - 1].complement();
+                    if let Some(s) = next_secondary_character {
+                        if primary_index < primary_sequence.len() {
+                            // Diagonal characters
+                            let p = primary_sequence[primary_index].clone();
 
-                        opened_nodes_output.extend(
-                            node.generate_secondary_diagonal_successor(
-                                config
-                                    .secondary_edit_costs
-                                    .match_or_substitution_cost(p.clone(), s.clone()),
-                                p == s,
-                                self,
-                            ),
-                        );
-                    }
-
-                    if secondary_index > 0
-                        && Strategies::SecondaryDeletion::allow_secondary_deletions()
-                    {
-                        if secondary_index > secondary_sequence.len() {
-                            panic!("Secondary index out of bounds for node {node}");
+                            opened_nodes_output.extend(
+                                node.generate_secondary_diagonal_successor(
+                                    config
+                                        .secondary_edit_costs
+                                        .match_or_substitution_cost(p.clone(), s.clone()),
+                                    p == s,
+                                    self,
+                                ),
+                            );
                         }
 
-                        // Deleted character
-                        let s = secondary_sequence[secondary_index - 1].complement();
-
-                        opened_nodes_output.extend(
-                            node.generate_secondary_deletion_successor(
-                                config
-                                    .secondary_edit_costs
-                                    .gap_costs(s, gap_type != GapType::Deletion),
-                                self,
-                            ),
-                        );
+                        if Strategies::SecondaryDeletion::allow_secondary_deletions() {
+                            // Deleted character
+                            opened_nodes_output.extend(
+                                node.generate_secondary_deletion_successor(
+                                    config
+                                        .secondary_edit_costs
+                                        .gap_costs(s, gap_type != GapType::Deletion),
+                                    self,
+                                ),
+                            );
+                        }
                     }
 
                     if primary_index < primary_sequence.len() {
@@ -593,6 +792,10 @@ impl<
             }
         }
 
+        if self.mode.is_clipping() {
+            opened_nodes_output.extend(node.generate_primary_clip_successor(self));
+        }
+
         // Add additional successors through strategies.
         <<Strategies as AlignmentStrategySelector>::Shortcut as ShortcutStrategy<
             <Strategies as AlignmentStrategySelector>::Cost,
@@ -620,6 +823,50 @@ impl<
     fn memory_limit(&self) -> Option<usize> {
         self.memory_limit
     }
+
+    fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    fn cancellation_token(&self) -> Option<&CancellationToken> {
+        self.cancellation_token.as_ref()
+    }
+
+    /// With negative costs, a node may be reached with a lower cost after it was closed.
+    fn is_label_setting(&self) -> bool {
+        !self.has_negative_costs()
+    }
+
+    fn heap_size(&self) -> usize {
+        self.a_star_buffers.heap_size()
+            + <Strategies::TemplateSwitchMinLength as TemplateSwitchMinLengthStrategy<
+                Strategies::Cost,
+            >>::memory_heap_size(&self.memory.template_switch_min_length)
+            + <Strategies::Chaining as ChainingStrategy<Strategies::Cost>>::memory_heap_size(
+                &self.memory.chaining,
+            )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<
+    SubsequenceType: GenomeSequence<Strategies::Alphabet, SubsequenceType> + ?Sized,
+    Strategies: AlignmentStrategySelector,
+> CheckpointContext for Context<'_, '_, SubsequenceType, Strategies>
+{
+    /// Only the memory of the template switch min length strategy is built up during the search.
+    /// The memory of the other strategies is precomputed when constructing the context.
+    type Checkpoint = <Strategies::TemplateSwitchMinLength as TemplateSwitchMinLengthStrategy<
+        Strategies::Cost,
+    >>::Memory;
+
+    fn checkpoint(&self) -> &Self::Checkpoint {
+        &self.memory.template_switch_min_length
+    }
+
+    fn restore_checkpoint(&mut self, checkpoint: Self::Checkpoint) {
+        self.memory.template_switch_min_length = checkpoint;
+    }
 }
 
 fn generate_output_mapper_function<
@@ -685,6 +932,28 @@ impl<
     fn range(&self) -> &AlignmentRange {
         &self.range
     }
+
+    fn partial_alignment_end(
+        &self,
+        identifier: &<Self::Node as AStarNode>::Identifier,
+    ) -> Option<(usize, usize)> {
+        match *identifier {
+            Identifier::Primary {
+                reference_index,
+                query_index,
+                ..
+            }
+            | Identifier::PrimaryReentry {
+                reference_index,
+                query_index,
+                ..
+            } => Some((reference_index, query_index)),
+            // Alignments cannot end inside a template switch.
+            Identifier::TemplateSwitchEntrance { .. }
+            | Identifier::Secondary { .. }
+            | Identifier::TemplateSwitchExit { .. } => None,
+        }
+    }
 }
 
 impl<Strategies: AlignmentStrategySelector> Display for AlignmentStrategiesNodeMemory<Strategies> {
@@ -1,6 +1,9 @@
 use std::fmt::{Display, Formatter, Result};
 
-use super::{AlignmentType, GapType, Identifier, TemplateSwitchPrimary, TemplateSwitchSecondary};
+use super::{
+    AlignmentType, GapType, Identifier, TemplateSwitchDirection, TemplateSwitchPrimary,
+    TemplateSwitchSecondary,
+};
 
 impl Display for AlignmentType {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
@@ -18,8 +21,15 @@ impl Display for AlignmentType {
             Self::TemplateSwitchEntrance {
                 primary,
                 secondary,
+                direction: TemplateSwitchDirection::Reverse,
                 first_offset,
             } => write!(f, "[TS{primary}{secondary}{first_offset}:"),
+            Self::TemplateSwitchEntrance {
+                primary,
+                secondary,
+                direction: TemplateSwitchDirection::Forward,
+                first_offset,
+            } => write!(f, "[FTS{primary}{secondary}{first_offset}:"),
             Self::TemplateSwitchExit { anti_primary_gap } => write!(f, ":{anti_primary_gap}]"),
             Self::Root => Ok(()),
             Self::SecondaryRoot => Ok(()),
@@ -28,6 +38,10 @@ impl Display for AlignmentType {
                 delta_reference,
                 delta_query,
             } => write!(f, "[PS:R{delta_reference}Q{delta_query}]"),
+            Self::PrimaryClip {
+                reference_length,
+                query_length,
+            } => write!(f, "[SC:R{reference_length}Q{query_length}]"),
         }
     }
 }
@@ -60,6 +74,15 @@ impl Display for TemplateSwitchSecondary {
     }
 }
 
+impl Display for TemplateSwitchDirection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Reverse => write!(f, "Rev"),
+            Self::Forward => write!(f, "Fwd"),
+        }
+    }
+}
+
 impl<PrimaryExtraData> Display for Identifier<PrimaryExtraData> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         match self {
@@ -90,11 +113,12 @@ impl<PrimaryExtraData> Display for Identifier<PrimaryExtraData> {
                 entrance_query_index,
                 template_switch_primary,
                 template_switch_secondary,
+                template_switch_direction,
                 template_switch_first_offset,
             } => {
                 write!(
                     f,
-                    "TemplateSwitchEntrance({entrance_reference_index}R, {entrance_query_index}Q, {template_switch_primary}P, {template_switch_secondary}S, {template_switch_first_offset}O)",
+                    "TemplateSwitchEntrance({entrance_reference_index}R, {entrance_query_index}Q, {template_switch_primary}P, {template_switch_secondary}S, {template_switch_direction}, {template_switch_first_offset}O)",
                 )
             }
 
@@ -104,13 +128,14 @@ impl<PrimaryExtraData> Display for Identifier<PrimaryExtraData> {
                 entrance_query_index,
                 template_switch_primary,
                 template_switch_secondary,
+                template_switch_direction,
                 length,
                 primary_index,
                 secondary_index,
                 gap_type,
             } => write!(
                 f,
-                "Secondary({}R, {}Q, {}L, {}P, {}S, {}, {}, {})",
+                "Secondary({}R, {}Q, {}L, {}P, {}S, {}, {}, {}, {})",
                 entrance_reference_index,
                 entrance_query_index,
                 length,
@@ -118,6 +143,7 @@ impl<PrimaryExtraData> Display for Identifier<PrimaryExtraData> {
                 secondary_index,
                 template_switch_primary,
                 template_switch_secondary,
+                template_switch_direction,
                 gap_type
             ),
 
@@ -127,17 +153,19 @@ impl<PrimaryExtraData> Display for Identifier<PrimaryExtraData> {
                 entrance_query_index,
                 template_switch_primary,
                 template_switch_secondary,
+                template_switch_direction,
                 primary_index,
                 anti_primary_gap,
             } => write!(
                 f,
-                "TemplateSwitchExit({}R, {}Q, {}P, {}G, {}, {})",
+                "TemplateSwitchExit({}R, {}Q, {}P, {}G, {}, {}, {})",
                 entrance_reference_index,
                 entrance_query_index,
                 primary_index,
                 anti_primary_gap,
                 template_switch_primary,
-                template_switch_secondary
+                template_switch_secondary,
+                template_switch_direction
             ),
         }
     }
@@ -1,4 +1,5 @@
 use compact_genome::interface::sequence::GenomeSequence;
+use seed_chain::seed::AnchorSequence;
 
 use super::{
     AlignmentType, Context,
@@ -6,6 +7,7 @@ use super::{
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Identifier<PrimaryExtraData> {
     Primary {
         reference_index: usize,
@@ -28,6 +30,7 @@ pub enum Identifier<PrimaryExtraData> {
         entrance_query_index: usize,
         template_switch_primary: TemplateSwitchPrimary,
         template_switch_secondary: TemplateSwitchSecondary,
+        template_switch_direction: TemplateSwitchDirection,
         template_switch_first_offset: isize,
     },
     Secondary {
@@ -35,6 +38,7 @@ pub enum Identifier<PrimaryExtraData> {
         entrance_query_index: usize,
         template_switch_primary: TemplateSwitchPrimary,
         template_switch_secondary: TemplateSwitchSecondary,
+        template_switch_direction: TemplateSwitchDirection,
         length: usize,
         /// The index that does not jump.
         primary_index: usize,
@@ -47,6 +51,7 @@ pub enum Identifier<PrimaryExtraData> {
         entrance_query_index: usize,
         template_switch_primary: TemplateSwitchPrimary,
         template_switch_secondary: TemplateSwitchSecondary,
+        template_switch_direction: TemplateSwitchDirection,
         /// The index that does not jump.
         primary_index: usize,
         anti_primary_gap: isize,
@@ -54,6 +59,7 @@ pub enum Identifier<PrimaryExtraData> {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GapType {
     Insertion,
     Deletion,
@@ -76,6 +82,21 @@ pub enum TemplateSwitchSecondary {
     Query,
 }
 
+/// The direction in which the secondary sequence is aligned against the primary sequence.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TemplateSwitchDirection {
+    /// The primary is aligned against the reverse complement of the secondary, i.e. the secondary index decreases.
+    ///
+    /// This is the classic template switch that creates inverted repeats.
+    #[default]
+    Reverse,
+    /// The primary is aligned against the secondary on the same strand, i.e. the secondary index increases.
+    ///
+    /// This creates direct repeats, such as tandem duplications, or deletions between direct repeats.
+    Forward,
+}
+
 impl<PrimaryExtraData> Identifier<PrimaryExtraData> {
     pub const fn new_primary(
         reference_index: usize,
@@ -253,15 +274,23 @@ impl<PrimaryExtraData> Identifier<PrimaryExtraData> {
                     TemplateSwitchSecondary::Reference,
                     TemplateSwitchSecondary::Query,
                 ])
-                .map(
+                .flat_map(
                     move |(template_switch_primary, template_switch_secondary)| {
-                        Identifier::TemplateSwitchEntrance {
-                            entrance_reference_index,
-                            entrance_query_index,
-                            template_switch_primary,
-                            template_switch_secondary,
-                            template_switch_first_offset,
-                        }
+                        [
+                            TemplateSwitchDirection::Reverse,
+                            TemplateSwitchDirection::Forward,
+                        ]
+                        .into_iter()
+                        .map(
+                            move |template_switch_direction| Identifier::TemplateSwitchEntrance {
+                                entrance_reference_index,
+                                entrance_query_index,
+                                template_switch_primary,
+                                template_switch_secondary,
+                                template_switch_direction,
+                                template_switch_first_offset,
+                            },
+                        )
                     },
                 )
             }
@@ -279,6 +308,7 @@ impl<PrimaryExtraData> Identifier<PrimaryExtraData> {
                 entrance_query_index,
                 template_switch_primary,
                 template_switch_secondary,
+                template_switch_direction,
                 length,
                 primary_index,
                 secondary_index,
@@ -288,9 +318,10 @@ impl<PrimaryExtraData> Identifier<PrimaryExtraData> {
                 entrance_query_index,
                 template_switch_primary,
                 template_switch_secondary,
+                template_switch_direction,
                 length: length + 1,
                 primary_index: primary_index + 1,
-                secondary_index: secondary_index - 1,
+                secondary_index: template_switch_direction.advance_secondary_index(secondary_index),
                 gap_type: GapType::None,
             },
             other => unreachable!(
@@ -307,6 +338,7 @@ impl<PrimaryExtraData> Identifier<PrimaryExtraData> {
                 entrance_query_index,
                 template_switch_primary,
                 template_switch_secondary,
+                template_switch_direction,
                 length,
                 primary_index,
                 secondary_index,
@@ -316,9 +348,10 @@ impl<PrimaryExtraData> Identifier<PrimaryExtraData> {
                 entrance_query_index,
                 template_switch_primary,
                 template_switch_secondary,
+                template_switch_direction,
                 length,
                 primary_index,
-                secondary_index: secondary_index - 1,
+                secondary_index: template_switch_direction.advance_secondary_index(secondary_index),
                 gap_type: GapType::Deletion,
             },
             other => unreachable!(
@@ -335,6 +368,7 @@ impl<PrimaryExtraData> Identifier<PrimaryExtraData> {
                 entrance_query_index,
                 template_switch_primary,
                 template_switch_secondary,
+                template_switch_direction,
                 length,
                 primary_index,
                 secondary_index,
@@ -344,6 +378,7 @@ impl<PrimaryExtraData> Identifier<PrimaryExtraData> {
                 entrance_query_index,
                 template_switch_primary,
                 template_switch_secondary,
+                template_switch_direction,
                 length: length + 1,
                 primary_index: primary_index + 1,
                 secondary_index,
@@ -392,3 +427,31 @@ impl TemplateSwitchSecondary {
         }
     }
 }
+
+impl From<TemplateSwitchPrimary> for AnchorSequence {
+    fn from(template_switch_primary: TemplateSwitchPrimary) -> Self {
+        match template_switch_primary {
+            TemplateSwitchPrimary::Reference => Self::Reference,
+            TemplateSwitchPrimary::Query => Self::Query,
+        }
+    }
+}
+
+impl From<TemplateSwitchSecondary> for AnchorSequence {
+    fn from(template_switch_secondary: TemplateSwitchSecondary) -> Self {
+        match template_switch_secondary {
+            TemplateSwitchSecondary::Reference => Self::Reference,
+            TemplateSwitchSecondary::Query => Self::Query,
+        }
+    }
+}
+
+impl TemplateSwitchDirection {
+    /// Returns the secondary index after consuming one character of the secondary sequence.
+    pub fn advance_secondary_index(&self, secondary_index: usize) -> usize {
+        match self {
+            Self::Reverse => secondary_index - 1,
+            Self::Forward => secondary_index + 1,
+        }
+    }
+}
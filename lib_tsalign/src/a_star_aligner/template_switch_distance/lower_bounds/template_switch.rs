@@ -8,11 +8,12 @@ use compact_genome::{
     implementation::vec_sequence::VectorGenome,
     interface::{alphabet::Alphabet, sequence::GenomeSequence},
 };
-use generic_a_star::{AStar, AStarNode, AStarResult, cost::AStarCost};
+use generic_a_star::{AStar, AStarNode, AStarResult, cost::AStarCost, memory};
 use log::{debug, info, trace};
 
 use crate::{
     a_star_aligner::{
+        alignment_geometry::AlignmentMode,
         alignment_result::IAlignmentType,
         template_switch_distance::{
             Context, Identifier, Node,
@@ -92,6 +93,7 @@ impl<Cost: AStarCost> TemplateSwitchLowerBoundMatrix<Cost> {
                     "",
                     "",
                     None,
+                    AlignmentMode::Global,
                     lower_bound_config.clone(),
                     Memory {
                         template_switch_min_length: (),
@@ -102,6 +104,8 @@ impl<Cost: AStarCost> TemplateSwitchLowerBoundMatrix<Cost> {
                     },
                     None,
                     None,
+                    None,
+                    None,
                 ),
             );
             let root_xy = genome_length / 2;
@@ -203,7 +207,9 @@ impl<Cost: AStarCost> TemplateSwitchLowerBoundMatrix<Cost> {
                         }
                     }
                 }) {
-                    AStarResult::FoundTarget { identifier, cost } => {
+                    AStarResult::FoundTarget {
+                        identifier, cost, ..
+                    } => {
                         trace!("Search termianted with target {identifier} at cost {cost}");
 
                         if let Identifier::PrimaryReentry { .. } = identifier {
@@ -243,7 +249,10 @@ impl<Cost: AStarCost> TemplateSwitchLowerBoundMatrix<Cost> {
                         false
                     }
                     AStarResult::ExceededCostLimit { .. }
-                    | AStarResult::ExceededMemoryLimit { .. } => unreachable!("No limits set"),
+                    | AStarResult::ExceededMemoryLimit { .. }
+                    | AStarResult::ExceededTimeLimit { .. }
+                    | AStarResult::Cancelled { .. } => unreachable!("No limits set"),
+                    AStarResult::Stopped { .. } => unreachable!("No observer set"),
                 };
 
                 if has_target {
@@ -281,6 +290,11 @@ impl<Cost: AStarCost> TemplateSwitchLowerBoundMatrix<Cost> {
     pub fn iter(&self) -> impl Iterator<Item = &TSLBMatrixEntry<Cost>> {
         self.entries.iter()
     }
+
+    /// Returns the number of bytes used by the matrix on the heap.
+    pub fn heap_size(&self) -> usize {
+        memory::vec_heap_size::<TSLBMatrixEntry<Cost>>(self.entries.capacity())
+    }
 }
 
 impl<Cost> TSLBMatrixEntry<Cost> {
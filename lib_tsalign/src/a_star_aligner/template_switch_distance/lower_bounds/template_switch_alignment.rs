@@ -8,12 +8,13 @@ use compact_genome::{
     implementation::vec_sequence::VectorGenome,
     interface::{alphabet::Alphabet, sequence::GenomeSequence},
 };
-use generic_a_star::{AStar, AStarNode, AStarResult, cost::AStarCost};
+use generic_a_star::{AStar, AStarNode, AStarResult, cost::AStarCost, memory};
 use log::{debug, info, trace};
 use ndarray::Array2;
 
 use crate::{
     a_star_aligner::{
+        alignment_geometry::AlignmentMode,
         alignment_result::IAlignmentType,
         template_switch_distance::{
             Context, Identifier,
@@ -94,6 +95,7 @@ impl<Cost: AStarCost> TemplateSwitchAlignmentLowerBoundMatrix<Cost> {
                 "",
                 "",
                 None,
+                AlignmentMode::Global,
                 lower_bound_config.clone(),
                 Memory {
                     template_switch_min_length: (),
@@ -111,6 +113,8 @@ impl<Cost: AStarCost> TemplateSwitchAlignmentLowerBoundMatrix<Cost> {
                 },
                 None,
                 None,
+                None,
+                None,
             ),
         );
         a_star.initialise();
@@ -166,7 +170,9 @@ impl<Cost: AStarCost> TemplateSwitchAlignmentLowerBoundMatrix<Cost> {
                     unreachable!()
                 }
             }) {
-                AStarResult::FoundTarget { identifier, cost } => {
+                AStarResult::FoundTarget {
+                    identifier, cost, ..
+                } => {
                     trace!("Search terminated with target {identifier} at cost {cost}");
 
                     if let Identifier::Primary { .. } | Identifier::PrimaryReentry { .. } =
@@ -205,7 +211,10 @@ impl<Cost: AStarCost> TemplateSwitchAlignmentLowerBoundMatrix<Cost> {
                     unreachable!("Search terminated without target for target reference index {target_reference_index} and target query index {target_query_index}");
                 }
                 AStarResult::ExceededCostLimit { .. }
-                | AStarResult::ExceededMemoryLimit { .. } => unreachable!("No limits set"),
+                | AStarResult::ExceededMemoryLimit { .. }
+                | AStarResult::ExceededTimeLimit { .. }
+                | AStarResult::Cancelled { .. } => unreachable!("No limits set"),
+                AStarResult::Stopped { .. } => unreachable!("No observer set"),
             }
         }
 
@@ -229,6 +238,11 @@ impl<Cost: AStarCost> TemplateSwitchAlignmentLowerBoundMatrix<Cost> {
     pub fn cost(&self, delta_reference: usize, delta_query: usize) -> Cost {
         self.matrix[(delta_reference, delta_query)]
     }
+
+    /// Returns the number of bytes used by the matrix on the heap.
+    pub fn heap_size(&self) -> usize {
+        memory::vec_heap_size::<Cost>(self.matrix.len())
+    }
 }
 
 fn generate_template_switch_alignment_lower_bound_config<
@@ -0,0 +1,265 @@
+//! Dumping the closed nodes of a template switch alignment to inspect the explored search space.
+
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    io::{BufRead, Write},
+    ops::ControlFlow,
+    str::FromStr,
+};
+
+use deterministic_default_hasher::DeterministicDefaultHasher;
+use generic_a_star::{AStarNode, cost::AStarCost, observer::AStarObserver};
+
+use super::{Identifier, Node, TemplateSwitchPrimary, strategies::AlignmentStrategySelector};
+use crate::error::{Error, Result};
+
+const HEADER: [&str; 5] = [
+    "reference_index",
+    "query_index",
+    "kind",
+    "cost",
+    "lower_bound",
+];
+
+/// The kind of a node, corresponding to the variants of [`Identifier`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NodeKind {
+    Primary,
+    PrimaryReentry,
+    TemplateSwitchEntrance,
+    Secondary,
+    TemplateSwitchExit,
+}
+
+/// A closed node, located in the reference × query plane.
+///
+/// Nodes inside a template switch are located at the index of the primary sequence and the entrance index of the anti-primary sequence.
+/// Template switch exit nodes are located where they reenter the primary matrix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchDumpRecord {
+    pub reference_index: usize,
+    pub query_index: usize,
+    pub kind: NodeKind,
+    pub cost: f64,
+    pub lower_bound: f64,
+}
+
+/// The closed nodes of a template switch alignment.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchDump {
+    pub records: Vec<SearchDumpRecord>,
+}
+
+/// Records the closed nodes of a template switch alignment.
+///
+/// If a node is closed multiple times, only its last visit is recorded.
+pub struct SearchDumpObserver<Strategies: AlignmentStrategySelector> {
+    closed_nodes: HashMap<
+        <Node<Strategies> as AStarNode>::Identifier,
+        SearchDumpRecord,
+        DeterministicDefaultHasher,
+    >,
+}
+
+impl<PrimaryExtraData> Identifier<PrimaryExtraData> {
+    /// Returns the kind and the reference and query indices of this identifier, as recorded in a [`SearchDump`].
+    fn search_dump_location(&self) -> (NodeKind, usize, usize) {
+        match *self {
+            Self::Primary {
+                reference_index,
+                query_index,
+                ..
+            } => (NodeKind::Primary, reference_index, query_index),
+            Self::PrimaryReentry {
+                reference_index,
+                query_index,
+                ..
+            } => (NodeKind::PrimaryReentry, reference_index, query_index),
+            Self::TemplateSwitchEntrance {
+                entrance_reference_index,
+                entrance_query_index,
+                ..
+            } => (
+                NodeKind::TemplateSwitchEntrance,
+                entrance_reference_index,
+                entrance_query_index,
+            ),
+            Self::Secondary {
+                entrance_reference_index,
+                entrance_query_index,
+                template_switch_primary,
+                primary_index,
+                ..
+            } => match template_switch_primary {
+                TemplateSwitchPrimary::Reference => {
+                    (NodeKind::Secondary, primary_index, entrance_query_index)
+                }
+                TemplateSwitchPrimary::Query => {
+                    (NodeKind::Secondary, entrance_reference_index, primary_index)
+                }
+            },
+            Self::TemplateSwitchExit {
+                entrance_reference_index,
+                entrance_query_index,
+                template_switch_primary,
+                primary_index,
+                anti_primary_gap,
+                ..
+            } => match template_switch_primary {
+                TemplateSwitchPrimary::Reference => (
+                    NodeKind::TemplateSwitchExit,
+                    primary_index,
+                    entrance_query_index.saturating_add_signed(anti_primary_gap),
+                ),
+                TemplateSwitchPrimary::Query => (
+                    NodeKind::TemplateSwitchExit,
+                    entrance_reference_index.saturating_add_signed(anti_primary_gap),
+                    primary_index,
+                ),
+            },
+        }
+    }
+}
+
+impl SearchDump {
+    /// Writes the records with a header line, separating columns by `delimiter`.
+    pub fn write(&self, mut writer: impl Write, delimiter: char) -> Result<()> {
+        writeln!(writer, "{}", HEADER.join(&delimiter.to_string()))?;
+
+        for SearchDumpRecord {
+            reference_index,
+            query_index,
+            kind,
+            cost,
+            lower_bound,
+        } in &self.records
+        {
+            writeln!(
+                writer,
+                "{reference_index}{delimiter}{query_index}{delimiter}{kind}{delimiter}{cost}{delimiter}{lower_bound}"
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads records written by [`write`](Self::write).
+    ///
+    /// The delimiter is either a tab or a comma, and is detected from the header line.
+    pub fn read(reader: impl BufRead) -> Result<Self> {
+        let mut lines = reader.lines();
+        let header = lines.next().transpose()?.unwrap_or_default();
+        let delimiter = if header.contains('\t') { '\t' } else { ',' };
+        if !header.split(delimiter).eq(HEADER) {
+            return Err(Error::SearchDump {
+                line: 1,
+                message: format!("expected header {:?}", HEADER.join(&delimiter.to_string())),
+            });
+        }
+
+        let mut records = Vec::new();
+        for (line_index, line) in lines.enumerate() {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+
+            let error = |message: String| Error::SearchDump {
+                line: line_index + 2,
+                message,
+            };
+            let columns: Vec<_> = line.split(delimiter).collect();
+            let [reference_index, query_index, kind, cost, lower_bound] = columns[..] else {
+                return Err(error(format!(
+                    "expected {} columns, but found {}",
+                    HEADER.len(),
+                    columns.len()
+                )));
+            };
+
+            records.push(SearchDumpRecord {
+                reference_index: reference_index
+                    .parse()
+                    .map_err(|parse_error| error(format!("{parse_error}")))?,
+                query_index: query_index
+                    .parse()
+                    .map_err(|parse_error| error(format!("{parse_error}")))?,
+                kind: kind.parse().map_err(error)?,
+                cost: cost
+                    .parse()
+                    .map_err(|parse_error| error(format!("{parse_error}")))?,
+                lower_bound: lower_bound
+                    .parse()
+                    .map_err(|parse_error| error(format!("{parse_error}")))?,
+            });
+        }
+
+        Ok(Self { records })
+    }
+}
+
+impl<Strategies: AlignmentStrategySelector> SearchDumpObserver<Strategies> {
+    pub fn new() -> Self {
+        Self {
+            closed_nodes: Default::default(),
+        }
+    }
+
+    /// Returns the recorded nodes, sorted by their location.
+    pub fn into_search_dump(self) -> SearchDump {
+        let mut records: Vec<_> = self.closed_nodes.into_values().collect();
+        records.sort_by(|a, b| {
+            (a.reference_index, a.query_index, a.kind)
+                .cmp(&(b.reference_index, b.query_index, b.kind))
+                .then(a.cost.total_cmp(&b.cost))
+        });
+        SearchDump { records }
+    }
+}
+
+impl<Strategies: AlignmentStrategySelector> AStarObserver<Node<Strategies>>
+    for SearchDumpObserver<Strategies>
+{
+    fn on_node_closed(&mut self, node: &Node<Strategies>) -> ControlFlow<()> {
+        let (kind, reference_index, query_index) = node.identifier().search_dump_location();
+        self.closed_nodes.insert(
+            *node.identifier(),
+            SearchDumpRecord {
+                reference_index,
+                query_index,
+                kind,
+                cost: node.cost().as_f64(),
+                lower_bound: node.a_star_lower_bound().as_f64(),
+            },
+        );
+        ControlFlow::Continue(())
+    }
+}
+
+impl<Strategies: AlignmentStrategySelector> Default for SearchDumpObserver<Strategies> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for NodeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl FromStr for NodeKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(match s {
+            "Primary" => Self::Primary,
+            "PrimaryReentry" => Self::PrimaryReentry,
+            "TemplateSwitchEntrance" => Self::TemplateSwitchEntrance,
+            "Secondary" => Self::Secondary,
+            "TemplateSwitchExit" => Self::TemplateSwitchExit,
+            other => return Err(format!("unknown node kind '{other}'")),
+        })
+    }
+}
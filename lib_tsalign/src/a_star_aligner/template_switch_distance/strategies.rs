@@ -22,6 +22,22 @@ pub mod shortcut;
 pub mod template_switch_count;
 pub mod template_switch_min_length;
 
+/// Requires [`serde::Serialize`] and [`serde::de::DeserializeOwned`] if the `serde` feature is enabled, and nothing otherwise.
+///
+/// Strategies that are stored in nodes implement this, such that searches can be checkpointed.
+#[cfg(feature = "serde")]
+pub trait MaybeSerde: serde::Serialize + serde::de::DeserializeOwned {}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> MaybeSerde for T {}
+
+/// Requires [`serde::Serialize`] and [`serde::de::DeserializeOwned`] if the `serde` feature is enabled, and nothing otherwise.
+#[cfg(not(feature = "serde"))]
+pub trait MaybeSerde {}
+
+#[cfg(not(feature = "serde"))]
+impl<T> MaybeSerde for T {}
+
 pub trait AlignmentStrategySelector: Eq + Clone + std::fmt::Debug {
     type Alphabet: Alphabet;
     type Cost: AStarCost;
@@ -36,6 +52,14 @@ pub trait AlignmentStrategySelector: Eq + Clone + std::fmt::Debug {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "Selector::NodeOrd: serde::Serialize, Selector::TemplateSwitchMinLength: serde::Serialize, Selector::TemplateSwitchCount: serde::Serialize, Selector::PrimaryMatch: serde::Serialize",
+        deserialize = "Selector::NodeOrd: serde::Deserialize<'de>, Selector::TemplateSwitchMinLength: serde::Deserialize<'de>, Selector::TemplateSwitchCount: serde::Deserialize<'de>, Selector::PrimaryMatch: serde::Deserialize<'de>"
+    ))
+)]
 pub struct AlignmentStrategiesNodeMemory<Selector: AlignmentStrategySelector> {
     pub node_ord_strategy: Selector::NodeOrd,
     pub template_switch_min_length_strategy: Selector::TemplateSwitchMinLength,
@@ -43,7 +67,7 @@ pub struct AlignmentStrategiesNodeMemory<Selector: AlignmentStrategySelector> {
     pub primary_match: Selector::PrimaryMatch,
 }
 
-pub trait AlignmentStrategy: Eq + Clone + std::fmt::Debug {
+pub trait AlignmentStrategy: Eq + Clone + std::fmt::Debug + Send {
     fn create_root<
         SubsequenceType: GenomeSequence<Strategies::Alphabet, SubsequenceType> + ?Sized,
         Strategies: AlignmentStrategySelector,
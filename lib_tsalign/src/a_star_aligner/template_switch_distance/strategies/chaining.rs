@@ -5,9 +5,11 @@ use generic_a_star::cost::AStarCost;
 use log::debug;
 use seed_chain::{
     chain::{Chain, ChainingCostsProvider},
-    seed::{ChainingAnchor, ChainingAnchors},
+    seed::ChainingAnchors,
 };
 
+pub use seed_chain::seed::{AnchorSequence, ReverseComplementAnchor, SeedingMethod};
+
 use crate::{
     a_star_aligner::template_switch_distance::{
         AlignmentType, Context, Identifier, Node,
@@ -32,7 +34,7 @@ pub trait ChainingStrategy<Cost>: AlignmentStrategy {
         reference: &SubsequenceType,
         query: &SubsequenceType,
         config: &TemplateSwitchConfig<AlphabetType, Cost>,
-        block_size: usize,
+        seeding_method: &SeedingMethod,
     ) -> Self::Memory;
 
     fn apply_lower_bound<
@@ -42,9 +44,11 @@ pub trait ChainingStrategy<Cost>: AlignmentStrategy {
         node: Node<Strategies>,
         context: &Context<SubsequenceType, Strategies>,
     ) -> Node<Strategies>;
+
+    /// Returns the number of bytes used by the memory on the heap.
+    fn memory_heap_size(memory: &Self::Memory) -> usize;
 }
 
-#[expect(dead_code)]
 pub struct ChainingMemory<Cost> {
     ts_lower_bounds: TemplateSwitchLowerBoundMatrix<Cost>,
     tsa_lower_bounds: TemplateSwitchAlignmentLowerBoundMatrix<Cost>,
@@ -52,6 +56,14 @@ pub struct ChainingMemory<Cost> {
     max_gap_open_cost: Cost,
 }
 
+impl<Cost: AStarCost> ChainingMemory<Cost> {
+    fn heap_size(&self) -> usize {
+        self.ts_lower_bounds.heap_size()
+            + self.tsa_lower_bounds.heap_size()
+            + self.chain.heap_size()
+    }
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct NoChainingStrategy<Cost> {
     phantom_data: PhantomData<Cost>,
@@ -84,7 +96,7 @@ impl<Cost: AStarCost> ChainingStrategy<Cost> for NoChainingStrategy<Cost> {
         _reference: &SubsequenceType,
         _query: &SubsequenceType,
         _config: &TemplateSwitchConfig<AlphabetType, Cost>,
-        _block_size: usize,
+        _seeding_method: &SeedingMethod,
     ) -> Self::Memory {
         // Do nothing.
     }
@@ -98,6 +110,10 @@ impl<Cost: AStarCost> ChainingStrategy<Cost> for NoChainingStrategy<Cost> {
     ) -> Node<Strategies> {
         node
     }
+
+    fn memory_heap_size(_memory: &Self::Memory) -> usize {
+        0
+    }
 }
 
 impl<Cost: AStarCost> ChainingStrategy<Cost> for PrecomputeOnlyChainingStrategy<Cost> {
@@ -110,8 +126,25 @@ impl<Cost: AStarCost> ChainingStrategy<Cost> for PrecomputeOnlyChainingStrategy<
         reference: &SubsequenceType,
         query: &SubsequenceType,
         config: &TemplateSwitchConfig<AlphabetType, Cost>,
-        block_size: usize,
+        seeding_method: &SeedingMethod,
     ) -> Self::Memory {
+        assert!(
+            !config.has_negative_costs(),
+            "Chaining lower bounds do not support negative costs"
+        );
+
+        // The lower bounds between anchors may only assume as many consecutive matches as can occur without an anchor.
+        let (max_consecutive_primary_matches, max_consecutive_primary_matches_at_start_and_end) =
+            match seeding_method {
+                SeedingMethod::NonOverlapping { block_size } => {
+                    (block_size * 2 - 1, block_size - 1)
+                }
+                SeedingMethod::SpacedSeed { pattern } => (pattern.len() * 2 - 1, pattern.len() - 1),
+                SeedingMethod::Minimizers { window_size, k } => {
+                    (window_size + k - 2, window_size + k - 2)
+                }
+            };
+
         let ts_lower_bounds = TemplateSwitchLowerBoundMatrix::new(config);
         debug!("{ts_lower_bounds}");
         let tsa_lower_bounds = TemplateSwitchAlignmentLowerBoundMatrix::new(
@@ -119,11 +152,11 @@ impl<Cost: AStarCost> ChainingStrategy<Cost> for PrecomputeOnlyChainingStrategy<
             &ts_lower_bounds,
             reference.len(),
             query.len(),
-            block_size * 2 - 1,
-            block_size - 1,
+            max_consecutive_primary_matches,
+            max_consecutive_primary_matches_at_start_and_end,
         );
         debug!("{tsa_lower_bounds}");
-        let chaining_anchors = ChainingAnchors::seed_nonoverlapping(reference, query, block_size);
+        let chaining_anchors = ChainingAnchors::seed(reference, query, seeding_method);
         let chain = Chain::compute_chain(
             TemplateSwitchAlignmentLowerBoundChainingCosts {
                 matrix: &tsa_lower_bounds,
@@ -152,6 +185,10 @@ impl<Cost: AStarCost> ChainingStrategy<Cost> for PrecomputeOnlyChainingStrategy<
     ) -> Node<Strategies> {
         node
     }
+
+    fn memory_heap_size(memory: &Self::Memory) -> usize {
+        memory.heap_size()
+    }
 }
 
 impl<Cost: AStarCost> ChainingStrategy<Cost> for LowerBoundChainingStrategy<Cost> {
@@ -164,9 +201,9 @@ impl<Cost: AStarCost> ChainingStrategy<Cost> for LowerBoundChainingStrategy<Cost
         reference: &SubsequenceType,
         query: &SubsequenceType,
         config: &TemplateSwitchConfig<AlphabetType, Cost>,
-        block_size: usize,
+        seeding_method: &SeedingMethod,
     ) -> Self::Memory {
-        PrecomputeOnlyChainingStrategy::initialise_memory(reference, query, config, block_size)
+        PrecomputeOnlyChainingStrategy::initialise_memory(reference, query, config, seeding_method)
     }
 
     fn apply_lower_bound<
@@ -191,7 +228,8 @@ impl<Cost: AStarCost> ChainingStrategy<Cost> for LowerBoundChainingStrategy<Cost
             ..
         } = node.node_data.identifier
         {
-            if flank_index <= 0 {
+            // Chains bound the cost of aligning until the end of the range, which clipping alignments do not need to reach.
+            if flank_index <= 0 && !context.mode.is_clipping() {
                 let mut chain_lower_bound = context
                     .memory
                     .chaining
@@ -209,6 +247,9 @@ impl<Cost: AStarCost> ChainingStrategy<Cost> for LowerBoundChainingStrategy<Cost
 
         node
     }
+    fn memory_heap_size(memory: &Self::Memory) -> usize {
+        memory.heap_size()
+    }
 }
 
 impl<Cost: AStarCost> ChainingCostsProvider
@@ -221,13 +262,8 @@ impl<Cost: AStarCost> ChainingCostsProvider
         from: &seed_chain::chain::Identifier,
         to: &seed_chain::chain::Identifier,
     ) -> Cost {
-        let from = seed_chain_identifier_to_chaining_anchor(
-            from,
-            self.reference_length,
-            self.query_length,
-        );
-        let to =
-            seed_chain_identifier_to_chaining_anchor(to, self.reference_length, self.query_length);
+        let from = from.to_anchor(self.reference_length, self.query_length);
+        let to = to.to_anchor(self.reference_length, self.query_length);
         if from.reference_block().end > to.reference_block().start
             || from.query_block().end > to.query_block().start
         {
@@ -240,21 +276,6 @@ impl<Cost: AStarCost> ChainingCostsProvider
     }
 }
 
-fn seed_chain_identifier_to_chaining_anchor(
-    identifier: &seed_chain::chain::Identifier,
-    reference_length: usize,
-    query_length: usize,
-) -> ChainingAnchor {
-    match identifier {
-        seed_chain::chain::Identifier::Root => ChainingAnchor::new(0..0, 0..0),
-        seed_chain::chain::Identifier::Anchor { anchor } => anchor.clone(),
-        seed_chain::chain::Identifier::Target => ChainingAnchor::new(
-            reference_length..reference_length,
-            query_length..query_length,
-        ),
-    }
-}
-
 impl<Cost: AStarCost> AlignmentStrategy for NoChainingStrategy<Cost> {
     fn create_root<
         SubsequenceType: GenomeSequence<Strategies::Alphabet, SubsequenceType> + ?Sized,
@@ -3,10 +3,12 @@ use generic_a_star::cost::AStarCost;
 
 use crate::a_star_aligner::template_switch_distance::{AlignmentType, Context, Identifier, Node};
 
-use super::{AlignmentStrategy, AlignmentStrategySelector, primary_match::PrimaryMatchStrategy};
+use super::{
+    AlignmentStrategy, AlignmentStrategySelector, MaybeSerde, primary_match::PrimaryMatchStrategy,
+};
 
 pub trait NodeOrdStrategy<Cost, PrimaryMatch: PrimaryMatchStrategy<Cost>>:
-    AlignmentStrategy
+    AlignmentStrategy + MaybeSerde
 {
     fn cmp<Strategies: AlignmentStrategySelector<Cost = Cost, PrimaryMatch = PrimaryMatch>>(
         &self,
@@ -16,9 +18,11 @@ pub trait NodeOrdStrategy<Cost, PrimaryMatch: PrimaryMatchStrategy<Cost>>:
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CostOnlyNodeOrdStrategy;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AntiDiagonalNodeOrdStrategy;
 
 impl<Cost: AStarCost, PrimaryMatch: PrimaryMatchStrategy<Cost>> NodeOrdStrategy<Cost, PrimaryMatch>
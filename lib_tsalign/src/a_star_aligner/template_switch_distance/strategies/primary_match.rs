@@ -9,11 +9,11 @@ use num_traits::Bounded;
 
 use crate::a_star_aligner::template_switch_distance::{AlignmentType, Context, Identifier};
 
-use super::AlignmentStrategySelector;
+use super::{AlignmentStrategySelector, MaybeSerde};
 
-pub trait PrimaryMatchStrategy<Cost>: Eq + Clone + Debug + Display {
+pub trait PrimaryMatchStrategy<Cost>: Eq + Clone + Debug + Display + MaybeSerde {
     type Memory;
-    type IdentifierPrimaryExtraData: Eq + Copy + Debug + Ord + Hash;
+    type IdentifierPrimaryExtraData: Eq + Copy + Debug + Ord + Hash + MaybeSerde;
 
     fn create_root<
         SubsequenceType: GenomeSequence<Strategies::Alphabet, SubsequenceType> + ?Sized,
@@ -78,9 +78,11 @@ pub trait PrimaryMatchStrategy<Cost>: Eq + Clone + Debug + Display {
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AllowPrimaryMatchStrategy;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MaxConsecutivePrimaryMatchStrategy;
 
 pub struct MaxConsecutivePrimaryMatchMemory<Cost> {
@@ -2,10 +2,12 @@ use compact_genome::interface::sequence::GenomeSequence;
 
 use crate::a_star_aligner::template_switch_distance::{AlignmentType, Context, Identifier};
 
-use super::{AlignmentStrategy, AlignmentStrategySelector, primary_match::PrimaryMatchStrategy};
+use super::{
+    AlignmentStrategy, AlignmentStrategySelector, MaybeSerde, primary_match::PrimaryMatchStrategy,
+};
 
-pub trait TemplateSwitchCountStrategy: AlignmentStrategy {
-    type Memory;
+pub trait TemplateSwitchCountStrategy: AlignmentStrategy + MaybeSerde {
+    type Memory: Clone + Sync;
 
     /// Called when a template switch has ended.
     fn increment_count(&mut self);
@@ -21,9 +23,11 @@ pub trait TemplateSwitchCountStrategy: AlignmentStrategy {
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NoTemplateSwitchCountStrategy;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MaxTemplateSwitchCountStrategy {
     template_switch_count: usize,
 }
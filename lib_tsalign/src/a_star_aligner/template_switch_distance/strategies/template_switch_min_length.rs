@@ -4,20 +4,22 @@ use compact_genome::interface::sequence::GenomeSequence;
 use deterministic_default_hasher::DeterministicDefaultHasher;
 use generic_a_star::cost::AStarCost;
 use generic_a_star::reset::Reset;
-use generic_a_star::{AStar, AStarContext, AStarNode, AStarResult};
+use generic_a_star::{AStar, AStarContext, AStarNode, AStarResult, memory};
 
 use crate::a_star_aligner::template_switch_distance::AlignmentType;
 use crate::a_star_aligner::template_switch_distance::{
     Context, Identifier, Node,
-    identifier::{GapType, TemplateSwitchPrimary, TemplateSwitchSecondary},
+    identifier::{
+        GapType, TemplateSwitchDirection, TemplateSwitchPrimary, TemplateSwitchSecondary,
+    },
 };
 
 use super::primary_match::PrimaryMatchStrategy;
-use super::{AlignmentStrategy, AlignmentStrategySelector};
+use super::{AlignmentStrategy, AlignmentStrategySelector, MaybeSerde};
 
-pub trait TemplateSwitchMinLengthStrategy<Cost>: AlignmentStrategy {
+pub trait TemplateSwitchMinLengthStrategy<Cost>: AlignmentStrategy + MaybeSerde {
     /// The type used to memorise lookahead results.
-    type Memory: Default + Reset;
+    type Memory: Default + Reset + MaybeSerde;
 
     /// Takes the template switch entrance node and provides a lower bound for its costs depending on the minimum length of a template switch.
     /// The modified entrance node is returned in the iterator along with further nodes that were created while computing the lower bound.
@@ -32,14 +34,19 @@ pub trait TemplateSwitchMinLengthStrategy<Cost>: AlignmentStrategy {
         secondary_root_node: Node<Strategies>,
         context: &mut Context<SubsequenceType, Strategies>,
     ) -> impl IntoIterator<Item = Node<Strategies>>;
+
+    /// Returns the number of bytes used by the memory on the heap.
+    fn memory_heap_size(memory: &Self::Memory) -> usize;
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NoTemplateSwitchMinLengthStrategy<Cost> {
     phantom_data: PhantomData<Cost>,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LookaheadTemplateSwitchMinLengthStrategy<Cost> {
     phantom_data: PhantomData<Cost>,
 }
@@ -74,12 +81,18 @@ impl<Cost: AStarCost> TemplateSwitchMinLengthStrategy<Cost>
     ) -> impl IntoIterator<Item = Node<Strategies>> {
         Some(secondary_root_node)
     }
+
+    fn memory_heap_size(_memory: &Self::Memory) -> usize {
+        0
+    }
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LookaheadMemoryKey {
     template_switch_primary: TemplateSwitchPrimary,
     template_switch_secondary: TemplateSwitchSecondary,
+    template_switch_direction: TemplateSwitchDirection,
     primary_index: usize,
     secondary_index: usize,
 }
@@ -100,6 +113,7 @@ impl<Cost: AStarCost> TemplateSwitchMinLengthStrategy<Cost>
         let Identifier::Secondary {
             template_switch_primary,
             template_switch_secondary,
+            template_switch_direction,
             length: 0,
             primary_index,
             secondary_index,
@@ -113,6 +127,7 @@ impl<Cost: AStarCost> TemplateSwitchMinLengthStrategy<Cost>
         let memory_key = LookaheadMemoryKey {
             template_switch_primary,
             template_switch_secondary,
+            template_switch_direction,
             primary_index,
             secondary_index,
         };
@@ -120,7 +135,7 @@ impl<Cost: AStarCost> TemplateSwitchMinLengthStrategy<Cost>
         let secondary_root_node = if let Some(a_star_lower_bound) =
             context.memory.template_switch_min_length.get(&memory_key)
         {
-            secondary_root_node.node_data.a_star_lower_bound += *a_star_lower_bound;
+            add_min_length_lower_bound(&mut secondary_root_node, *a_star_lower_bound, context);
             secondary_root_node
         } else {
             let buffers = mem::take(&mut context.a_star_buffers);
@@ -142,7 +157,7 @@ impl<Cost: AStarCost> TemplateSwitchMinLengthStrategy<Cost>
                     .memory
                     .template_switch_min_length
                     .insert(memory_key, lower_bound);
-                secondary_root_node.node_data.a_star_lower_bound += lower_bound;
+                add_min_length_lower_bound(&mut secondary_root_node, lower_bound, context);
 
                 secondary_root_node
             } else {
@@ -153,6 +168,33 @@ impl<Cost: AStarCost> TemplateSwitchMinLengthStrategy<Cost>
 
         Some(secondary_root_node)
     }
+    fn memory_heap_size(memory: &Self::Memory) -> usize {
+        memory::hash_map_heap_size::<LookaheadMemoryKey, Cost>(memory.capacity())
+    }
+}
+
+/// Adds the minimum cost of reaching the minimum length of a template switch to the lower bound of its secondary root node.
+///
+/// With negative costs, the lower bound of the secondary root node may stem from alignment steps before the template switch that overlap with the minimum length.
+/// Then the minimum cost is only added to the lower bound derived from the remaining characters.
+fn add_min_length_lower_bound<
+    Strategies: AlignmentStrategySelector,
+    SubsequenceType: GenomeSequence<Strategies::Alphabet, SubsequenceType> + ?Sized,
+>(
+    secondary_root_node: &mut Node<Strategies>,
+    min_length_cost: Strategies::Cost,
+    context: &Context<SubsequenceType, Strategies>,
+) {
+    let a_star_lower_bound = &mut secondary_root_node.node_data.a_star_lower_bound;
+
+    if context.has_negative_costs() {
+        *a_star_lower_bound = (*a_star_lower_bound).max(
+            context.negative_cost_lower_bound(&secondary_root_node.node_data.identifier)
+                + min_length_cost,
+        );
+    } else {
+        *a_star_lower_bound += min_length_cost;
+    }
 }
 
 impl<Cost: AStarCost> AlignmentStrategy for NoTemplateSwitchMinLengthStrategy<Cost> {
@@ -264,6 +306,10 @@ impl<
     fn memory_limit(&self) -> Option<usize> {
         self.context.memory_limit()
     }
+
+    fn is_label_setting(&self) -> bool {
+        self.context.is_label_setting()
+    }
 }
 
 impl<
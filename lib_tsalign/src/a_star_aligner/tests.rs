@@ -1,29 +1,1290 @@
+use std::{ops::ControlFlow, time::Duration};
+
 use compact_genome::{
-    implementation::{alphabets::dna_alphabet::DnaAlphabet, vec_sequence::VectorGenome},
+    implementation::{
+        alphabets::{dna_alphabet::DnaAlphabet, dna_alphabet_or_n::DnaAlphabetOrN},
+        vec_sequence::VectorGenome,
+    },
     interface::sequence::{GenomeSequence, OwnedGenomeSequence},
 };
-use generic_a_star::cost::U64Cost;
+use generic_a_star::{
+    AStar, AStarNode, AStarResult,
+    cancellation::CancellationToken,
+    closed_list::ClosedListMode,
+    cost::{AStarCost, F64Cost, I64Cost, U64Cost},
+    observer::AStarObserver,
+    open_list::{AStarOpenList, BinaryHeapOpenList, BucketQueueOpenList},
+};
 use num_traits::real::Real;
 
-use super::{gap_affine_edit_distance::ScoringTable, gap_affine_edit_distance_a_star_align};
+use super::{
+    HeuristicChecker, a_star_align,
+    alignment_geometry::AlignmentMode,
+    alignment_options::AlignmentOptions,
+    alignment_result::AlignmentResult,
+    anytime_search,
+    gap_affine_edit_distance::{self, ScoringTable},
+    gap_affine_edit_distance_a_star_align, gap_affine_edit_distance_bidirectional_a_star_align,
+    gap_affine_edit_distance_k_best_a_star_align,
+    template_switch_distance::{
+        AlignmentType, TemplateSwitchDirection,
+        strategies::{
+            AlignmentStrategySelection,
+            chaining::{
+                ChainingStrategy, LowerBoundChainingStrategy, NoChainingStrategy,
+                PrecomputeOnlyChainingStrategy, SeedingMethod,
+            },
+            node_ord::CostOnlyNodeOrdStrategy,
+            primary_match::AllowPrimaryMatchStrategy,
+            primary_range::NoPrunePrimaryRangeStrategy,
+            secondary_deletion::AllowSecondaryDeletionStrategy,
+            shortcut::NoShortcutStrategy,
+            template_switch_count::NoTemplateSwitchCountStrategy,
+            template_switch_min_length::{
+                LookaheadTemplateSwitchMinLengthStrategy, NoTemplateSwitchMinLengthStrategy,
+                TemplateSwitchMinLengthStrategy,
+            },
+        },
+    },
+    template_switch_distance_a_star_align, template_switch_distance_context,
+    template_switch_distance_k_best_a_star_align, template_switch_distance_parallel_a_star_align,
+};
+use crate::{config::TemplateSwitchConfig, error::Error};
 
-#[test]
-fn match_overtakes_gap() {
-    let reference = VectorGenome::<DnaAlphabet>::from_iter_u8("AGT".bytes()).unwrap();
-    let query = VectorGenome::from_iter_u8("GTCC".bytes()).unwrap();
-    let scoring_table = ScoringTable::<U64Cost> {
+/// Pairs of reference and query with a mismatch, gaps at both ends, only gaps, and an empty sequence.
+const SEQUENCE_PAIRS: [(&str, &str); 4] = [
+    ("AGT", "GTCC"),
+    ("ACGTACGTTTGACA", "ACGTTCGTTGACCA"),
+    ("AAAAAAAAAA", "CCC"),
+    ("", "ACG"),
+];
+
+/// The strategies of the template switch alignments in these tests.
+///
+/// Only the cost type and the strategies that affect the lower bound vary between the tests.
+type TestStrategies<
+    Cost,
+    TemplateSwitchMinLength = NoTemplateSwitchMinLengthStrategy<Cost>,
+    Chaining = NoChainingStrategy<Cost>,
+> = AlignmentStrategySelection<
+    DnaAlphabetOrN,
+    Cost,
+    CostOnlyNodeOrdStrategy,
+    TemplateSwitchMinLength,
+    Chaining,
+    NoTemplateSwitchCountStrategy,
+    AllowSecondaryDeletionStrategy,
+    NoShortcutStrategy<Cost>,
+    AllowPrimaryMatchStrategy,
+    NoPrunePrimaryRangeStrategy,
+>;
+
+fn scoring_table() -> ScoringTable<U64Cost> {
+    ScoringTable {
         match_cost: 0u64.into(),
         substitution_cost: 2u64.into(),
         gap_open_cost: 4u64.into(),
         gap_extend_cost: 1u64.into(),
-    };
+    }
+}
+
+#[test]
+fn match_overtakes_gap() {
+    let reference = VectorGenome::<DnaAlphabet>::from_iter_u8("AGT".bytes()).unwrap();
+    let query = VectorGenome::from_iter_u8("GTCC".bytes()).unwrap();
+    let scoring_table = scoring_table();
 
     let alignment_result = gap_affine_edit_distance_a_star_align(
         reference.as_genome_subsequence(),
         query.as_genome_subsequence(),
         scoring_table,
-    );
+        AlignmentMode::Global,
+    )
+    .unwrap();
 
     assert_eq!(alignment_result.cigar(), "1D2M2I");
     assert!((alignment_result.statistics().cost - 9.0).abs() < 1e-6);
+    assert!(alignment_result.statistics().memory > 0.0);
+}
+
+#[test]
+fn bidirectional_matches_unidirectional() {
+    let scoring_table = scoring_table();
+
+    for (reference, query) in SEQUENCE_PAIRS.into_iter().chain([("GATTACA", "GATTACA")]) {
+        let reference = VectorGenome::<DnaAlphabet>::from_iter_u8(reference.bytes()).unwrap();
+        let query = VectorGenome::from_iter_u8(query.bytes()).unwrap();
+
+        let unidirectional = gap_affine_edit_distance_a_star_align(
+            reference.as_genome_subsequence(),
+            query.as_genome_subsequence(),
+            scoring_table,
+            AlignmentMode::Global,
+        )
+        .unwrap();
+        let bidirectional = gap_affine_edit_distance_bidirectional_a_star_align(
+            reference.as_genome_subsequence(),
+            query.as_genome_subsequence(),
+            scoring_table,
+        )
+        .unwrap();
+
+        assert_eq!(
+            unidirectional.statistics().cost,
+            bidirectional.statistics().cost
+        );
+        assert_eq!(
+            unidirectional.statistics().result,
+            bidirectional.statistics().result
+        );
+    }
+}
+
+#[test]
+fn bidirectional_rejects_negative_costs() {
+    let scoring_table = ScoringTable::<I64Cost> {
+        match_cost: (-1i64).into(),
+        substitution_cost: 2i64.into(),
+        gap_open_cost: 4i64.into(),
+        gap_extend_cost: 1i64.into(),
+    };
+    let reference = VectorGenome::<DnaAlphabet>::from_iter_u8("ACGTACGTTTGACA".bytes()).unwrap();
+    let query = VectorGenome::from_iter_u8("ACGTTCGTTGACCA".bytes()).unwrap();
+
+    assert!(matches!(
+        gap_affine_edit_distance_bidirectional_a_star_align(
+            reference.as_genome_subsequence(),
+            query.as_genome_subsequence(),
+            scoring_table,
+        ),
+        Err(Error::BidirectionalWithNegativeCosts)
+    ));
+}
+
+fn search_with_open_list<OpenList: AStarOpenList<gap_affine_edit_distance::Node<U64Cost>>>(
+    reference: &VectorGenome<DnaAlphabet>,
+    query: &VectorGenome<DnaAlphabet>,
+    scoring_table: ScoringTable<U64Cost>,
+    epsilon: Option<f64>,
+) -> U64Cost {
+    let mut a_star = AStar::<_, (), OpenList>::new_with_observer(
+        gap_affine_edit_distance::Context::new(
+            reference.as_genome_subsequence(),
+            query.as_genome_subsequence(),
+            scoring_table,
+            AlignmentMode::Global,
+        ),
+        (),
+    );
+    a_star.initialise();
+    if let Some(epsilon) = epsilon {
+        a_star.search_weighted(epsilon).cost()
+    } else {
+        a_star.search().cost()
+    }
+}
+
+#[test]
+fn bucket_queue_matches_binary_heap() {
+    let scoring_table = scoring_table();
+
+    for (reference, query) in SEQUENCE_PAIRS {
+        let reference = VectorGenome::<DnaAlphabet>::from_iter_u8(reference.bytes()).unwrap();
+        let query = VectorGenome::from_iter_u8(query.bytes()).unwrap();
+
+        let binary_heap =
+            search_with_open_list::<BinaryHeapOpenList<_>>(&reference, &query, scoring_table, None);
+        let bucket_queue = search_with_open_list::<BucketQueueOpenList<_>>(
+            &reference,
+            &query,
+            scoring_table,
+            None,
+        );
+        assert_eq!(binary_heap, bucket_queue);
+
+        let weighted = search_with_open_list::<BucketQueueOpenList<_>>(
+            &reference,
+            &query,
+            scoring_table,
+            Some(1.5),
+        );
+        assert!(weighted >= binary_heap);
+        assert!(weighted.as_f64() <= 1.5 * binary_heap.as_f64());
+    }
+}
+
+#[test]
+fn compact_closed_list_matches_full() {
+    let scoring_table = scoring_table();
+
+    for (reference, query) in SEQUENCE_PAIRS {
+        let reference = VectorGenome::<DnaAlphabet>::from_iter_u8(reference.bytes()).unwrap();
+        let query = VectorGenome::from_iter_u8(query.bytes()).unwrap();
+
+        let [full, compact] = [ClosedListMode::Full, ClosedListMode::Compact].map(|mode| {
+            let mut a_star = AStar::new(gap_affine_edit_distance::Context::new(
+                reference.as_genome_subsequence(),
+                query.as_genome_subsequence(),
+                scoring_table,
+                AlignmentMode::Global,
+            ));
+            a_star.set_closed_list_mode(mode);
+            a_star.initialise();
+            let AStarResult::FoundTarget {
+                identifier, cost, ..
+            } = a_star.search()
+            else {
+                panic!("No target found");
+            };
+            assert_eq!(a_star.closed_node(&identifier).unwrap().cost(), cost);
+            assert_eq!(
+                a_star.closed_node(&identifier).unwrap().node().is_some(),
+                mode == ClosedListMode::Full
+            );
+
+            (
+                cost,
+                a_star.backtrack_with_costs().collect::<Vec<_>>(),
+                a_star.performance_counters().closed_nodes,
+            )
+        });
+
+        assert_eq!(full, compact);
+    }
+}
+
+#[test]
+fn k_best_alignments_are_ranked() {
+    let reference = VectorGenome::<DnaAlphabet>::from_iter_u8("ACGTACGTTTGACA".bytes()).unwrap();
+    let query = VectorGenome::from_iter_u8("ACGTTCGTTGACCA".bytes()).unwrap();
+    let scoring_table = scoring_table();
+
+    let optimal = gap_affine_edit_distance_a_star_align(
+        reference.as_genome_subsequence(),
+        query.as_genome_subsequence(),
+        scoring_table,
+        AlignmentMode::Global,
+    )
+    .unwrap();
+    let k_best = gap_affine_edit_distance_k_best_a_star_align(
+        reference.as_genome_subsequence(),
+        query.as_genome_subsequence(),
+        scoring_table,
+        10,
+        None,
+    );
+
+    assert_eq!(k_best.len(), 10);
+    assert_eq!(k_best[0].statistics().cost, optimal.statistics().cost);
+    assert!(k_best.windows(2).all(|pair| {
+        pair[0].statistics().cost <= pair[1].statistics().cost && pair[0].cigar() != pair[1].cigar()
+    }));
+
+    let within_delta = gap_affine_edit_distance_k_best_a_star_align(
+        reference.as_genome_subsequence(),
+        query.as_genome_subsequence(),
+        scoring_table,
+        usize::MAX,
+        Some(2u64.into()),
+    );
+
+    assert!(
+        within_delta
+            .iter()
+            .all(|alignment| alignment.statistics().cost <= optimal.statistics().cost + 2.0)
+    );
+    for (a, b) in within_delta.iter().zip(&k_best) {
+        assert_eq!(a.statistics().cost, b.statistics().cost);
+    }
+}
+
+#[derive(Default)]
+struct CountingObserver {
+    opened_nodes: usize,
+    closed_nodes: usize,
+    suboptimal_nodes: usize,
+    targets: usize,
+    close_limit: Option<usize>,
+}
+
+impl<Node: AStarNode> AStarObserver<Node> for CountingObserver {
+    fn on_node_opened(&mut self, _node: &Node) {
+        self.opened_nodes += 1;
+    }
+
+    fn on_node_closed(&mut self, _node: &Node) -> ControlFlow<()> {
+        if self.close_limit == Some(self.closed_nodes) {
+            return ControlFlow::Break(());
+        }
+
+        self.closed_nodes += 1;
+        ControlFlow::Continue(())
+    }
+
+    fn on_suboptimal_node(&mut self, _node: &Node) {
+        self.suboptimal_nodes += 1;
+    }
+
+    fn on_target_found(&mut self, _node: &Node) {
+        self.targets += 1;
+    }
+}
+
+#[test]
+fn observer_sees_search_events() {
+    let reference = VectorGenome::<DnaAlphabet>::from_iter_u8("ACGTACGTTTGACA".bytes()).unwrap();
+    let query = VectorGenome::from_iter_u8("ACGTTCGTTGACCA".bytes()).unwrap();
+    let scoring_table = scoring_table();
+
+    let mut observer = CountingObserver::default();
+    let alignment_result = a_star_align(
+        gap_affine_edit_distance::Context::new(
+            reference.as_genome_subsequence(),
+            query.as_genome_subsequence(),
+            scoring_table,
+            AlignmentMode::Global,
+        ),
+        None,
+        None,
+        ClosedListMode::Full,
+        &mut observer,
+    );
+
+    let statistics = alignment_result.statistics();
+    assert_eq!(observer.targets, 1);
+    assert_eq!(observer.opened_nodes as f64, statistics.opened_nodes.raw());
+    assert_eq!(observer.closed_nodes as f64, statistics.closed_nodes.raw());
+    assert_eq!(
+        observer.suboptimal_nodes as f64,
+        statistics.suboptimal_opened_nodes.raw()
+    );
+
+    let mut observer = CountingObserver {
+        close_limit: Some(5),
+        ..Default::default()
+    };
+    let alignment_result = a_star_align(
+        gap_affine_edit_distance::Context::new(
+            reference.as_genome_subsequence(),
+            query.as_genome_subsequence(),
+            scoring_table,
+            AlignmentMode::Global,
+        ),
+        None,
+        None,
+        ClosedListMode::Full,
+        &mut observer,
+    );
+
+    assert!(matches!(
+        alignment_result.statistics().result,
+        AStarResult::Stopped { .. }
+    ));
+    assert_eq!(observer.closed_nodes, 5);
+    assert_eq!(observer.targets, 0);
+}
+
+#[test]
+fn cost_limit_doubling_matches_unlimited() {
+    let reference = VectorGenome::<DnaAlphabet>::from_iter_u8("ACGTACGTTTGACAGGT".bytes()).unwrap();
+    let query = VectorGenome::from_iter_u8("ACGTTCGTTGACCAGT".bytes()).unwrap();
+    let scoring_table = scoring_table();
+
+    let unlimited = gap_affine_edit_distance_a_star_align(
+        reference.as_genome_subsequence(),
+        query.as_genome_subsequence(),
+        scoring_table,
+        AlignmentMode::Global,
+    )
+    .unwrap();
+
+    let mut observer = CountingObserver::default();
+    let doubling = a_star_align(
+        gap_affine_edit_distance::Context::new(
+            reference.as_genome_subsequence(),
+            query.as_genome_subsequence(),
+            scoring_table,
+            AlignmentMode::Global,
+        ),
+        None,
+        Some(1u64.into()),
+        ClosedListMode::Full,
+        &mut observer,
+    );
+
+    assert_eq!(doubling.cigar(), unlimited.cigar());
+    assert_eq!(doubling.statistics().cost, unlimited.statistics().cost);
+    assert!(unlimited.statistics().cost.raw() > 1.0);
+    // Pruned nodes are counted as opened only once they are moved to the open list.
+    assert_eq!(
+        observer.opened_nodes as f64,
+        doubling.statistics().opened_nodes.raw()
+    );
+}
+
+#[test]
+fn anytime_search_respects_suboptimality_bound() {
+    type Strategies = TestStrategies<
+        U64Cost,
+        NoTemplateSwitchMinLengthStrategy<U64Cost>,
+        LowerBoundChainingStrategy<U64Cost>,
+    >;
+
+    let reference =
+        VectorGenome::<DnaAlphabetOrN>::from_iter_u8("AACCAACCGGTGTGGGCGTTCTTGAGGTTT".bytes())
+            .unwrap();
+    let query = VectorGenome::from_iter_u8("AACCAAACGGTGTGGCGTTCTTGACGTTT".bytes()).unwrap();
+    let config = TemplateSwitchConfig::read_plain(
+        include_str!("../../../sample_tsa_config/config.tsa").as_bytes(),
+    )
+    .unwrap();
+    let options = AlignmentOptions::default();
+    let create_a_star = || {
+        let mut a_star = AStar::new(template_switch_distance_context::<Strategies, _>(
+            reference.as_genome_subsequence(),
+            query.as_genome_subsequence(),
+            config.clone(),
+            &options,
+            None,
+            (),
+        ));
+        a_star.initialise();
+        a_star
+    };
+
+    let optimal_cost = create_a_star().search().cost();
+
+    for epsilon in [1.5, 4.0] {
+        let weighted = create_a_star().search_weighted(epsilon);
+        assert!(weighted.suboptimality_bound() <= epsilon);
+        assert!(weighted.cost() >= optimal_cost);
+        assert!(weighted.cost().as_f64() <= weighted.suboptimality_bound() * optimal_cost.as_f64());
+
+        let anytime = anytime_search(&mut create_a_star(), epsilon);
+        assert_eq!(anytime.cost(), optimal_cost);
+        assert_eq!(anytime.suboptimality_bound(), 1.0);
+    }
+}
+
+/// Aligns with the given strategies while checking the lower bound, and returns the cost of the alignment.
+///
+/// Panics if the lower bound is not admissible.
+fn check_template_switch_heuristic<
+    TemplateSwitchMinLength: TemplateSwitchMinLengthStrategy<U64Cost>,
+    Chaining: ChainingStrategy<U64Cost>,
+>(
+    reference: &VectorGenome<DnaAlphabetOrN>,
+    query: &VectorGenome<DnaAlphabetOrN>,
+    seeding_method: &SeedingMethod,
+) -> f64 {
+    let config = TemplateSwitchConfig::read_plain(
+        include_str!("../../../sample_tsa_config/config.tsa").as_bytes(),
+    )
+    .unwrap();
+    let mut heuristic_checker = HeuristicChecker::new();
+
+    let alignment: AlignmentResult<AlignmentType, U64Cost> =
+        template_switch_distance_a_star_align::<
+            TestStrategies<U64Cost, TemplateSwitchMinLength, Chaining>,
+            _,
+        >(
+            reference.as_genome_subsequence(),
+            query.as_genome_subsequence(),
+            config,
+            &AlignmentOptions::default().with_seeding_method(seeding_method.clone()),
+            (),
+            &mut heuristic_checker,
+        )
+        .unwrap();
+
+    let report = heuristic_checker.into_report();
+    assert!(report.is_admissible(), "{report}");
+    alignment.statistics().cost.raw()
+}
+
+#[test]
+fn template_switch_strategies_are_admissible() {
+    let reference =
+        VectorGenome::<DnaAlphabetOrN>::from_iter_u8("AACCAACCGGTGTGGGCGTTCTTGAGGTTT".bytes())
+            .unwrap();
+    let query = VectorGenome::from_iter_u8("AACCAAACGGTGTGGCGTTCTTGACGTTT".bytes()).unwrap();
+
+    let costs = [
+        check_template_switch_heuristic::<
+            NoTemplateSwitchMinLengthStrategy<U64Cost>,
+            NoChainingStrategy<U64Cost>,
+        >(&reference, &query, &SeedingMethod::default()),
+        check_template_switch_heuristic::<
+            NoTemplateSwitchMinLengthStrategy<U64Cost>,
+            PrecomputeOnlyChainingStrategy<U64Cost>,
+        >(&reference, &query, &SeedingMethod::default()),
+        check_template_switch_heuristic::<
+            NoTemplateSwitchMinLengthStrategy<U64Cost>,
+            LowerBoundChainingStrategy<U64Cost>,
+        >(&reference, &query, &SeedingMethod::default()),
+        check_template_switch_heuristic::<
+            LookaheadTemplateSwitchMinLengthStrategy<U64Cost>,
+            NoChainingStrategy<U64Cost>,
+        >(&reference, &query, &SeedingMethod::default()),
+        check_template_switch_heuristic::<
+            LookaheadTemplateSwitchMinLengthStrategy<U64Cost>,
+            PrecomputeOnlyChainingStrategy<U64Cost>,
+        >(&reference, &query, &SeedingMethod::default()),
+        check_template_switch_heuristic::<
+            LookaheadTemplateSwitchMinLengthStrategy<U64Cost>,
+            LowerBoundChainingStrategy<U64Cost>,
+        >(&reference, &query, &SeedingMethod::default()),
+    ];
+
+    assert!(costs.iter().all(|cost| *cost == costs[0]), "{costs:?}");
+}
+
+#[test]
+fn chaining_seeding_methods_are_admissible() {
+    for (reference, query) in [
+        (
+            "AACCAACCGGTGTGGGCGTTCTTGAGGTTTACGATCGATCAGGCT",
+            "AACCAAACGGTGTGGCGTTCTTGACGTTTACGTTCGATCAGCT",
+        ),
+        // Long exact matches are covered by many overlapping minimizers.
+        (
+            "ACGGTCATTGCAGTCCATGACGTAGCTTGACC",
+            "ACGGTCATTGCAGTCCATCACGTAGCTTGACC",
+        ),
+    ] {
+        let reference = VectorGenome::<DnaAlphabetOrN>::from_iter_u8(reference.bytes()).unwrap();
+        let query = VectorGenome::from_iter_u8(query.bytes()).unwrap();
+
+        let expected_cost = check_template_switch_heuristic::<
+            NoTemplateSwitchMinLengthStrategy<U64Cost>,
+            NoChainingStrategy<U64Cost>,
+        >(&reference, &query, &SeedingMethod::default());
+
+        for seeding_method in [
+            SeedingMethod::NonOverlapping { block_size: 4 },
+            SeedingMethod::Minimizers {
+                window_size: 3,
+                k: 4,
+            },
+            SeedingMethod::SpacedSeed {
+                pattern: vec![true, true, false, true, true],
+            },
+            // Longer than the sequences.
+            SeedingMethod::NonOverlapping { block_size: 100 },
+        ] {
+            let cost = check_template_switch_heuristic::<
+                NoTemplateSwitchMinLengthStrategy<U64Cost>,
+                LowerBoundChainingStrategy<U64Cost>,
+            >(&reference, &query, &seeding_method);
+            assert_eq!(cost, expected_cost, "{seeding_method:?}");
+        }
+    }
+}
+
+#[test]
+fn heuristic_checker_accepts_gap_affine_lower_bound() {
+    let reference = VectorGenome::<DnaAlphabet>::from_iter_u8("ACGTACGTTTGACA".bytes()).unwrap();
+    let query = VectorGenome::from_iter_u8("ACGTTCGTTGACCA".bytes()).unwrap();
+    let scoring_table = scoring_table();
+
+    let mut a_star = AStar::<_, _>::new_with_observer(
+        gap_affine_edit_distance::Context::new(
+            reference.as_genome_subsequence(),
+            query.as_genome_subsequence(),
+            scoring_table,
+            AlignmentMode::Global,
+        ),
+        HeuristicChecker::new(),
+    );
+    a_star.initialise();
+    assert!(matches!(a_star.search(), AStarResult::FoundTarget { .. }));
+
+    let report = a_star.into_observer().into_report();
+    assert!(report.is_admissible() && report.is_consistent(), "{report}");
+}
+
+fn template_switch_align<Cost: AStarCost>(
+    reference: &VectorGenome<DnaAlphabetOrN>,
+    query: &VectorGenome<DnaAlphabetOrN>,
+    config: TemplateSwitchConfig<DnaAlphabetOrN, Cost>,
+) -> AlignmentResult<AlignmentType, Cost> {
+    template_switch_distance_a_star_align::<TestStrategies<Cost>, _>(
+        reference.as_genome_subsequence(),
+        query.as_genome_subsequence(),
+        config,
+        &AlignmentOptions::default(),
+        (),
+        (),
+    )
+    .unwrap()
+}
+
+#[test]
+fn parallel_matches_sequential() {
+    type Strategies = TestStrategies<U64Cost, LookaheadTemplateSwitchMinLengthStrategy<U64Cost>>;
+    let config = TemplateSwitchConfig::<DnaAlphabetOrN, U64Cost>::read_plain(
+        include_str!("../../../sample_tsa_config/config.tsa").as_bytes(),
+    )
+    .unwrap();
+
+    for (reference, query) in [
+        ("AAAAAACCTTTTTCCCCCCCC", "AAAAAAGGAAAAAGGCCCCCC"),
+        (
+            "AACCAACCGGTGTGGGCGTTCTTGAGGTTT",
+            "AACCAAACGGTGTGGCGTTCTTGACGTTT",
+        ),
+        ("ACGT", ""),
+    ] {
+        let reference = VectorGenome::<DnaAlphabetOrN>::from_iter_u8(reference.bytes()).unwrap();
+        let query = VectorGenome::from_iter_u8(query.bytes()).unwrap();
+        let sequential = template_switch_align(&reference, &query, config.clone());
+
+        for threads in [1, 2, 4] {
+            let parallel = template_switch_distance_parallel_a_star_align::<Strategies, _>(
+                reference.as_genome_subsequence(),
+                query.as_genome_subsequence(),
+                config.clone(),
+                &AlignmentOptions::default(),
+                threads,
+                (),
+            )
+            .unwrap();
+
+            assert_eq!(parallel.statistics().cost, sequential.statistics().cost);
+            assert_eq!(
+                parallel.statistics().template_switch_amount,
+                sequential.statistics().template_switch_amount
+            );
+        }
+    }
+}
+
+#[test]
+fn unsupported_options_are_rejected() {
+    let config = TemplateSwitchConfig::<DnaAlphabetOrN, U64Cost>::read_plain(
+        include_str!("../../../sample_tsa_config/config.tsa").as_bytes(),
+    )
+    .unwrap();
+    let reference = VectorGenome::<DnaAlphabetOrN>::from_iter_u8("ACGTACGT".bytes()).unwrap();
+    let query = VectorGenome::from_iter_u8("ACGTTCGT".bytes()).unwrap();
+
+    for options in [
+        AlignmentOptions::default().with_mode(AlignmentMode::SemiGlobal),
+        AlignmentOptions::default().with_epsilon(Some(2.0)),
+        AlignmentOptions::default().with_initial_cost_limit(Some(1u64.into())),
+        AlignmentOptions::default().with_closed_list_mode(ClosedListMode::Compact),
+    ] {
+        assert!(matches!(
+            template_switch_distance_k_best_a_star_align::<TestStrategies<U64Cost>, _>(
+                reference.as_genome_subsequence(),
+                query.as_genome_subsequence(),
+                config.clone(),
+                &options,
+                2,
+                None,
+                (),
+            ),
+            Err(Error::UnsupportedAlignmentOptions)
+        ));
+        assert!(matches!(
+            template_switch_distance_parallel_a_star_align::<TestStrategies<U64Cost>, _>(
+                reference.as_genome_subsequence(),
+                query.as_genome_subsequence(),
+                config.clone(),
+                &options,
+                2,
+                (),
+            ),
+            Err(Error::UnsupportedAlignmentOptions)
+        ));
+        #[cfg(feature = "serde")]
+        assert!(matches!(
+            super::template_switch_distance_checkpointed_a_star_align::<TestStrategies<U64Cost>, _>(
+                reference.as_genome_subsequence(),
+                query.as_genome_subsequence(),
+                config.clone(),
+                &options,
+                (),
+                None,
+                Duration::ZERO,
+                None,
+                (),
+            ),
+            Err(Error::UnsupportedAlignmentOptions)
+        ));
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn resumed_alignment_matches_uninterrupted() {
+    let config = TemplateSwitchConfig::<DnaAlphabetOrN, U64Cost>::read_plain(
+        include_str!("../../../sample_tsa_config/config.tsa").as_bytes(),
+    )
+    .unwrap();
+    let reference =
+        VectorGenome::<DnaAlphabetOrN>::from_iter_u8("AACCAACCGGTGTGGGCGTTCTTGAGGTTT".bytes())
+            .unwrap();
+    let query = VectorGenome::from_iter_u8("AACCAAACGGTGTGGCGTTCTTGACGTTT".bytes()).unwrap();
+    let checkpoint_file =
+        std::env::temp_dir().join(format!("lib-tsalign-checkpoint-{}", std::process::id()));
+    let checkpointed_align = |checkpoint_file: Option<&std::path::Path>,
+                              resume_file: Option<&std::path::Path>,
+                              observer: &mut CountingObserver| {
+        super::template_switch_distance_checkpointed_a_star_align::<TestStrategies<U64Cost>, _>(
+            reference.as_genome_subsequence(),
+            query.as_genome_subsequence(),
+            config.clone(),
+            &AlignmentOptions::default(),
+            (),
+            checkpoint_file,
+            Duration::ZERO,
+            resume_file,
+            observer,
+        )
+        .unwrap()
+    };
+
+    let uninterrupted = template_switch_align(&reference, &query, config.clone());
+
+    // Without a delay, a checkpoint is written whenever the clock is checked.
+    let mut checkpointed_observer = CountingObserver::default();
+    let checkpointed = checkpointed_align(Some(&checkpoint_file), None, &mut checkpointed_observer);
+    let mut resumed_observer = CountingObserver::default();
+    let resumed = checkpointed_align(None, Some(&checkpoint_file), &mut resumed_observer);
+    std::fs::remove_file(&checkpoint_file).unwrap();
+
+    for result in [&checkpointed, &resumed] {
+        assert_eq!(result.cigar(), uninterrupted.cigar());
+        assert_eq!(result.statistics().cost, uninterrupted.statistics().cost);
+    }
+    // The resumed search continues from the last checkpoint instead of starting over.
+    assert!(resumed_observer.closed_nodes < checkpointed_observer.closed_nodes);
+}
+
+#[test]
+fn partial_alignment_on_cost_limit() {
+    let reference =
+        VectorGenome::<DnaAlphabetOrN>::from_iter_u8("AACCAACCGGTGTGGGCGTTCTTGAGGTTT".bytes())
+            .unwrap();
+    let query = VectorGenome::from_iter_u8("AACCAACCGGTGTGGGCGTTCTTGAGCATA".bytes()).unwrap();
+    let config = TemplateSwitchConfig::<DnaAlphabetOrN, U64Cost>::read_plain(
+        include_str!("../../../sample_tsa_config/config.tsa").as_bytes(),
+    )
+    .unwrap();
+
+    for closed_list_mode in [ClosedListMode::Full, ClosedListMode::Compact] {
+        let result = template_switch_distance_a_star_align::<TestStrategies<U64Cost>, _>(
+            reference.as_genome_subsequence(),
+            query.as_genome_subsequence(),
+            config.clone(),
+            &AlignmentOptions::default()
+                .with_cost_limit(Some(U64Cost::from(2u8)))
+                .with_closed_list_mode(closed_list_mode),
+            (),
+            (),
+        )
+        .unwrap();
+
+        let AlignmentResult::Partial {
+            alignment,
+            partial_cost,
+            reference_end,
+            query_end,
+            ..
+        } = &result
+        else {
+            panic!("Expected a partial alignment, but got {result}");
+        };
+        assert!(matches!(
+            result.statistics().result,
+            AStarResult::ExceededCostLimit { .. }
+        ));
+        // The mismatching suffix cannot be aligned within the cost limit.
+        assert!(*reference_end < 30 || *query_end < 30);
+        assert!(*reference_end + *query_end >= 2 * 25);
+        assert!(*partial_cost <= 2.0);
+
+        let (reference_length, query_length) = alignment.iter_flat_cloned().fold(
+            (0, 0),
+            |(reference_length, query_length), alignment_type| match alignment_type {
+                AlignmentType::PrimaryMatch | AlignmentType::PrimarySubstitution => {
+                    (reference_length + 1, query_length + 1)
+                }
+                AlignmentType::PrimaryDeletion => (reference_length + 1, query_length),
+                AlignmentType::PrimaryInsertion => (reference_length, query_length + 1),
+                _ => (reference_length, query_length),
+            },
+        );
+        assert_eq!(
+            (reference_length, query_length),
+            (*reference_end, *query_end)
+        );
+    }
+}
+
+/// Cancels the search once `cancel_after` nodes have been closed.
+struct CancellingObserver {
+    cancellation_token: CancellationToken,
+    cancel_after: usize,
+    closed_nodes: usize,
+}
+
+impl<Node: AStarNode> AStarObserver<Node> for CancellingObserver {
+    fn on_node_closed(&mut self, _node: &Node) -> ControlFlow<()> {
+        self.closed_nodes += 1;
+        if self.closed_nodes == self.cancel_after {
+            self.cancellation_token.cancel();
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+#[test]
+fn interrupted_alignment() {
+    let reference =
+        VectorGenome::<DnaAlphabetOrN>::from_iter_u8("AACCAACCGGTGTGGGCGTTCTTGAGGTTT".bytes())
+            .unwrap();
+    let query = VectorGenome::from_iter_u8("AACCAACCGGTGTGGGCGTTCTTGAGCATA".bytes()).unwrap();
+    let config = TemplateSwitchConfig::<DnaAlphabetOrN, U64Cost>::read_plain(
+        include_str!("../../../sample_tsa_config/config.tsa").as_bytes(),
+    )
+    .unwrap();
+    let align = |options: &AlignmentOptions<U64Cost>, observer: Option<&mut CancellingObserver>| {
+        template_switch_distance_a_star_align::<TestStrategies<U64Cost>, _>(
+            reference.as_genome_subsequence(),
+            query.as_genome_subsequence(),
+            config.clone(),
+            options,
+            (),
+            observer,
+        )
+        .unwrap()
+    };
+
+    // A deadline that has passed when the search starts aborts it before any node is closed.
+    let result = align(
+        &AlignmentOptions::default().with_time_limit(Some(Duration::ZERO)),
+        None,
+    );
+    assert!(
+        matches!(result, AlignmentResult::WithoutTarget { .. }),
+        "Expected no alignment, but got {result}"
+    );
+    assert!(matches!(
+        result.statistics().result,
+        AStarResult::ExceededTimeLimit { .. }
+    ));
+
+    // Cancelling a running search returns the furthest alignment so far.
+    let cancellation_token = CancellationToken::new();
+    let mut observer = CancellingObserver {
+        cancellation_token: cancellation_token.clone(),
+        cancel_after: 50,
+        closed_nodes: 0,
+    };
+    let result = align(
+        &AlignmentOptions::default().with_cancellation_token(Some(cancellation_token)),
+        Some(&mut observer),
+    );
+    let AlignmentResult::Partial {
+        reference_end,
+        query_end,
+        ..
+    } = &result
+    else {
+        panic!("Expected a partial alignment, but got {result}");
+    };
+    assert!(matches!(
+        result.statistics().result,
+        AStarResult::Cancelled { .. }
+    ));
+    assert!(*reference_end > 0 && *query_end > 0);
+    assert_eq!(observer.closed_nodes, 50);
+}
+
+#[test]
+fn decimal_costs() {
+    let reference =
+        VectorGenome::<DnaAlphabetOrN>::from_iter_u8("AACCAACCGGTGTGGGCGTTCTTGAGGTTT".bytes())
+            .unwrap();
+    let query = VectorGenome::from_iter_u8("AACCAAACGGTGTGGCGTTCTTGACGTTT".bytes()).unwrap();
+    let config = include_str!("../../../sample_tsa_config/config.tsa");
+
+    let integer = template_switch_align::<U64Cost>(
+        &reference,
+        &query,
+        TemplateSwitchConfig::read_plain(config.as_bytes()).unwrap(),
+    );
+    let float = template_switch_align::<F64Cost>(
+        &reference,
+        &query,
+        TemplateSwitchConfig::read_plain(config.as_bytes()).unwrap(),
+    );
+    assert_eq!(integer.statistics().cost, float.statistics().cost);
+
+    // Make opening a gap in the primary alignment cheaper by a fraction.
+    let decimal_config = config.replacen(" 3 3 3 3 3", " 2.5 2.5 2.5 2.5 2.5", 1);
+    assert!(
+        TemplateSwitchConfig::<DnaAlphabetOrN, U64Cost>::read_plain(decimal_config.as_bytes())
+            .is_err()
+    );
+    let decimal = template_switch_align::<F64Cost>(
+        &reference,
+        &query,
+        TemplateSwitchConfig::read_plain(decimal_config.as_bytes()).unwrap(),
+    );
+    assert!(decimal.statistics().cost < integer.statistics().cost);
+    assert_eq!(decimal.statistics().cost.raw().fract(), 0.5);
+}
+
+/// Aligns with the negative costs from `test_files/config/negative`.
+///
+/// Panics if the lower bound is not admissible.
+fn negative_costs_align<TemplateSwitchMinLength: TemplateSwitchMinLengthStrategy<I64Cost>>(
+    reference: &VectorGenome<DnaAlphabetOrN>,
+    query: &VectorGenome<DnaAlphabetOrN>,
+    mode: AlignmentMode,
+) -> AlignmentResult<AlignmentType, I64Cost> {
+    let config = TemplateSwitchConfig::read_plain(
+        include_str!("../../../test_files/config/negative/config.tsa").as_bytes(),
+    )
+    .unwrap();
+    let mut heuristic_checker = HeuristicChecker::new();
+
+    let alignment = template_switch_distance_a_star_align::<
+        TestStrategies<I64Cost, TemplateSwitchMinLength>,
+        _,
+    >(
+        reference.as_genome_subsequence(),
+        query.as_genome_subsequence(),
+        config,
+        &AlignmentOptions::default().with_mode(mode),
+        (),
+        &mut heuristic_checker,
+    )
+    .unwrap();
+
+    let report = heuristic_checker.into_report();
+    assert!(report.is_admissible(), "{report}");
+    alignment
+}
+
+#[test]
+fn negative_costs() {
+    // The negative config equals the non-negative config minus one per character of the reference and the query.
+    // Hence, the optimal costs differ by exactly the length of both sequences.
+    let non_negative_config = TemplateSwitchConfig::<DnaAlphabetOrN, U64Cost>::read_plain(
+        include_str!("../../../test_files/config/negative/non_negative.tsa").as_bytes(),
+    )
+    .unwrap();
+
+    for (reference, query, template_switch_amount) in [
+        ("AAAAAACCTTTTTCCCCCCCC", "AAAAAAGGAAAAAGGCCCCCC", 1.0),
+        (
+            "AACCAACCGGTGTGGGCGTTCTTGAGGTTT",
+            "AACCAAACGGTGTGGCGTTCTTGACGTTT",
+            0.0,
+        ),
+        ("ACGTACGTTTGACA", "ACGTTCGTTGACCAGGT", 0.0),
+    ] {
+        let length = (reference.len() + query.len()) as f64;
+        let reference = VectorGenome::<DnaAlphabetOrN>::from_iter_u8(reference.bytes()).unwrap();
+        let query = VectorGenome::from_iter_u8(query.bytes()).unwrap();
+
+        let non_negative = template_switch_align(&reference, &query, non_negative_config.clone());
+        assert_eq!(
+            non_negative.statistics().template_switch_amount.raw(),
+            template_switch_amount
+        );
+
+        for negative in [
+            negative_costs_align::<NoTemplateSwitchMinLengthStrategy<I64Cost>>(
+                &reference,
+                &query,
+                AlignmentMode::Global,
+            ),
+            negative_costs_align::<LookaheadTemplateSwitchMinLengthStrategy<I64Cost>>(
+                &reference,
+                &query,
+                AlignmentMode::Global,
+            ),
+        ] {
+            assert!(negative.statistics().cost < 0.0);
+            assert_eq!(
+                negative.statistics().cost.raw(),
+                non_negative.statistics().cost.raw() - length
+            );
+            assert_eq!(
+                negative.statistics().template_switch_amount.raw(),
+                template_switch_amount
+            );
+        }
+    }
+}
+
+#[test]
+fn local_alignment() {
+    let reference =
+        VectorGenome::<DnaAlphabetOrN>::from_iter_u8("CCCCCCCCACGTACGTTTGACACCCCCCCC".bytes())
+            .unwrap();
+    let query = VectorGenome::from_iter_u8("ACGTACGTTTGACA".bytes()).unwrap();
+
+    // The query is aligned inside the reference, clipping the flanking Cs of the reference.
+    for negative in [
+        negative_costs_align::<NoTemplateSwitchMinLengthStrategy<I64Cost>>(
+            &reference,
+            &query,
+            AlignmentMode::Local,
+        ),
+        negative_costs_align::<LookaheadTemplateSwitchMinLengthStrategy<I64Cost>>(
+            &reference,
+            &query,
+            AlignmentMode::Local,
+        ),
+    ] {
+        assert_eq!(negative.cigar(), "[SC:R8Q0]14M[SC:R8Q0]");
+        assert_eq!(negative.statistics().cost.raw(), -28.0);
+    }
+
+    let global = negative_costs_align::<NoTemplateSwitchMinLengthStrategy<I64Cost>>(
+        &reference,
+        &query,
+        AlignmentMode::Global,
+    );
+    assert!(global.statistics().cost.raw() > -28.0);
+
+    let scoring_table = ScoringTable::<I64Cost> {
+        match_cost: (-2i64).into(),
+        substitution_cost: 2u8.into(),
+        gap_open_cost: 2u8.into(),
+        gap_extend_cost: 0u8.into(),
+    };
+    let gap_affine_local = gap_affine_edit_distance_a_star_align(
+        reference.as_genome_subsequence(),
+        query.as_genome_subsequence(),
+        scoring_table,
+        AlignmentMode::Local,
+    )
+    .unwrap();
+    assert_eq!(gap_affine_local.cigar(), "[SC:R8Q0]14M[SC:R8Q0]");
+    assert_eq!(gap_affine_local.statistics().cost.raw(), -28.0);
+    let gap_affine_global = gap_affine_edit_distance_a_star_align(
+        reference.as_genome_subsequence(),
+        query.as_genome_subsequence(),
+        scoring_table,
+        AlignmentMode::Global,
+    )
+    .unwrap();
+    assert_eq!(gap_affine_global.statistics().cost.raw(), -24.0);
+}
+
+#[test]
+fn clipping_everything_requires_negative_costs() {
+    let reference =
+        VectorGenome::<DnaAlphabetOrN>::from_iter_u8("CCCCCCCCACGTACGTTTGACACCCCCCCC".bytes())
+            .unwrap();
+    let query = VectorGenome::from_iter_u8("ACGTACGTTTGACA".bytes()).unwrap();
+    let config = TemplateSwitchConfig::<DnaAlphabetOrN, U64Cost>::read_plain(
+        include_str!("../../../sample_tsa_config/config.tsa").as_bytes(),
+    )
+    .unwrap();
+
+    // Without negative costs, the empty alignment would be optimal.
+    for mode in [AlignmentMode::Local, AlignmentMode::Overlap] {
+        assert!(matches!(
+            gap_affine_edit_distance_a_star_align(
+                reference.as_genome_subsequence(),
+                query.as_genome_subsequence(),
+                scoring_table(),
+                mode,
+            ),
+            Err(Error::ClippingWithoutNegativeCosts { mode: error_mode }) if error_mode == mode
+        ));
+        assert!(matches!(
+            template_switch_distance_a_star_align::<TestStrategies<U64Cost>, _>(
+                reference.as_genome_subsequence(),
+                query.as_genome_subsequence(),
+                config.clone(),
+                &AlignmentOptions::default().with_mode(mode),
+                (),
+                (),
+            ),
+            Err(Error::ClippingWithoutNegativeCosts { mode: error_mode }) if error_mode == mode
+        ));
+    }
+}
+
+#[test]
+fn semi_global_and_overlap_alignment() {
+    let reference =
+        VectorGenome::<DnaAlphabetOrN>::from_iter_u8("CCCCCCCCACGTACGTTTGACACCCCCCCC".bytes())
+            .unwrap();
+    let query = VectorGenome::from_iter_u8("ACGTACGTTTGACA".bytes()).unwrap();
+
+    // Semi-global alignments clip the reference without requiring negative costs.
+    let semi_global = gap_affine_edit_distance_a_star_align(
+        reference.as_genome_subsequence(),
+        query.as_genome_subsequence(),
+        ScoringTable::<U64Cost> {
+            match_cost: 0u8.into(),
+            substitution_cost: 2u8.into(),
+            gap_open_cost: 2u8.into(),
+            gap_extend_cost: 1u8.into(),
+        },
+        AlignmentMode::SemiGlobal,
+    )
+    .unwrap();
+    assert_eq!(semi_global.cigar(), "[SC:R8Q0]14M[SC:R8Q0]");
+    assert_eq!(semi_global.statistics().cost.raw(), 0.0);
+
+    for negative in [
+        negative_costs_align::<NoTemplateSwitchMinLengthStrategy<I64Cost>>(
+            &reference,
+            &query,
+            AlignmentMode::SemiGlobal,
+        ),
+        negative_costs_align::<LookaheadTemplateSwitchMinLengthStrategy<I64Cost>>(
+            &reference,
+            &query,
+            AlignmentMode::SemiGlobal,
+        ),
+    ] {
+        assert_eq!(negative.cigar(), "[SC:R8Q0]14M[SC:R8Q0]");
+        assert_eq!(negative.statistics().cost.raw(), -28.0);
+    }
+
+    // The end of the reference overlaps the start of the query.
+    let reference =
+        VectorGenome::<DnaAlphabetOrN>::from_iter_u8("CCCCCCCCACGTACGTTT".bytes()).unwrap();
+    let query = VectorGenome::from_iter_u8("ACGTACGTTTCCCCCCCC".bytes()).unwrap();
+    let scoring_table = ScoringTable::<I64Cost> {
+        match_cost: (-2i64).into(),
+        substitution_cost: 2u8.into(),
+        gap_open_cost: 2u8.into(),
+        gap_extend_cost: 0u8.into(),
+    };
+
+    let overlap = gap_affine_edit_distance_a_star_align(
+        reference.as_genome_subsequence(),
+        query.as_genome_subsequence(),
+        scoring_table,
+        AlignmentMode::Overlap,
+    )
+    .unwrap();
+    assert_eq!(overlap.cigar(), "[SC:R8Q0]10M[SC:R0Q8]");
+    assert_eq!(overlap.statistics().cost.raw(), -20.0);
+
+    let negative = negative_costs_align::<NoTemplateSwitchMinLengthStrategy<I64Cost>>(
+        &reference,
+        &query,
+        AlignmentMode::Overlap,
+    );
+    assert_eq!(negative.cigar(), "[SC:R8Q0]10M[SC:R0Q8]");
+    assert_eq!(negative.statistics().cost.raw(), -20.0);
+
+    // Semi-global alignments cannot clip the query.
+    let semi_global = gap_affine_edit_distance_a_star_align(
+        reference.as_genome_subsequence(),
+        query.as_genome_subsequence(),
+        scoring_table,
+        AlignmentMode::SemiGlobal,
+    )
+    .unwrap();
+    assert!(semi_global.statistics().cost.raw() > -20.0);
+}
+
+#[test]
+fn epsilon_rejects_negative_costs() {
+    let reference = VectorGenome::<DnaAlphabetOrN>::from_iter_u8("ACGTACGTTTGACA".bytes()).unwrap();
+    let query = VectorGenome::from_iter_u8("ACGTTCGTTGACCA".bytes()).unwrap();
+    let config = TemplateSwitchConfig::<DnaAlphabetOrN, I64Cost>::read_plain(
+        include_str!("../../../test_files/config/negative/config.tsa").as_bytes(),
+    )
+    .unwrap();
+
+    assert!(matches!(
+        template_switch_distance_a_star_align::<TestStrategies<I64Cost>, _>(
+            reference.as_genome_subsequence(),
+            query.as_genome_subsequence(),
+            config,
+            &AlignmentOptions::default().with_epsilon(Some(2.0)),
+            (),
+            (),
+        ),
+        Err(Error::EpsilonWithNegativeCosts)
+    ));
+}
+
+#[test]
+fn unsupported_negative_costs() {
+    let config = include_str!("../../../test_files/config/negative/config.tsa");
+
+    // Negative secondary edit costs.
+    assert!(
+        TemplateSwitchConfig::<DnaAlphabetOrN, I64Cost>::read_plain(
+            config.replacen("A |  0  8", "A | -1  8", 1).as_bytes()
+        )
+        .is_err()
+    );
+
+    // Template switches that rewind by more than they cost.
+    let rewinding_config =
+        config.replacen(" -inf 0   1\n  inf 0 inf", " -inf -20 1\n  inf   0 inf", 1);
+    assert_ne!(rewinding_config, config);
+    assert!(
+        TemplateSwitchConfig::<DnaAlphabetOrN, I64Cost>::read_plain(rewinding_config.as_bytes())
+            .is_err()
+    );
+
+    // Rewinding is fine if it is expensive enough.
+    let expensive_rewinding_config = rewinding_config.replace("_cost = 2", "_cost = 40");
+    assert!(
+        TemplateSwitchConfig::<DnaAlphabetOrN, I64Cost>::read_plain(
+            expensive_rewinding_config.as_bytes()
+        )
+        .is_ok()
+    );
+}
+
+#[test]
+fn forward_template_switch() {
+    // The query contains a tandem duplication of `GTGTGGGC`.
+    let reference =
+        VectorGenome::<DnaAlphabetOrN>::from_iter_u8("AACCAACCGGTGTGGGCGTTCTTGAGGTTT".bytes())
+            .unwrap();
+    let query =
+        VectorGenome::from_iter_u8("AACCAACCGGTGTGGGCGTGTGGGCGTTCTTGAGGTTT".bytes()).unwrap();
+
+    let reverse = template_switch_align::<U64Cost>(
+        &reference,
+        &query,
+        TemplateSwitchConfig::read_plain(
+            include_str!("../../../sample_tsa_config/config.tsa").as_bytes(),
+        )
+        .unwrap(),
+    );
+    let forward = template_switch_align::<U64Cost>(
+        &reference,
+        &query,
+        TemplateSwitchConfig::read_plain(
+            include_str!("../../../test_files/config/forward/config.tsa").as_bytes(),
+        )
+        .unwrap(),
+    );
+
+    assert!(forward.statistics().cost < reverse.statistics().cost);
+    assert!(
+        forward
+            .alignment()
+            .unwrap()
+            .iter_flat_cloned()
+            .any(|alignment_type| matches!(
+                alignment_type,
+                AlignmentType::TemplateSwitchEntrance {
+                    direction: TemplateSwitchDirection::Forward,
+                    ..
+                }
+            ))
+    );
 }
@@ -1,7 +1,14 @@
 use compact_genome::interface::alphabet::Alphabet;
+use generic_a_star::cost::AStarCost;
 use num_traits::bounds::UpperBounded;
 
-use crate::costs::{cost_function::CostFunction, gap_affine::GapAffineAlignmentCostTable};
+use crate::{
+    a_star_aligner::template_switch_distance::{
+        TemplateSwitchDirection, TemplateSwitchPrimary, TemplateSwitchSecondary,
+    },
+    costs::{cost_function::CostFunction, gap_affine::GapAffineAlignmentCostTable},
+    error::{Error, Result},
+};
 
 pub mod io;
 
@@ -27,12 +34,21 @@ pub struct TemplateSwitchConfig<AlphabetType, Cost> {
     pub length_difference_costs: CostFunction<isize, Cost>,
 }
 
+/// The base costs of template switches, by their primary and secondary sequence.
+///
+/// The first letter names the primary and the second letter the secondary sequence.
+/// The `_forward` costs are for [forward](TemplateSwitchDirection::Forward) template switches,
+/// and the others for the classic [reverse](TemplateSwitchDirection::Reverse) template switches.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct BaseCost<Cost> {
     pub rr: Cost,
     pub rq: Cost,
     pub qr: Cost,
     pub qq: Cost,
+    pub rr_forward: Cost,
+    pub rq_forward: Cost,
+    pub qr_forward: Cost,
+    pub qq_forward: Cost,
 }
 
 impl<Cost: UpperBounded> BaseCost<Cost> {
@@ -42,10 +58,185 @@ impl<Cost: UpperBounded> BaseCost<Cost> {
             rq: Cost::max_value(),
             qr: Cost::max_value(),
             qq: Cost::max_value(),
+            rr_forward: Cost::max_value(),
+            rq_forward: Cost::max_value(),
+            qr_forward: Cost::max_value(),
+            qq_forward: Cost::max_value(),
         }
     }
 }
 
+impl<Cost: Copy> BaseCost<Cost> {
+    pub fn get(
+        &self,
+        primary: TemplateSwitchPrimary,
+        secondary: TemplateSwitchSecondary,
+        direction: TemplateSwitchDirection,
+    ) -> Cost {
+        use TemplateSwitchDirection::{Forward, Reverse};
+        use TemplateSwitchPrimary as P;
+        use TemplateSwitchSecondary as S;
+
+        match (primary, secondary, direction) {
+            (P::Reference, S::Reference, Reverse) => self.rr,
+            (P::Reference, S::Query, Reverse) => self.rq,
+            (P::Query, S::Reference, Reverse) => self.qr,
+            (P::Query, S::Query, Reverse) => self.qq,
+            (P::Reference, S::Reference, Forward) => self.rr_forward,
+            (P::Reference, S::Query, Forward) => self.rq_forward,
+            (P::Query, S::Reference, Forward) => self.qr_forward,
+            (P::Query, S::Query, Forward) => self.qq_forward,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Cost> {
+        [
+            self.rr,
+            self.rq,
+            self.qr,
+            self.qq,
+            self.rr_forward,
+            self.rq_forward,
+            self.qr_forward,
+            self.qq_forward,
+        ]
+        .into_iter()
+    }
+}
+
+impl<AlphabetType: Alphabet, Cost: AStarCost> TemplateSwitchConfig<AlphabetType, Cost> {
+    /// Returns the minimum cost of a match or substitution outside of template switches, or zero if no such step has a negative cost.
+    ///
+    /// Negative costs are supported only in the primary and flank edit costs, see [`check_negative_costs`](Self::check_negative_costs).
+    pub fn min_negative_diagonal_cost(&self) -> Cost {
+        self.primary_and_flank_edit_costs()
+            .flat_map(|edit_costs| {
+                [
+                    edit_costs.min_match_cost(),
+                    edit_costs.min_substitution_cost(),
+                ]
+            })
+            .fold(Cost::zero(), Ord::min)
+    }
+
+    /// Returns the minimum cost of a gap character outside of template switches, or zero if no such step has a negative cost.
+    ///
+    /// Negative costs are supported only in the primary and flank edit costs, see [`check_negative_costs`](Self::check_negative_costs).
+    pub fn min_negative_gap_cost(&self) -> Cost {
+        self.primary_and_flank_edit_costs()
+            .flat_map(|edit_costs| {
+                [
+                    edit_costs.min_gap_open_cost(),
+                    edit_costs.min_gap_extend_cost(),
+                ]
+            })
+            .fold(Cost::zero(), Ord::min)
+    }
+
+    /// Returns true if any alignment step outside of template switches has a negative cost.
+    pub fn has_negative_costs(&self) -> bool {
+        self.min_negative_diagonal_cost()
+            .min(self.min_negative_gap_cost())
+            < Cost::zero()
+    }
+
+    fn primary_and_flank_edit_costs(
+        &self,
+    ) -> impl Iterator<Item = &GapAffineAlignmentCostTable<AlphabetType, Cost>> {
+        [
+            &self.primary_edit_costs,
+            &self.left_flank_edit_costs,
+            &self.right_flank_edit_costs,
+        ]
+        .into_iter()
+    }
+
+    /// Returns the maximum number of characters by which exiting a template switch moves the anti-primary sequence backwards.
+    ///
+    /// This is measured relative to the index of the anti-primary sequence at the entrance of the template switch.
+    pub fn max_template_switch_rewind(&self) -> usize {
+        let Some(min_length_difference) = self.length_difference_costs.minimum_finite_input()
+        else {
+            return 0;
+        };
+
+        let min_length = isize::try_from(self.min_length).unwrap_or(isize::MAX);
+        min_length
+            .saturating_add(min_length_difference)
+            .saturating_neg()
+            .max(0)
+            .unsigned_abs()
+    }
+
+    /// Checks if the negative costs of this config can be handled by the aligner.
+    ///
+    /// Negative costs are supported in the primary and flank edit costs only.
+    /// Additionally, template switches must not be able to rewind by an unbounded number of characters, and they must cost at least as much as the negative costs they allow to collect again by rewinding.
+    /// Otherwise, the alignment may contain arbitrarily many template switches that each lower its cost.
+    ///
+    /// Configs without negative costs are always supported.
+    pub fn check_negative_costs(&self) -> Result<()> {
+        let base_cost = self.base_cost.iter().min().unwrap();
+        let offset_cost = self.offset_costs.min(..).unwrap();
+        let length_cost = self.length_costs.min(..).unwrap();
+        let length_difference_cost = self.length_difference_costs.min(..).unwrap();
+
+        for (costs, cost) in [
+            ("base costs", base_cost),
+            ("offset costs", offset_cost),
+            ("length costs", length_cost),
+            ("length difference costs", length_difference_cost),
+            ("secondary edit costs", self.secondary_edit_costs.min_cost()),
+        ] {
+            if cost < Cost::zero() {
+                return Err(Error::UnsupportedNegativeCosts { costs });
+            }
+        }
+
+        let min_step_cost = self
+            .min_negative_diagonal_cost()
+            .min(self.min_negative_gap_cost());
+        if min_step_cost == Cost::zero() {
+            return Ok(());
+        }
+        if min_step_cost == Cost::min_value() {
+            return Err(Error::NegativeInfiniteCost);
+        }
+
+        let (Some(jump_cost), Some(min_length), Some(min_length_difference)) = (
+            base_cost
+                .checked_add(&offset_cost)
+                .and_then(|jump_cost| jump_cost.checked_add(&length_cost))
+                .filter(|jump_cost| *jump_cost != Cost::max_value()),
+            isize::try_from(self.min_length).ok(),
+            self.length_difference_costs.minimum_finite_input(),
+        ) else {
+            // Template switches cannot be taken.
+            return Ok(());
+        };
+        if min_length_difference == isize::MIN {
+            return Err(Error::UnboundedTemplateSwitchRewind);
+        }
+
+        // A template switch of length `l` and length difference `d` moves the anti-primary sequence by `l + d` characters.
+        // If `l + d` is negative, then the template switch allows to collect the negative costs of `-(l + d)` characters again.
+        let mut rewind_cost = Cost::zero();
+        for length_difference in (min_length_difference..min_length.saturating_neg()).rev() {
+            rewind_cost -= min_step_cost;
+            let length_difference_cost = self.length_difference_costs.evaluate(&length_difference);
+
+            if jump_cost
+                .checked_add(&length_difference_cost)
+                .is_some_and(|cost| cost != Cost::max_value() && cost < rewind_cost)
+            {
+                return Err(Error::CheapTemplateSwitchRewind { length_difference });
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl<AlphabetType: Alphabet, Cost: Clone> Clone for TemplateSwitchConfig<AlphabetType, Cost> {
     fn clone(&self) -> Self {
         Self {
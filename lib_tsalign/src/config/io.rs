@@ -19,13 +19,18 @@ use crate::{
 use super::TemplateSwitchConfig;
 
 impl<AlphabetType: Alphabet, Cost: AStarCost> TemplateSwitchConfig<AlphabetType, Cost> {
+    /// Reads a config from its plain text format.
+    ///
+    /// Fails if the config contains [unsupported negative costs](Self::check_negative_costs).
     pub fn read_plain(mut reader: impl std::io::Read) -> crate::error::Result<Self> {
         let mut input = String::new();
         reader.read_to_string(&mut input)?;
         let input = input.as_str();
-        Self::parse_plain(input)
+        let config = Self::parse_plain(input)
             .map(|(_, result)| result)
-            .map_err(translate_nom_error)
+            .map_err(translate_nom_error)?;
+        config.check_negative_costs()?;
+        Ok(config)
     }
 
     fn parse_plain(input: &str) -> IResult<&str, Self> {
@@ -40,6 +45,15 @@ impl<AlphabetType: Alphabet, Cost: AStarCost> TemplateSwitchConfig<AlphabetType,
         let (input, rq_cost) = parse_specific_equals_value("rq_cost")(input)?;
         let (input, qr_cost) = parse_specific_equals_value("qr_cost")(input)?;
         let (input, qq_cost) = parse_specific_equals_value("qq_cost")(input)?;
+        // Forward template switches are optional, and disabled if their costs are missing.
+        let (input, rr_forward_cost) =
+            parse_optional_specific_equals_value("rr_forward_cost")(input)?;
+        let (input, rq_forward_cost) =
+            parse_optional_specific_equals_value("rq_forward_cost")(input)?;
+        let (input, qr_forward_cost) =
+            parse_optional_specific_equals_value("qr_forward_cost")(input)?;
+        let (input, qq_forward_cost) =
+            parse_optional_specific_equals_value("qq_forward_cost")(input)?;
 
         trace!("Parsing jump costs");
         let (input, ()) = parse_specific_name("Jump Costs")(input)?;
@@ -71,6 +85,10 @@ impl<AlphabetType: Alphabet, Cost: AStarCost> TemplateSwitchConfig<AlphabetType,
                     rq: rq_cost,
                     qr: qr_cost,
                     qq: qq_cost,
+                    rr_forward: rr_forward_cost.unwrap_or(Cost::max_value()),
+                    rq_forward: rq_forward_cost.unwrap_or(Cost::max_value()),
+                    qr_forward: qr_forward_cost.unwrap_or(Cost::max_value()),
+                    qq_forward: qq_forward_cost.unwrap_or(Cost::max_value()),
                 },
 
                 primary_edit_costs,
@@ -118,6 +136,24 @@ fn parse_specific_equals_value<Value: FromStr>(
     }
 }
 
+/// Parses `identifier = value` if the next identifier is `identifier`, and otherwise consumes nothing.
+fn parse_optional_specific_equals_value<Value: FromStr>(
+    identifier: &str,
+) -> impl '_ + FnMut(&str) -> IResult<&str, Option<Value>> {
+    move |input| {
+        let actual_identifier: IResult<&str, &str> =
+            take_while1(|c: char| c.is_alphanumeric() || c == '_')(skip_any_whitespace(input)?);
+
+        match actual_identifier {
+            Ok((_, actual_identifier)) if actual_identifier == identifier => {
+                parse_specific_equals_value(identifier)(input)
+                    .map(|(input, value)| (input, Some(value)))
+            }
+            _ => Ok((input, None)),
+        }
+    }
+}
+
 fn parse_equals_value<Value: FromStr>(input: &str) -> IResult<&str, (&str, Value)> {
     let input = skip_any_whitespace(input)?;
     let (input, identifier) = take_while1(|c: char| c.is_alphanumeric() || c == '_')(input)?;
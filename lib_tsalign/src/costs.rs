@@ -1,4 +1,45 @@
 pub mod cost_function;
 pub mod gap_affine;
 
-pub use generic_a_star::cost::U64Cost;
+pub use generic_a_star::cost::{AStarCost, F64Cost, I64Cost, U64Cost};
+
+/// Returns a lower bound on the cost of aligning the given numbers of remaining reference and query characters.
+///
+/// The costs `min_diagonal_cost` and `min_gap_cost` are the minimum costs of a match or substitution and of a gap character,
+/// or zero if no such step has a negative cost.
+/// The remaining characters are aligned by at most as many matches or substitutions as the shorter of both has characters, and by gaps otherwise.
+/// Hence, the remaining cost is at least the minimum over the two extremes of aligning as many characters as possible by matches or substitutions, or aligning all characters by gaps.
+/// Since both costs are at most zero, this also bounds the cost of aligning only some of the remaining characters.
+pub fn remaining_characters_lower_bound<Cost: AStarCost>(
+    min_diagonal_cost: Cost,
+    min_gap_cost: Cost,
+    remaining_reference: usize,
+    remaining_query: usize,
+) -> Cost {
+    let characters = remaining_reference + remaining_query;
+    let diagonals = remaining_reference.min(remaining_query);
+    let only_gaps = multiply_cost(min_gap_cost, characters);
+    let max_diagonals = multiply_cost(min_diagonal_cost, diagonals)
+        + multiply_cost(min_gap_cost, characters - 2 * diagonals);
+
+    only_gaps.min(max_diagonals)
+}
+
+/// Multiplies a cost with a factor by repeated doubling.
+fn multiply_cost<Cost: AStarCost>(cost: Cost, mut factor: usize) -> Cost {
+    let mut result = Cost::zero();
+    let mut power = cost;
+
+    while factor > 0 {
+        if factor & 1 == 1 {
+            result += power;
+        }
+
+        factor >>= 1;
+        if factor > 0 {
+            power += power;
+        }
+    }
+
+    result
+}
@@ -118,6 +118,9 @@ impl<SourceType: FromStr + PrimInt, Cost: FromStr + Bounded> CostFunction<Source
     }
 }
 
+/// Parses a number or `inf`, optionally preceded by a sign.
+///
+/// Numbers may have a fractional part, which fails to parse if `Output` is an integer type.
 pub fn parse_inf_value<Output: FromStr + Bounded>(input: &str) -> IResult<&str, Output> {
     let mut length = 0;
 
@@ -149,6 +152,9 @@ pub fn parse_inf_value<Output: FromStr + Bounded>(input: &str) -> IResult<&str,
         }
     } else {
         length += digit1(&input[length..])?.1.len();
+        if let Some(fraction) = input[length..].strip_prefix('.') {
+            length += 1 + digit1(fraction)?.1.len();
+        }
         let result = Output::from_str(&input[..length]).map_err(|_| {
             nom::Err::Failure(nom::error::Error {
                 input,
@@ -162,7 +168,9 @@ pub fn parse_inf_value<Output: FromStr + Bounded>(input: &str) -> IResult<&str,
 
 #[cfg(test)]
 mod tests {
-    use generic_a_star::cost::U64Cost;
+    use generic_a_star::cost::{F64Cost, U64Cost};
+
+    use num_traits::Bounded;
 
     use crate::costs::cost_function::CostFunction;
 
@@ -196,4 +204,32 @@ mod tests {
         assert_eq!(expected_parsing_result, actual_parsing_result);
         assert_eq!(expected_output, output);
     }
+
+    #[test]
+    fn decimal_example() {
+        let input = "-inf -1 0 2 inf\n 0.5 1 1.25 +2.0 inf";
+        let expected_output = "-inf -1    0 2 inf\n 0.5  1 1.25 2 inf";
+        let expected_parsing_result = CostFunction::<isize, F64Cost> {
+            function: vec![
+                (isize::MIN, 0.5.into()),
+                (-1, 1.0.into()),
+                (0, 1.25.into()),
+                (2, 2.0.into()),
+                (isize::MAX, F64Cost::max_value()),
+            ],
+        };
+
+        let (remaining_input, actual_parsing_result) =
+            CostFunction::<isize, F64Cost>::parse_plain(input).unwrap();
+        assert!(remaining_input.is_empty());
+
+        let mut writer = Vec::new();
+        actual_parsing_result.write_plain(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        assert_eq!(expected_parsing_result, actual_parsing_result);
+        assert_eq!(expected_output, output);
+
+        assert!(CostFunction::<isize, U64Cost>::parse_plain(input).is_err());
+    }
 }
@@ -144,6 +144,17 @@ impl<AlphabetType: Alphabet, Cost: AStarCost> GapAffineAlignmentCostTable<Alphab
         self.gap_extend_cost_vector.iter().min().copied().unwrap()
     }
 
+    /// Returns the minimum over all match, substitution, gap open and gap extend costs.
+    pub fn min_cost(&self) -> Cost {
+        self.substitution_cost_table
+            .iter()
+            .chain(&self.gap_open_cost_vector)
+            .chain(&self.gap_extend_cost_vector)
+            .min()
+            .copied()
+            .unwrap()
+    }
+
     /// Fill all costs with their minimum over all characters.
     ///
     /// Gap open costs and gap extend costs are set to the minimum value over all characters.
@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::a_star_aligner::alignment_geometry::AlignmentMode;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug, Error)]
@@ -7,6 +9,10 @@ pub enum Error {
     #[error("An IO error occurred: {0}.")]
     Io(#[from] std::io::Error),
 
+    #[cfg(feature = "serde")]
+    #[error("A checkpoint could not be written or read: {0}.")]
+    Checkpoint(#[from] bincode::Error),
+
     #[error("A parsing error of kind '{kind:?}' occurred when the remaining input was '{input}'.")]
     Parser {
         input: String,
@@ -31,4 +37,45 @@ pub enum Error {
         "A cost function was attempted to create from a sequence whose index does not strictly increase at {index}."
     )]
     CostFunctionIndexNotIncreasing { index: usize },
+
+    #[error(
+        "Negative costs are only supported in the primary and flank edit costs, but the {costs} contain negative costs."
+    )]
+    UnsupportedNegativeCosts { costs: &'static str },
+
+    #[error("Negative infinite costs are not supported.")]
+    NegativeInfiniteCost,
+
+    #[error(
+        "With negative edit costs, the length difference of template switches must be bounded from below."
+    )]
+    UnboundedTemplateSwitchRewind,
+
+    #[error(
+        "With negative edit costs, template switches must cost at least the negative edit costs they allow to collect again by rewinding, but template switches with length difference {length_difference} may cost less."
+    )]
+    CheapTemplateSwitchRewind { length_difference: isize },
+
+    #[error(
+        "Bidirectional search is not supported with negative costs, since its termination criterion requires non-negative costs."
+    )]
+    BidirectionalWithNegativeCosts,
+
+    #[error(
+        "Weighting the lower bounds with an epsilon is not supported with negative costs, since then the suboptimality bound does not hold."
+    )]
+    EpsilonWithNegativeCosts,
+
+    #[error(
+        "The alignment mode, epsilon, initial cost limit and closed list mode are only supported by the default A* alignment, not by the k-best, parallel or checkpointed alignments."
+    )]
+    UnsupportedAlignmentOptions,
+
+    #[error(
+        "In {mode:?} mode, clipping both sequences completely is optimal unless some alignment steps have negative costs."
+    )]
+    ClippingWithoutNegativeCosts { mode: AlignmentMode },
+
+    #[error("The search dump is malformed in line {line}: {message}.")]
+    SearchDump { line: usize, message: String },
 }
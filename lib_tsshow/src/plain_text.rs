@@ -1,12 +1,11 @@
 use std::{io::Write, iter};
 
 use alignment_stream::{AlignmentCoordinates, AlignmentStream};
-use lib_tsalign::{
-    a_star_aligner::{
-        alignment_result::{AlignmentResult, a_star_sequences::SequencePair},
-        template_switch_distance::{AlignmentType, TemplateSwitchPrimary, TemplateSwitchSecondary},
+use lib_tsalign::a_star_aligner::{
+    alignment_result::{AlignmentResult, a_star_sequences::SequencePair},
+    template_switch_distance::{
+        AlignmentType, TemplateSwitchDirection, TemplateSwitchPrimary, TemplateSwitchSecondary,
     },
-    costs::U64Cost,
 };
 use log::{debug, info, trace, warn};
 use mutlipair_alignment_renderer::MultipairAlignmentRenderer;
@@ -16,24 +15,34 @@ pub mod alignment_stream;
 pub mod mutlipair_alignment_renderer;
 mod parse_template_switches;
 
-pub fn show_template_switches(
+pub fn show_template_switches<Cost>(
     mut output: impl Write,
-    result: &AlignmentResult<AlignmentType, U64Cost>,
-    no_ts_result: &Option<AlignmentResult<AlignmentType, U64Cost>>,
+    result: &AlignmentResult<AlignmentType, Cost>,
+    no_ts_result: &Option<AlignmentResult<AlignmentType, Cost>>,
 ) {
-    let AlignmentResult::WithTarget {
-        alignment,
-        statistics,
-    } = result
-    else {
+    let Some(alignment) = result.alignment() else {
         warn!("Alignment was aborted early, no template switches present");
         return;
     };
+    let statistics = result.statistics();
 
-    info!("CIGAR: {} (Cost: {:.0})", result.cigar(), statistics.cost);
+    if let AlignmentResult::Partial {
+        partial_cost,
+        reference_end,
+        query_end,
+        ..
+    } = result
+    {
+        warn!(
+            "Alignment was aborted early, showing the partial alignment up to reference index {reference_end} and query index {query_end}"
+        );
+        info!("Partial CIGAR: {} (Cost: {partial_cost})", result.cigar());
+    } else {
+        info!("CIGAR: {} (Cost: {})", result.cigar(), statistics.cost);
+    }
     if let Some(no_ts_result) = no_ts_result.as_ref() {
         info!(
-            "No-ts CIGAR: {} (Cost: {:.0})",
+            "No-ts CIGAR: {} (Cost: {})",
             no_ts_result.cigar(),
             no_ts_result.statistics().cost
         )
@@ -60,11 +69,11 @@ pub fn show_template_switches(
     }
 }
 
-fn show_template_switch(
+fn show_template_switch<Cost>(
     mut output: impl Write,
     template_switch: &TSShow<AlignmentType>,
     sequences: &SequencePair,
-    no_ts_result: &Option<AlignmentResult<AlignmentType, U64Cost>>,
+    no_ts_result: &Option<AlignmentResult<AlignmentType, Cost>>,
     reference_offset: usize,
     query_offset: usize,
 ) {
@@ -126,6 +135,7 @@ fn show_template_switch(
         || (template_switch.primary == TemplateSwitchPrimary::Query
             && template_switch.secondary == TemplateSwitchSecondary::Query);
 
+    let primary_forward_label = format!("{primary_label}F");
     let primary_reverse_label = format!("{primary_label}R");
     let anti_primary_forward_label = format!("{anti_primary_label}F");
     let anti_primary_reverse_label = format!("{anti_primary_label}R");
@@ -148,12 +158,26 @@ fn show_template_switch(
     let primary_sp4 = primary_coordinate_picker(&template_switch.sp4_offset);
     let anti_primary_sp4 = anti_primary_coordinate_picker(&template_switch.sp4_offset);
 
-    let ts_reverse: String = primary[primary_coordinate_picker(&template_switch.sp1_offset)
-        ..primary_coordinate_picker(&template_switch.sp4_offset)]
-        .chars()
-        .rev()
-        .collect();
-    let ts_reverse_alignment = template_switch.template_switch.reverse();
+    // Reverse template switches are aligned against the complement of the secondary, right to left.
+    // Forward template switches are aligned against the secondary itself, left to right.
+    let ts_inner = &primary[primary_coordinate_picker(&template_switch.sp1_offset)
+        ..primary_coordinate_picker(&template_switch.sp4_offset)];
+    let (ts_sequence, ts_alignment): (String, _) = match template_switch.direction {
+        TemplateSwitchDirection::Reverse => (
+            ts_inner.chars().rev().collect(),
+            template_switch.template_switch.reverse(),
+        ),
+        TemplateSwitchDirection::Forward => (
+            ts_inner.to_string(),
+            template_switch.template_switch.clone(),
+        ),
+    };
+    let ts_secondary_offset = template_switch
+        .sp2_secondary_offset
+        .min(template_switch.sp3_secondary_offset);
+    let ts_secondary_limit = template_switch
+        .sp2_secondary_offset
+        .max(template_switch.sp3_secondary_offset);
 
     debug!("Primary offset: {primary_offset}");
     debug!("SP1 primary offset: {primary_sp1}");
@@ -175,16 +199,13 @@ fn show_template_switch(
     );
 
     if primary_equals_secondary {
-        let primary_extended_offset = primary_offset.min(
-            template_switch
-                .sp3_secondary_offset
-                .saturating_sub(STREAM_PADDING),
-        );
+        let primary_extended_offset =
+            primary_offset.min(ts_secondary_offset.saturating_sub(STREAM_PADDING));
         let primary_extended_limit = primary_limit.max(
             primary
                 .chars()
                 .count()
-                .min(template_switch.sp2_secondary_offset + STREAM_PADDING),
+                .min(ts_secondary_limit + STREAM_PADDING),
         );
 
         debug!("Primary extended offset: {primary_extended_offset}");
@@ -237,18 +258,23 @@ fn show_template_switch(
             invert_alignment,
         );
 
+        let (secondary_label, secondary) = match template_switch.direction {
+            TemplateSwitchDirection::Reverse => (&primary_reverse_label, primary_c),
+            TemplateSwitchDirection::Forward => (&primary_forward_label, primary),
+        };
+
         debug!("Creating inside renderer");
         let mut inside_renderer = MultipairAlignmentRenderer::new_without_data(
-            primary_reverse_label.clone(),
-            primary_c[primary_extended_offset..primary_extended_limit].chars(),
+            secondary_label.clone(),
+            secondary[primary_extended_offset..primary_extended_limit].chars(),
         );
         debug!("Adding F2");
         inside_renderer.add_aligned_sequence_without_data(
-            &primary_reverse_label,
-            template_switch.sp3_secondary_offset - primary_extended_offset,
+            secondary_label,
+            ts_secondary_offset - primary_extended_offset,
             f2_label.clone(),
-            ts_reverse.chars(),
-            ts_reverse_alignment.iter_flat_cloned(),
+            ts_sequence.chars(),
+            ts_alignment.iter_flat_cloned(),
             true,
             false,
         );
@@ -267,19 +293,16 @@ fn show_template_switch(
             .unwrap();
         println!();
         inside_renderer
-            .render(&mut output, [&primary_reverse_label, &f2_label])
+            .render(&mut output, [secondary_label, &f2_label])
             .unwrap();
     } else {
-        let anti_primary_extended_offset = anti_primary_offset.min(
-            template_switch
-                .sp3_secondary_offset
-                .saturating_sub(STREAM_PADDING),
-        );
+        let anti_primary_extended_offset =
+            anti_primary_offset.min(ts_secondary_offset.saturating_sub(STREAM_PADDING));
         let anti_primary_extended_limit = anti_primary_f3_limit.max(
             anti_primary
                 .chars()
                 .count()
-                .min(template_switch.sp2_secondary_offset + STREAM_PADDING),
+                .min(ts_secondary_limit + STREAM_PADDING),
         );
 
         debug!("Anti-primary extended offset: {anti_primary_extended_offset}");
@@ -328,11 +351,14 @@ fn show_template_switch(
 
         debug!("Adding F2");
         renderer.add_aligned_sequence_without_data(
-            &anti_primary_reverse_label,
-            template_switch.sp3_secondary_offset - anti_primary_extended_offset,
+            match template_switch.direction {
+                TemplateSwitchDirection::Reverse => &anti_primary_reverse_label,
+                TemplateSwitchDirection::Forward => &anti_primary_forward_label,
+            },
+            ts_secondary_offset - anti_primary_extended_offset,
             f2_label.clone(),
-            ts_reverse.chars(),
-            ts_reverse_alignment.iter_flat_cloned(),
+            ts_sequence.chars(),
+            ts_alignment.iter_flat_cloned(),
             true,
             false,
         );
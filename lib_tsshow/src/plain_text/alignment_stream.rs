@@ -157,7 +157,8 @@ impl AlignmentStream {
             | AlignmentType::TemplateSwitchExit { .. }
             | AlignmentType::Root
             | AlignmentType::SecondaryRoot
-            | AlignmentType::PrimaryReentry => 0,
+            | AlignmentType::PrimaryReentry
+            | AlignmentType::PrimaryClip { .. } => 0,
             AlignmentType::PrimaryShortcut { .. } => {
                 unreachable!("Shortcut alignments are not supported for show")
             }
@@ -218,6 +219,10 @@ impl AlignmentCoordinates {
             | AlignmentType::Root
             | AlignmentType::SecondaryRoot
             | AlignmentType::PrimaryReentry => (0, 0),
+            AlignmentType::PrimaryClip {
+                reference_length,
+                query_length,
+            } => (reference_length, query_length),
             AlignmentType::PrimaryShortcut { .. } => {
                 unreachable!("Shortcut alignments are not supported for show")
             }
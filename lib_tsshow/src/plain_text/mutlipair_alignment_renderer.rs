@@ -343,7 +343,8 @@ impl<SequenceName: Eq + Ord, CharacterData>
                 | AlignmentType::TemplateSwitchEntrance { .. }
                 | AlignmentType::TemplateSwitchExit { .. }
                 | AlignmentType::SecondaryRoot
-                | AlignmentType::PrimaryShortcut { .. } => {
+                | AlignmentType::PrimaryShortcut { .. }
+                | AlignmentType::PrimaryClip { .. } => {
                     panic!("Not allowed in rendered alignment: {alignment_type:?}")
                 }
             }
@@ -1,6 +1,8 @@
 use lib_tsalign::a_star_aligner::{
     alignment_result::alignment::{Alignment, iter::CompactAlignmentIterCloned},
-    template_switch_distance::{AlignmentType, TemplateSwitchPrimary, TemplateSwitchSecondary},
+    template_switch_distance::{
+        AlignmentType, TemplateSwitchDirection, TemplateSwitchPrimary, TemplateSwitchSecondary,
+    },
 };
 use log::{debug, trace};
 
@@ -19,6 +21,7 @@ pub struct TSShow<AlignmentType> {
     pub sp4_offset: AlignmentCoordinates,
     pub primary: TemplateSwitchPrimary,
     pub secondary: TemplateSwitchSecondary,
+    pub direction: TemplateSwitchDirection,
     pub upstream: Alignment<AlignmentType>,
     pub template_switch: Alignment<AlignmentType>,
     pub downstream: Alignment<AlignmentType>,
@@ -56,6 +59,7 @@ fn parse_template_switch(
         alignment_type @ AlignmentType::TemplateSwitchEntrance {
             primary,
             secondary,
+            direction,
             first_offset,
         },
     ) = alignment.peek_front_cloned().unwrap()
@@ -128,6 +132,7 @@ fn parse_template_switch(
                 sp4_offset,
                 primary,
                 secondary,
+                direction,
                 upstream,
                 template_switch,
                 downstream,
@@ -142,8 +147,15 @@ fn parse_template_switch(
                     | AlignmentType::SecondarySubstitution
                     | AlignmentType::SecondaryMatch
             ) {
-                sp3_secondary_offset -= multiplicity;
-                assert!(sp3_secondary_offset < sp2_secondary_offset);
+                match direction {
+                    TemplateSwitchDirection::Reverse => {
+                        sp3_secondary_offset -= multiplicity;
+                        assert!(sp3_secondary_offset < sp2_secondary_offset);
+                    }
+                    TemplateSwitchDirection::Forward => {
+                        sp3_secondary_offset += multiplicity;
+                    }
+                }
             }
         }
     }
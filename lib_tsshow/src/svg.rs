@@ -3,12 +3,11 @@ use std::{collections::HashMap, io::Write};
 use arrows::{Arrow, ArrowEndpointDirection, add_arrow_defs};
 use font::{CharacterData, sans_serif_mono, svg_string, typewriter};
 use indexed_str::IndexedStr;
-use lib_tsalign::{
-    a_star_aligner::{
-        alignment_result::AlignmentResult,
-        template_switch_distance::{AlignmentType, TemplateSwitchPrimary, TemplateSwitchSecondary},
+use lib_tsalign::a_star_aligner::{
+    alignment_result::AlignmentResult,
+    template_switch_distance::{
+        AlignmentType, TemplateSwitchDirection, TemplateSwitchPrimary, TemplateSwitchSecondary,
     },
-    costs::U64Cost,
 };
 use log::{debug, info};
 use numbers::{Number, NumberAlignment};
@@ -37,6 +36,7 @@ mod font;
 mod indexed_str;
 pub mod labelled_sequence;
 mod numbers;
+pub mod search_heatmap;
 
 const SVG_PADDING: f32 = 10.0;
 const COPY_COLORS: &[&str] = &["#00CC00", "#009900", "#006600", "#003300"];
@@ -59,21 +59,18 @@ pub struct SvgConfig {
     pub render_more_complement: bool,
 }
 
-pub fn create_ts_svg(
+pub fn create_ts_svg<Cost>(
     output: impl Write,
-    result: &AlignmentResult<AlignmentType, U64Cost>,
-    no_ts_result: &Option<AlignmentResult<AlignmentType, U64Cost>>,
+    result: &AlignmentResult<AlignmentType, Cost>,
+    no_ts_result: &Option<AlignmentResult<AlignmentType, Cost>>,
     config: &SvgConfig,
 ) -> Result<()> {
     info!("Creating template switch SVG");
 
-    let AlignmentResult::WithTarget {
-        alignment,
-        statistics,
-    } = result
-    else {
+    let Some(alignment) = result.alignment() else {
         return Err(Error::AlignmentHasNoTarget);
     };
+    let statistics = result.statistics();
     debug!("Alignment: {alignment:?}");
 
     let reference = &statistics.sequences.reference;
@@ -133,6 +130,7 @@ pub fn create_ts_svg(
             index: ts_index,
             primary,
             secondary,
+            direction,
             sp1_reference,
             sp1_query,
             sp4_reference,
@@ -239,23 +237,50 @@ pub fn create_ts_svg(
             ),
         };
 
-        let (secondary_sp2, secondary_sp3, inner_row) = match secondary {
+        let (inner_first_column, inner_limit_column, inner_row) = match secondary {
             TemplateSwitchSecondary::Reference => (
-                ts_arrangement.reference_inner_last_non_blank_column(inner_identifier) + 1usize,
                 ts_arrangement.reference_inner_first_non_blank_column(inner_identifier),
+                ts_arrangement.reference_inner_last_non_blank_column(inner_identifier) + 1usize,
                 TsArrangementRow::ReferenceInner {
                     index: inner_identifier,
                 },
             ),
             TemplateSwitchSecondary::Query => (
-                ts_arrangement.query_inner_last_non_blank_column(inner_identifier) + 1usize,
                 ts_arrangement.query_inner_first_non_blank_column(inner_identifier),
+                ts_arrangement.query_inner_last_non_blank_column(inner_identifier) + 1usize,
                 TsArrangementRow::QueryInner {
                     index: inner_identifier,
                 },
             ),
         };
 
+        // Reverse inners are entered from the right, and forward inners from the left.
+        let (
+            secondary_sp2,
+            secondary_sp2_direction,
+            secondary_sp3,
+            secondary_sp3_direction,
+            number2_alignment,
+            number3_alignment,
+        ) = match direction {
+            TemplateSwitchDirection::Reverse => (
+                inner_limit_column,
+                ArrowEndpointDirection::Forward,
+                inner_first_column,
+                ArrowEndpointDirection::Backward,
+                NumberAlignment::Left,
+                NumberAlignment::Right,
+            ),
+            TemplateSwitchDirection::Forward => (
+                inner_first_column,
+                ArrowEndpointDirection::Backward,
+                inner_limit_column,
+                ArrowEndpointDirection::Forward,
+                NumberAlignment::Right,
+                NumberAlignment::Left,
+            ),
+        };
+
         let running_number = TS_RUNNING_NUMBER.chars().nth(*ts_index).unwrap();
         let number1 = Number::new(
             format!("{running_number}1"),
@@ -268,14 +293,14 @@ pub fn create_ts_svg(
             format!("{running_number}2"),
             secondary_sp2,
             inner_row,
-            NumberAlignment::Left,
+            number2_alignment,
             0.5,
         );
         let number3 = Number::new(
             format!("{running_number}3"),
             secondary_sp3,
             inner_row,
-            NumberAlignment::Right,
+            number3_alignment,
             0.5,
         );
         let number4 = Number::new(
@@ -295,14 +320,14 @@ pub fn create_ts_svg(
                 secondary_sp2,
                 number2.width(),
                 inner_row,
-                ArrowEndpointDirection::Forward,
+                secondary_sp2_direction,
             ));
 
             arrows.push(Arrow::new_curved(
                 secondary_sp3,
                 number3.width(),
                 inner_row,
-                ArrowEndpointDirection::Backward,
+                secondary_sp3_direction,
                 primary_sp4,
                 number4.width(),
                 primary_row,
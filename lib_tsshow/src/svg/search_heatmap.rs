@@ -0,0 +1,234 @@
+//! Rendering the explored search space of an alignment as a heatmap.
+
+use std::{collections::HashMap, io::Write};
+
+use lib_tsalign::a_star_aligner::{
+    alignment_result::AlignmentResult,
+    template_switch_distance::{AlignmentType, TemplateSwitchPrimary, search_dump::SearchDump},
+};
+use log::info;
+use svg::{
+    Document,
+    node::element::{Group, Polyline, Rectangle, Text},
+};
+
+use super::{SVG_PADDING, SvgLocation};
+use crate::error::Result;
+
+#[cfg(test)]
+mod tests;
+
+/// The width and height of a cell of the reference × query plane.
+const CELL_SIZE: f32 = 4.0;
+const HEATMAP_COLOR: &str = "red";
+const PATH_COLOR: &str = "blue";
+const TITLE_HEIGHT: f32 = 30.0;
+
+/// A part of the path of an alignment through the reference × query plane.
+struct PathSegment {
+    points: Vec<(usize, usize)>,
+    /// True if this segment is inside a template switch.
+    template_switch: bool,
+}
+
+/// Creates an SVG image showing the number of closed nodes per cell of the reference × query plane.
+///
+/// The opacity of a cell is logarithmic in its number of closed nodes.
+/// If the alignment has a target or is partial, then its path is drawn on top, with template switches drawn dashed.
+pub fn create_search_heatmap_svg<Cost>(
+    output: impl Write,
+    search_dump: &SearchDump,
+    result: &AlignmentResult<AlignmentType, Cost>,
+) -> Result<()> {
+    info!("Creating search heatmap SVG");
+
+    let statistics = result.statistics();
+    let reference_length = statistics.sequences.reference.len();
+    let query_length = statistics.sequences.query.len();
+
+    let mut cells = HashMap::<_, usize>::new();
+    for record in &search_dump.records {
+        *cells
+            .entry((record.reference_index, record.query_index))
+            .or_default() += 1;
+    }
+    let max_count = cells.values().copied().max().unwrap_or(0);
+
+    let width = (reference_length + 1) as f32 * CELL_SIZE;
+    let height = (query_length + 1) as f32 * CELL_SIZE;
+
+    let mut plane = Group::new().add(
+        Rectangle::new()
+            .set("width", width)
+            .set("height", height)
+            .set("fill", "white")
+            .set("stroke", "black"),
+    );
+
+    let mut cells: Vec<_> = cells.into_iter().collect();
+    cells.sort_unstable();
+    for ((reference_index, query_index), count) in cells {
+        let opacity = ((count + 1) as f32).ln() / ((max_count + 1) as f32).ln();
+        plane = plane.add(
+            Rectangle::new()
+                .set("x", reference_index as f32 * CELL_SIZE)
+                .set("y", query_index as f32 * CELL_SIZE)
+                .set("width", CELL_SIZE)
+                .set("height", CELL_SIZE)
+                .set("fill", HEATMAP_COLOR)
+                .set("fill-opacity", opacity),
+        );
+    }
+
+    if let Some(alignment) = result.alignment() {
+        for PathSegment {
+            points,
+            template_switch,
+        } in alignment_path(
+            statistics.reference_offset,
+            statistics.query_offset,
+            alignment.iter_flat_cloned(),
+        ) {
+            let mut polyline = Polyline::new()
+                .set(
+                    "points",
+                    points
+                        .iter()
+                        .map(|(reference_index, query_index)| {
+                            format!(
+                                "{},{}",
+                                (*reference_index as f32 + 0.5) * CELL_SIZE,
+                                (*query_index as f32 + 0.5) * CELL_SIZE,
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                )
+                .set("fill", "none")
+                .set("stroke", PATH_COLOR)
+                .set("stroke-width", CELL_SIZE / 2.0);
+            if template_switch {
+                polyline = polyline.set("stroke-dasharray", CELL_SIZE);
+            }
+            plane = plane.add(polyline);
+        }
+    }
+
+    let title = Text::new(format!(
+        "Closed nodes: {}, at most {max_count} per cell (x: reference, y: query)",
+        search_dump.records.len(),
+    ))
+    .set(
+        "transform",
+        SvgLocation {
+            x: SVG_PADDING,
+            y: SVG_PADDING + TITLE_HEIGHT / 2.0,
+        }
+        .as_transform(),
+    )
+    .set("font-family", "sans-serif");
+    let plane = plane.set(
+        "transform",
+        SvgLocation {
+            x: SVG_PADDING,
+            y: SVG_PADDING + TITLE_HEIGHT,
+        }
+        .as_transform(),
+    );
+
+    let svg = Document::new()
+        .set(
+            "viewBox",
+            (
+                0.0,
+                0.0,
+                width + 2.0 * SVG_PADDING,
+                height + TITLE_HEIGHT + 2.0 * SVG_PADDING,
+            ),
+        )
+        .add(title)
+        .add(plane);
+
+    svg::write(output, &svg)?;
+    Ok(())
+}
+
+/// Computes the path of an alignment through the reference × query plane.
+///
+/// Inside a template switch, the path follows the primary sequence, and at the exit it jumps to the reentry point on the anti-primary sequence.
+fn alignment_path(
+    reference_offset: usize,
+    query_offset: usize,
+    alignment: impl IntoIterator<Item = AlignmentType>,
+) -> Vec<PathSegment> {
+    let mut reference_index = reference_offset;
+    let mut query_index = query_offset;
+    let mut template_switch_primary = None;
+    let mut segments = vec![PathSegment {
+        points: vec![(reference_index, query_index)],
+        template_switch: false,
+    }];
+
+    for alignment_type in alignment {
+        match alignment_type {
+            AlignmentType::PrimaryMatch
+            | AlignmentType::PrimarySubstitution
+            | AlignmentType::PrimaryFlankMatch
+            | AlignmentType::PrimaryFlankSubstitution => {
+                reference_index += 1;
+                query_index += 1;
+            }
+            AlignmentType::PrimaryInsertion | AlignmentType::PrimaryFlankInsertion => {
+                query_index += 1;
+            }
+            AlignmentType::PrimaryDeletion | AlignmentType::PrimaryFlankDeletion => {
+                reference_index += 1;
+            }
+            AlignmentType::SecondaryMatch
+            | AlignmentType::SecondarySubstitution
+            | AlignmentType::SecondaryInsertion => match template_switch_primary {
+                Some(TemplateSwitchPrimary::Reference) => reference_index += 1,
+                Some(TemplateSwitchPrimary::Query) => query_index += 1,
+                None => unreachable!("Secondary alignment types only occur in template switches"),
+            },
+            AlignmentType::TemplateSwitchEntrance { primary, .. } => {
+                template_switch_primary = Some(primary);
+                segments.push(PathSegment {
+                    points: vec![(reference_index, query_index)],
+                    template_switch: true,
+                });
+                continue;
+            }
+            AlignmentType::TemplateSwitchExit { anti_primary_gap } => {
+                match template_switch_primary.take() {
+                    Some(TemplateSwitchPrimary::Reference) => {
+                        query_index = query_index.saturating_add_signed(anti_primary_gap)
+                    }
+                    Some(TemplateSwitchPrimary::Query) => {
+                        reference_index = reference_index.saturating_add_signed(anti_primary_gap)
+                    }
+                    None => unreachable!("Template switch exits only occur in template switches"),
+                }
+                segments
+                    .last_mut()
+                    .unwrap()
+                    .points
+                    .push((reference_index, query_index));
+                segments.push(PathSegment {
+                    points: vec![(reference_index, query_index)],
+                    template_switch: false,
+                });
+                continue;
+            }
+            _ => continue,
+        }
+
+        segments
+            .last_mut()
+            .unwrap()
+            .points
+            .push((reference_index, query_index));
+    }
+
+    segments
+}
@@ -0,0 +1,77 @@
+use compact_genome::{
+    implementation::{alphabets::dna_alphabet_or_n::DnaAlphabetOrN, vec_sequence::VectorGenome},
+    interface::sequence::{GenomeSequence, OwnedGenomeSequence},
+};
+use lib_tsalign::{
+    a_star_aligner::{
+        alignment_options::AlignmentOptions,
+        template_switch_distance::{
+            search_dump::{SearchDump, SearchDumpObserver},
+            strategies::{
+                AlignmentStrategySelection, chaining::NoChainingStrategy,
+                node_ord::CostOnlyNodeOrdStrategy, primary_match::AllowPrimaryMatchStrategy,
+                primary_range::NoPrunePrimaryRangeStrategy,
+                secondary_deletion::AllowSecondaryDeletionStrategy, shortcut::NoShortcutStrategy,
+                template_switch_count::NoTemplateSwitchCountStrategy,
+                template_switch_min_length::NoTemplateSwitchMinLengthStrategy,
+            },
+        },
+        template_switch_distance_a_star_align,
+    },
+    config::TemplateSwitchConfig,
+    costs::U64Cost,
+};
+
+use super::create_search_heatmap_svg;
+
+type Strategies = AlignmentStrategySelection<
+    DnaAlphabetOrN,
+    U64Cost,
+    CostOnlyNodeOrdStrategy,
+    NoTemplateSwitchMinLengthStrategy<U64Cost>,
+    NoChainingStrategy<U64Cost>,
+    NoTemplateSwitchCountStrategy,
+    AllowSecondaryDeletionStrategy,
+    NoShortcutStrategy<U64Cost>,
+    AllowPrimaryMatchStrategy,
+    NoPrunePrimaryRangeStrategy,
+>;
+
+#[test]
+fn search_dump_round_trip_and_heatmap() {
+    let reference =
+        VectorGenome::<DnaAlphabetOrN>::from_iter_u8("AACCAACCGGTGTGGGCGTTCTTGAGGTTT".bytes())
+            .unwrap();
+    let query = VectorGenome::from_iter_u8("AACCAAACGGTGTGGCGTTCTTGACGTTT".bytes()).unwrap();
+    let config = TemplateSwitchConfig::read_plain(
+        include_str!("../../../../sample_tsa_config/config.tsa").as_bytes(),
+    )
+    .unwrap();
+
+    let mut observer = SearchDumpObserver::<Strategies>::new();
+    let result = template_switch_distance_a_star_align::<Strategies, _>(
+        reference.as_genome_subsequence(),
+        query.as_genome_subsequence(),
+        config,
+        &AlignmentOptions::default(),
+        (),
+        &mut observer,
+    )
+    .unwrap();
+    let search_dump = observer.into_search_dump();
+    assert!(!search_dump.records.is_empty());
+
+    for delimiter in ['\t', ','] {
+        let mut buffer = Vec::new();
+        search_dump.write(&mut buffer, delimiter).unwrap();
+        assert_eq!(SearchDump::read(buffer.as_slice()).unwrap(), search_dump);
+    }
+
+    let mut svg = Vec::new();
+    create_search_heatmap_svg(&mut svg, &search_dump, &result).unwrap();
+    let svg = String::from_utf8(svg).unwrap();
+    assert!(svg.starts_with("<svg"), "{svg}");
+    assert!(svg.contains(&format!("Closed nodes: {}", search_dump.records.len())));
+    // The path of the alignment is drawn on top of the heatmap.
+    assert!(svg.contains("<polyline"));
+}
@@ -1,7 +1,7 @@
 use std::iter;
 
 use lib_tsalign::a_star_aligner::template_switch_distance::{
-    AlignmentType, TemplateSwitchSecondary,
+    AlignmentType, TemplateSwitchDirection, TemplateSwitchSecondary,
 };
 use log::trace;
 use tagged_vec::TaggedVec;
@@ -52,129 +52,257 @@ impl TsInnerArrangement {
         for ts in template_switches {
             trace!("source_inner: {:?}", ts.inner);
 
-            let (mut sp2_secondary, sp3_secondary) = match ts.secondary {
-                TemplateSwitchSecondary::Reference => (
-                    source_arrangement.reference_source_to_arrangement_column(ts.sp2_secondary),
-                    source_arrangement.reference_source_to_arrangement_column(ts.sp3_secondary),
-                ),
-                TemplateSwitchSecondary::Query => (
-                    source_arrangement.query_source_to_arrangement_column(ts.sp2_secondary),
-                    source_arrangement.query_source_to_arrangement_column(ts.sp3_secondary),
-                ),
+            let mut inner = match ts.direction {
+                TemplateSwitchDirection::Reverse => {
+                    result.arrange_reverse_inner(source_arrangement, complement_arrangement, &ts)
+                }
+                TemplateSwitchDirection::Forward => {
+                    result.arrange_forward_inner(source_arrangement, complement_arrangement, &ts)
+                }
             };
 
-            let mut source_inner = ts.inner.iter().rev().copied();
-            let mut inner = TaggedVec::<ArrangementColumn, _>::default();
-            inner.extend(iter::repeat_n(InnerChar::Blank, sp3_secondary.into()));
+            let suffix_blanks =
+                iter::repeat_n(InnerChar::Blank, source_arrangement.reference().len())
+                    .skip(inner.len());
+            inner.extend(suffix_blanks);
 
-            let mut current_arrangement_column = sp3_secondary;
-            for alignment_type in ts.inner_alignment.iter_flat_cloned().rev() {
-                match alignment_type {
-                    AlignmentType::SecondaryInsertion => {
-                        loop {
-                            let c = complement_arrangement.secondary_complement(ts.secondary)
-                                [current_arrangement_column];
+            match ts.secondary {
+                TemplateSwitchSecondary::Reference => {
+                    result.reference_inners.push(TsInner::new(inner, ts))
+                }
+                TemplateSwitchSecondary::Query => result.query_inners.push(TsInner::new(inner, ts)),
+            };
+        }
 
-                            if c.is_gap() || c.is_source_char() {
-                                break;
-                            }
+        result
+    }
 
-                            inner.push(InnerChar::Blank);
-                            current_arrangement_column += 1;
-                        }
+    /// Arranges the inner of a template switch that is aligned against the complement of the secondary, right to left.
+    fn arrange_reverse_inner(
+        &mut self,
+        source_arrangement: &mut TsSourceArrangement,
+        complement_arrangement: &mut TsComplementArrangement,
+        ts: &TemplateSwitch,
+    ) -> TaggedVec<ArrangementColumn, InnerChar> {
+        let (mut sp2_secondary, sp3_secondary) = match ts.secondary {
+            TemplateSwitchSecondary::Reference => (
+                source_arrangement.reference_source_to_arrangement_column(ts.sp2_secondary),
+                source_arrangement.reference_source_to_arrangement_column(ts.sp3_secondary),
+            ),
+            TemplateSwitchSecondary::Query => (
+                source_arrangement.query_source_to_arrangement_column(ts.sp2_secondary),
+                source_arrangement.query_source_to_arrangement_column(ts.sp3_secondary),
+            ),
+        };
 
-                        if !complement_arrangement.secondary_complement(ts.secondary)
-                            [current_arrangement_column]
-                            .is_gap()
-                        {
-                            complement_arrangement.insert_secondary_complement_gap(
-                                ts.secondary,
-                                current_arrangement_column,
-                            );
-
-                            source_arrangement.insert_blank(current_arrangement_column);
-                            for existing_inner in result
-                                .reference_inners
-                                .iter_values_mut()
-                                .chain(&mut result.query_inners)
-                            {
-                                existing_inner
-                                    .sequence
-                                    .insert(current_arrangement_column, InnerChar::Blank);
-                            }
-
-                            sp2_secondary += 1;
+        let mut source_inner = ts.inner.iter().rev().copied();
+        let mut inner = TaggedVec::<ArrangementColumn, _>::default();
+        inner.extend(iter::repeat_n(InnerChar::Blank, sp3_secondary.into()));
+
+        let mut current_arrangement_column = sp3_secondary;
+        for alignment_type in ts.inner_alignment.iter_flat_cloned().rev() {
+            match alignment_type {
+                AlignmentType::SecondaryInsertion => {
+                    loop {
+                        let c = complement_arrangement.secondary_complement(ts.secondary)
+                            [current_arrangement_column];
+
+                        if c.is_gap() || c.is_source_char() {
+                            break;
                         }
 
-                        inner.push(source_inner.next().unwrap().into());
+                        inner.push(InnerChar::Blank);
                         current_arrangement_column += 1;
                     }
-                    AlignmentType::SecondaryDeletion => {
-                        while !complement_arrangement.secondary_complement(ts.secondary)
-                            [current_arrangement_column]
-                            .is_source_char()
+
+                    if !complement_arrangement.secondary_complement(ts.secondary)
+                        [current_arrangement_column]
+                        .is_gap()
+                    {
+                        complement_arrangement.insert_secondary_complement_gap(
+                            ts.secondary,
+                            current_arrangement_column,
+                        );
+
+                        source_arrangement.insert_blank(current_arrangement_column);
+                        for existing_inner in self
+                            .reference_inners
+                            .iter_values_mut()
+                            .chain(&mut self.query_inners)
                         {
-                            inner.push(InnerChar::Blank);
-                            current_arrangement_column += 1;
+                            existing_inner
+                                .sequence
+                                .insert(current_arrangement_column, InnerChar::Blank);
                         }
 
-                        complement_arrangement
-                            .show_secondary_character(ts.secondary, current_arrangement_column);
-                        inner.push(InnerChar::Gap {
-                            copy_depth: source_arrangement.secondary(ts.secondary)
-                                [current_arrangement_column]
-                                .copy_depth(),
-                        });
+                        sp2_secondary += 1;
+                    }
+
+                    inner.push(source_inner.next().unwrap().into());
+                    current_arrangement_column += 1;
+                }
+                AlignmentType::SecondaryDeletion => {
+                    while !complement_arrangement.secondary_complement(ts.secondary)
+                        [current_arrangement_column]
+                        .is_source_char()
+                    {
+                        inner.push(InnerChar::Blank);
                         current_arrangement_column += 1;
                     }
-                    AlignmentType::SecondarySubstitution | AlignmentType::SecondaryMatch => {
-                        while !source_arrangement.secondary(ts.secondary)
+
+                    complement_arrangement
+                        .show_secondary_character(ts.secondary, current_arrangement_column);
+                    inner.push(InnerChar::Gap {
+                        copy_depth: source_arrangement.secondary(ts.secondary)
                             [current_arrangement_column]
-                            .is_source_char()
-                        {
-                            inner.push(InnerChar::Blank);
-                            current_arrangement_column += 1;
-                        }
+                            .copy_depth(),
+                    });
+                    current_arrangement_column += 1;
+                }
+                AlignmentType::SecondarySubstitution | AlignmentType::SecondaryMatch => {
+                    while !source_arrangement.secondary(ts.secondary)[current_arrangement_column]
+                        .is_source_char()
+                    {
+                        inner.push(InnerChar::Blank);
+                        current_arrangement_column += 1;
+                    }
+
+                    complement_arrangement
+                        .show_secondary_character(ts.secondary, current_arrangement_column);
 
+                    let mut inner_char: InnerChar = source_inner.next().unwrap().into();
+                    if alignment_type == AlignmentType::SecondarySubstitution {
                         complement_arrangement
-                            .show_secondary_character(ts.secondary, current_arrangement_column);
+                            .secondary_to_lower_case(ts.secondary, current_arrangement_column);
+                        inner_char.to_lower_case();
+                    }
+
+                    inner.push(inner_char);
+                    current_arrangement_column += 1;
+                }
+                _ => unreachable!(),
+            }
+        }
 
-                        let mut inner_char: InnerChar = source_inner.next().unwrap().into();
-                        if alignment_type == AlignmentType::SecondarySubstitution {
-                            complement_arrangement
-                                .secondary_to_lower_case(ts.secondary, current_arrangement_column);
-                            inner_char.to_lower_case();
+        // We skip further secondary non-source chars for the assertion below.
+        while !source_arrangement.secondary(ts.secondary)[current_arrangement_column]
+            .is_source_char()
+        {
+            current_arrangement_column += 1;
+        }
+        assert_eq!(current_arrangement_column, sp2_secondary);
+
+        inner
+    }
+
+    /// Arranges the inner of a template switch that is aligned against the secondary itself, left to right.
+    fn arrange_forward_inner(
+        &mut self,
+        source_arrangement: &mut TsSourceArrangement,
+        complement_arrangement: &mut TsComplementArrangement,
+        ts: &TemplateSwitch,
+    ) -> TaggedVec<ArrangementColumn, InnerChar> {
+        let (sp2_secondary, mut sp3_secondary) = match ts.secondary {
+            TemplateSwitchSecondary::Reference => (
+                source_arrangement.reference_source_to_arrangement_column(ts.sp2_secondary),
+                source_arrangement.reference_source_to_arrangement_column(ts.sp3_secondary),
+            ),
+            TemplateSwitchSecondary::Query => (
+                source_arrangement.query_source_to_arrangement_column(ts.sp2_secondary),
+                source_arrangement.query_source_to_arrangement_column(ts.sp3_secondary),
+            ),
+        };
+
+        let mut source_inner = ts.inner.iter().copied();
+        let mut inner = TaggedVec::<ArrangementColumn, _>::default();
+        inner.extend(iter::repeat_n(InnerChar::Blank, sp2_secondary.into()));
+
+        let mut current_arrangement_column = sp2_secondary;
+        for alignment_type in ts.inner_alignment.iter_flat_cloned() {
+            match alignment_type {
+                AlignmentType::SecondaryInsertion => {
+                    loop {
+                        let c =
+                            source_arrangement.secondary(ts.secondary)[current_arrangement_column];
+
+                        if c.is_gap() || c.is_source_char() {
+                            break;
                         }
 
-                        inner.push(inner_char);
+                        inner.push(InnerChar::Blank);
                         current_arrangement_column += 1;
                     }
-                    _ => unreachable!(),
+
+                    let c = source_arrangement.secondary(ts.secondary)[current_arrangement_column];
+                    if !c.is_gap() {
+                        source_arrangement.insert_secondary_gap(
+                            ts.secondary,
+                            current_arrangement_column,
+                            c.copy_depth(),
+                        );
+
+                        complement_arrangement.insert_blank(current_arrangement_column);
+                        for existing_inner in self
+                            .reference_inners
+                            .iter_values_mut()
+                            .chain(&mut self.query_inners)
+                        {
+                            existing_inner
+                                .sequence
+                                .insert(current_arrangement_column, InnerChar::Blank);
+                        }
+
+                        sp3_secondary += 1;
+                    }
+
+                    inner.push(source_inner.next().unwrap().into());
+                    current_arrangement_column += 1;
                 }
-            }
+                AlignmentType::SecondaryDeletion => {
+                    while !source_arrangement.secondary(ts.secondary)[current_arrangement_column]
+                        .is_source_char()
+                    {
+                        inner.push(InnerChar::Blank);
+                        current_arrangement_column += 1;
+                    }
 
-            // We skip further secondary non-source chars for the assertion below.
-            while !source_arrangement.secondary(ts.secondary)[current_arrangement_column]
-                .is_source_char()
-            {
-                current_arrangement_column += 1;
-            }
-            assert_eq!(current_arrangement_column, sp2_secondary);
+                    inner.push(InnerChar::Gap {
+                        copy_depth: source_arrangement.secondary(ts.secondary)
+                            [current_arrangement_column]
+                            .copy_depth(),
+                    });
+                    current_arrangement_column += 1;
+                }
+                AlignmentType::SecondarySubstitution | AlignmentType::SecondaryMatch => {
+                    while !source_arrangement.secondary(ts.secondary)[current_arrangement_column]
+                        .is_source_char()
+                    {
+                        inner.push(InnerChar::Blank);
+                        current_arrangement_column += 1;
+                    }
 
-            let suffix_blanks =
-                iter::repeat_n(InnerChar::Blank, source_arrangement.reference().len())
-                    .skip(inner.len());
-            inner.extend(suffix_blanks);
+                    // The secondary itself is already shown, so only the inner is marked.
+                    let mut inner_char: InnerChar = source_inner.next().unwrap().into();
+                    if alignment_type == AlignmentType::SecondarySubstitution {
+                        inner_char.to_lower_case();
+                    }
 
-            match ts.secondary {
-                TemplateSwitchSecondary::Reference => {
-                    result.reference_inners.push(TsInner::new(inner, ts))
+                    inner.push(inner_char);
+                    current_arrangement_column += 1;
                 }
-                TemplateSwitchSecondary::Query => result.query_inners.push(TsInner::new(inner, ts)),
-            };
+                _ => unreachable!(),
+            }
         }
 
-        result
+        // We skip further secondary non-source chars for the assertion below.
+        while !source_arrangement.secondary(ts.secondary)[current_arrangement_column]
+            .is_source_char()
+        {
+            current_arrangement_column += 1;
+        }
+        assert_eq!(current_arrangement_column, sp3_secondary);
+
+        inner
     }
 
     pub fn remove_columns(
@@ -2,7 +2,9 @@ use std::{cmp::Ordering, iter};
 
 use lib_tsalign::a_star_aligner::{
     alignment_result::alignment::Alignment,
-    template_switch_distance::{AlignmentType, TemplateSwitchPrimary, TemplateSwitchSecondary},
+    template_switch_distance::{
+        AlignmentType, TemplateSwitchDirection, TemplateSwitchPrimary, TemplateSwitchSecondary,
+    },
 };
 use log::trace;
 use tagged_vec::TaggedVec;
@@ -96,12 +98,14 @@ impl TsSourceArrangement {
                 AlignmentType::TemplateSwitchEntrance {
                     primary,
                     secondary,
+                    direction,
                     first_offset,
                 } => {
                     template_switches_out.extend([result.align_ts(
                         ts_index,
                         primary,
                         secondary,
+                        direction,
                         first_offset,
                         &mut alignment,
                         &mut current_reference_index,
@@ -110,6 +114,33 @@ impl TsSourceArrangement {
                     ts_index += 1;
                 }
 
+                AlignmentType::PrimaryClip {
+                    reference_length,
+                    query_length,
+                } => {
+                    // Clipped characters are not aligned, so they are shown opposite of gaps.
+                    for _ in 0..reference_length {
+                        result.query.insert(
+                            current_query_index,
+                            SourceChar::Gap {
+                                copy_depth: result.reference[current_reference_index].copy_depth(),
+                            },
+                        );
+                        current_reference_index += 1;
+                        current_query_index += 1;
+                    }
+                    for _ in 0..query_length {
+                        result.reference.insert(
+                            current_reference_index,
+                            SourceChar::Gap {
+                                copy_depth: result.query[current_query_index].copy_depth(),
+                            },
+                        );
+                        current_reference_index += 1;
+                        current_query_index += 1;
+                    }
+                }
+
                 AlignmentType::Root | AlignmentType::PrimaryReentry => { /* Do nothing */ }
                 AlignmentType::TemplateSwitchExit { .. }
                 | AlignmentType::SecondaryInsertion
@@ -130,6 +161,7 @@ impl TsSourceArrangement {
         ts_index: usize,
         ts_primary: TemplateSwitchPrimary,
         ts_secondary: TemplateSwitchSecondary,
+        ts_direction: TemplateSwitchDirection,
         first_offset: isize,
         mut alignment: impl Iterator<Item = AlignmentType>,
         current_reference_index: &mut ArrangementColumn,
@@ -157,14 +189,20 @@ impl TsSourceArrangement {
                     break anti_primary_gap;
                 }
                 Some(alignment_type @ AlignmentType::SecondaryDeletion) => {
-                    sp3_secondary -= 1;
+                    match ts_direction {
+                        TemplateSwitchDirection::Reverse => sp3_secondary -= 1,
+                        TemplateSwitchDirection::Forward => sp3_secondary += 1,
+                    }
                     inner_alignment.push(alignment_type);
                 }
                 Some(
                     alignment_type @ (AlignmentType::SecondarySubstitution
                     | AlignmentType::SecondaryMatch),
                 ) => {
-                    sp3_secondary -= 1;
+                    match ts_direction {
+                        TemplateSwitchDirection::Reverse => sp3_secondary -= 1,
+                        TemplateSwitchDirection::Forward => sp3_secondary += 1,
+                    }
                     primary_inner_length += 1;
                     inner_alignment.push(alignment_type);
                 }
@@ -288,6 +326,7 @@ impl TsSourceArrangement {
             index: ts_index,
             primary: ts_primary,
             secondary: ts_secondary,
+            direction: ts_direction,
             sp1_reference,
             sp1_query,
             sp4_reference,
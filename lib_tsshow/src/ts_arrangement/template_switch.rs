@@ -4,7 +4,9 @@ use super::{
 };
 use lib_tsalign::a_star_aligner::{
     alignment_result::alignment::Alignment,
-    template_switch_distance::{AlignmentType, TemplateSwitchPrimary, TemplateSwitchSecondary},
+    template_switch_distance::{
+        AlignmentType, TemplateSwitchDirection, TemplateSwitchPrimary, TemplateSwitchSecondary,
+    },
 };
 
 #[derive(Debug, Clone)]
@@ -12,6 +14,7 @@ pub struct TemplateSwitch {
     pub index: usize,
     pub primary: TemplateSwitchPrimary,
     pub secondary: TemplateSwitchSecondary,
+    pub direction: TemplateSwitchDirection,
     pub sp1_reference: ArrangementCharColumn,
     pub sp1_query: ArrangementCharColumn,
     pub sp4_reference: ArrangementCharColumn,
@@ -1,15 +1,19 @@
 use context::Context;
-use generic_a_star::{AStar, cost::AStarCost};
+use generic_a_star::{bidirectional::BidirectionalAStar, cost::AStarCost, memory};
 use log::info;
 use node::EdgeType;
 
 use crate::seed::ChainingAnchors;
 
 pub use context::ChainingCostsProvider;
+pub use gap_costs::GapChainingCosts;
+pub use local::{ChainOrientation, LocalChain, LocalChainingParameters};
 pub use node::Identifier;
 
 mod context;
 mod display;
+mod gap_costs;
+mod local;
 mod node;
 
 pub struct Chain<Cost> {
@@ -22,24 +26,44 @@ pub struct ChainLink<Cost> {
 }
 
 impl<Cost: AStarCost> Chain<Cost> {
+    /// Compute the cheapest chain of anchors from the start to the end of the sequences.
+    ///
+    /// Anchors may overlap, in which case the later anchor is trimmed when it is chained.
+    ///
+    /// The chain is computed with a bidirectional search, since the chaining costs provide no lower bounds to guide a forward search.
     pub fn compute_chain<ChainingCosts: ChainingCostsProvider<Cost = Cost>>(
         chaining_costs: ChainingCosts,
         chaining_anchors: ChainingAnchors,
     ) -> Self {
         info!("Computing chain...");
-        let mut a_star = AStar::new(Context::new(chaining_costs, chaining_anchors));
+        let mut a_star = BidirectionalAStar::new(Context::new(chaining_costs, chaining_anchors));
+        a_star
+            .initialise()
+            .expect("The chaining context is label setting");
+        a_star.search();
+
+        Self::from_backtrack(a_star.backtrack_with_costs())
+    }
+
+    /// Compute the chain using a forward search, for comparison with the bidirectional search.
+    #[cfg(test)]
+    fn compute_chain_forward<ChainingCosts: ChainingCostsProvider<Cost = Cost>>(
+        chaining_costs: ChainingCosts,
+        chaining_anchors: ChainingAnchors,
+    ) -> Self {
+        let mut a_star = generic_a_star::AStar::new(Context::new(chaining_costs, chaining_anchors));
         a_star.initialise();
         a_star.search();
 
+        Self::from_backtrack(a_star.backtrack_with_costs())
+    }
+
+    fn from_backtrack(backtrack: impl Iterator<Item = (EdgeType, Cost)>) -> Self {
         let mut chain = Vec::new();
-        chain.extend(
-            a_star
-                .backtrack_with_costs()
-                .map(|(EdgeType { to, .. }, cost)| match to {
-                    Identifier::Root => unreachable!(),
-                    identifier => ChainLink { identifier, cost },
-                }),
-        );
+        chain.extend(backtrack.map(|(EdgeType { to, .. }, cost)| match to {
+            Identifier::Root => unreachable!(),
+            identifier => ChainLink { identifier, cost },
+        }));
         chain.push(ChainLink {
             identifier: Identifier::Root,
             cost: Cost::zero(),
@@ -52,7 +76,7 @@ impl<Cost: AStarCost> Chain<Cost> {
             .iter_mut()
             .for_each(|link| link.cost = total_cost - link.cost);
 
-        // Assert that costs and blocks are ordered, and blocks do not overlap.
+        // Assert that costs and blocks are ordered, and blocks can be chained after trimming overlaps.
         debug_assert!({
             chain.windows(2).all(|window| {
                 let first = &window[0];
@@ -76,9 +100,7 @@ impl<Cost: AStarCost> Chain<Cost> {
                         },
                     ) => {
                         first.cost <= second.cost
-                            && first_anchor.reference_block().end
-                                <= second_anchor.reference_block().start
-                            && first_anchor.query_block().end <= second_anchor.query_block().start
+                            && second_anchor.trimmed_after(first_anchor).is_some()
                     }
                 }
             })
@@ -107,4 +129,9 @@ impl<Cost: AStarCost> Chain<Cost> {
                 .unwrap_or(Cost::zero()),
         }
     }
+
+    /// Returns the number of bytes used by the chain on the heap.
+    pub fn heap_size(&self) -> usize {
+        memory::vec_heap_size::<ChainLink<Cost>>(self.chain.capacity())
+    }
 }
@@ -1,4 +1,7 @@
-use generic_a_star::{AStarContext, AStarNode, cost::AStarCost, reset::Reset};
+use generic_a_star::{
+    AStarContext, AStarNode, bidirectional::BidirectionalAStarContext, cost::AStarCost,
+    reset::Reset,
+};
 
 use crate::seed::ChainingAnchors;
 
@@ -9,6 +12,8 @@ pub trait ChainingCostsProvider {
 
     /// Returns the cost of chaining `to` after `from`.
     ///
+    /// If `to` is an anchor that overlaps `from`, then it is given trimmed as by [`crate::seed::ChainingAnchor::trimmed_after`],
+    /// so the two never overlap.
     /// If the chaining is impossible, then `Cost::MAX` is returned.
     fn chaining_costs(&self, from: &Identifier, to: &Identifier) -> Self::Cost;
 }
@@ -26,15 +31,14 @@ impl<ChainingCosts: ChainingCostsProvider> AStarContext for Context<ChainingCost
     }
 
     fn generate_successors(&mut self, node: &Self::Node, output: &mut impl Extend<Self::Node>) {
-        // We skip all anchors whose reference block is not right of the current node.
+        // We skip all anchors whose reference block starts left of the current node.
         let first_chainable_block_index = match node.identifier() {
             Identifier::Root => 0,
             Identifier::Anchor { anchor } => {
                 self.chaining_anchors
                     .anchors()
                     .partition_point(|chaining_anchor| {
-                        // We do not allow to chain overlapping blocks.
-                        chaining_anchor.reference_block().start < anchor.reference_block().end
+                        chaining_anchor.reference_block().start < anchor.reference_block().start
                     })
             }
             Identifier::Target => self.chaining_anchors.anchors().len(),
@@ -46,20 +50,26 @@ impl<ChainingCosts: ChainingCostsProvider> AStarContext for Context<ChainingCost
                 .iter()
                 .skip(first_chainable_block_index)
                 .filter_map(|chaining_anchor| {
-                    // We skip all anchors whose query block is not right of the current node.
-                    if let Identifier::Anchor { anchor } = node.identifier() {
-                        if chaining_anchor.query_block().start < anchor.query_block().end {
-                            return None;
-                        }
-                    }
-
-                    let successor_identifier = Identifier::Anchor {
-                        anchor: chaining_anchor.clone(),
+                    // Overlapping anchors are trimmed for computing the chaining costs,
+                    // but the successor keeps the untrimmed anchor, since only its end matters for further chaining.
+                    let trimmed_anchor = if let Identifier::Anchor { anchor } = node.identifier() {
+                        chaining_anchor.trimmed_after(anchor)?
+                    } else {
+                        chaining_anchor.clone()
                     };
-                    let cost_increment = self
-                        .chaining_costs
-                        .chaining_costs(node.identifier(), &successor_identifier);
-                    node.generate_successor(successor_identifier, cost_increment)
+
+                    let cost_increment = self.chaining_costs.chaining_costs(
+                        node.identifier(),
+                        &Identifier::Anchor {
+                            anchor: trimmed_anchor,
+                        },
+                    );
+                    node.generate_successor(
+                        Identifier::Anchor {
+                            anchor: chaining_anchor.clone(),
+                        },
+                        cost_increment,
+                    )
                 })
                 .chain(if !matches!(node.identifier(), Identifier::Target) {
                     node.generate_successor(
@@ -86,6 +96,69 @@ impl<ChainingCosts: ChainingCostsProvider> AStarContext for Context<ChainingCost
     }
 }
 
+impl<ChainingCosts: ChainingCostsProvider> BidirectionalAStarContext for Context<ChainingCosts> {
+    fn create_reverse_roots(&self, output: &mut impl Extend<Self::Node>) {
+        output.extend([Self::Node::new_reverse_root()]);
+    }
+
+    fn generate_reverse_successors(
+        &mut self,
+        node: &Self::Node,
+        output: &mut impl Extend<Self::Node>,
+    ) {
+        // We skip all anchors whose reference block starts right of the current node.
+        // Since anchors are ordered by their start, this only limits the end of the considered range.
+        let last_chainable_block_index = match node.identifier() {
+            Identifier::Root => 0,
+            Identifier::Anchor { anchor } => {
+                self.chaining_anchors
+                    .anchors()
+                    .partition_point(|chaining_anchor| {
+                        chaining_anchor.reference_block().start <= anchor.reference_block().start
+                    })
+            }
+            Identifier::Target => self.chaining_anchors.anchors().len(),
+        };
+
+        output.extend(
+            self.chaining_anchors.anchors()[..last_chainable_block_index]
+                .iter()
+                .filter_map(|chaining_anchor| {
+                    // Overlapping anchors are trimmed like in the forward search.
+                    let trimmed_identifier =
+                        if let Identifier::Anchor { anchor } = node.identifier() {
+                            Identifier::Anchor {
+                                anchor: anchor.trimmed_after(chaining_anchor)?,
+                            }
+                        } else {
+                            node.identifier().clone()
+                        };
+
+                    let predecessor_identifier = Identifier::Anchor {
+                        anchor: chaining_anchor.clone(),
+                    };
+                    let cost_increment = self
+                        .chaining_costs
+                        .chaining_costs(&predecessor_identifier, &trimmed_identifier);
+                    node.generate_reverse_successor(predecessor_identifier, cost_increment)
+                })
+                .chain(if !matches!(node.identifier(), Identifier::Root) {
+                    node.generate_reverse_successor(
+                        Identifier::Root,
+                        self.chaining_costs
+                            .chaining_costs(&Identifier::Root, node.identifier()),
+                    )
+                } else {
+                    None
+                }),
+        );
+    }
+
+    fn is_reverse_target(&self, node: &Self::Node) -> bool {
+        matches!(node.identifier(), Identifier::Root)
+    }
+}
+
 impl<ChainingCosts: ChainingCostsProvider> Context<ChainingCosts> {
     pub fn new(chaining_costs: ChainingCosts, chaining_anchors: ChainingAnchors) -> Self {
         Self {
@@ -0,0 +1,169 @@
+use generic_a_star::cost::AStarCost;
+
+use super::{ChainingCostsProvider, Identifier};
+
+/// Chaining costs from a linear or affine gap cost model.
+///
+/// Chaining an anchor after another is free if both lie on the same diagonal,
+/// and otherwise costs a single gap whose length is the difference between their diagonals.
+/// The characters between two anchors are free, so if an aligner charges at least these gap costs,
+/// then the cost of a chain is a lower bound for the cost of any alignment that contains its anchors.
+///
+/// This does not hold for template switch alignments, since a template switch may change the diagonal for less than a gap.
+/// Hence, these costs are meant for aligners without template switches,
+/// and template switch alignments chain with costs derived from their own lower bounds instead.
+#[derive(Debug, Clone)]
+pub struct GapChainingCosts<Cost> {
+    gap_open_cost: Cost,
+    gap_extend_cost: Cost,
+    reference_length: usize,
+    query_length: usize,
+}
+
+impl<Cost: AStarCost> GapChainingCosts<Cost> {
+    /// A gap of length `n` costs `n * gap_cost`.
+    pub fn new_linear(gap_cost: Cost, reference_length: usize, query_length: usize) -> Self {
+        Self::new_affine(Cost::zero(), gap_cost, reference_length, query_length)
+    }
+
+    /// A gap of length `n` costs `gap_open_cost + n * gap_extend_cost`.
+    pub fn new_affine(
+        gap_open_cost: Cost,
+        gap_extend_cost: Cost,
+        reference_length: usize,
+        query_length: usize,
+    ) -> Self {
+        Self {
+            gap_open_cost,
+            gap_extend_cost,
+            reference_length,
+            query_length,
+        }
+    }
+
+    /// Returns the cost of a gap of the given length, or `Cost::max_value()` if it overflows.
+    pub fn gap_cost(&self, length: usize) -> Cost {
+        if length == 0 {
+            return Cost::zero();
+        }
+
+        // Multiply by doubling, since costs only support addition.
+        let mut extend_cost = Cost::zero();
+        let mut power = self.gap_extend_cost;
+        let mut remaining_length = length;
+        while remaining_length > 0 {
+            if remaining_length & 1 == 1 {
+                let Some(sum) = extend_cost.checked_add(&power) else {
+                    return Cost::max_value();
+                };
+                extend_cost = sum;
+            }
+
+            remaining_length >>= 1;
+            if remaining_length > 0 {
+                let Some(double) = power.checked_add(&power) else {
+                    return Cost::max_value();
+                };
+                power = double;
+            }
+        }
+
+        self.gap_open_cost
+            .checked_add(&extend_cost)
+            .unwrap_or_else(Cost::max_value)
+    }
+}
+
+impl<Cost: AStarCost> ChainingCostsProvider for GapChainingCosts<Cost> {
+    type Cost = Cost;
+
+    fn chaining_costs(&self, from: &Identifier, to: &Identifier) -> Cost {
+        let from = from.to_anchor(self.reference_length, self.query_length);
+        let to = to.to_anchor(self.reference_length, self.query_length);
+        if from.reference_block().end > to.reference_block().start
+            || from.query_block().end > to.query_block().start
+        {
+            return Cost::max_value();
+        }
+
+        let delta_reference = to.reference_block().start - from.reference_block().end;
+        let delta_query = to.query_block().start - from.query_block().end;
+        self.gap_cost(delta_reference.abs_diff(delta_query))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use generic_a_star::cost::U64Cost;
+
+    use crate::{
+        chain::Chain,
+        seed::{ChainingAnchor, ChainingAnchors},
+    };
+
+    use super::GapChainingCosts;
+
+    fn chain_cost(
+        anchors: &[(std::ops::Range<usize>, std::ops::Range<usize>)],
+        costs: &GapChainingCosts<U64Cost>,
+    ) -> U64Cost {
+        let anchors = ChainingAnchors::from_anchors(
+            anchors
+                .iter()
+                .map(|(reference_block, query_block)| {
+                    ChainingAnchor::new(reference_block.clone(), query_block.clone())
+                })
+                .collect(),
+        );
+
+        let cost = Chain::compute_chain(costs.clone(), anchors.clone()).chain_lower_bound(0, 0);
+        assert_eq!(
+            cost,
+            Chain::compute_chain_forward(costs.clone(), anchors).chain_lower_bound(0, 0)
+        );
+        cost
+    }
+
+    #[test]
+    fn test_gap_cost() {
+        let costs = GapChainingCosts::new_affine(U64Cost::from(3u64), U64Cost::from(2u64), 0, 0);
+        assert_eq!(costs.gap_cost(0), U64Cost::from(0u64));
+        assert_eq!(costs.gap_cost(1), U64Cost::from(5u64));
+        assert_eq!(costs.gap_cost(7), U64Cost::from(17u64));
+        assert_eq!(costs.gap_cost(usize::MAX), U64Cost::from(u64::MAX));
+
+        let costs = GapChainingCosts::new_linear(U64Cost::from(2u64), 0, 0);
+        assert_eq!(costs.gap_cost(6), U64Cost::from(12u64));
+    }
+
+    #[test]
+    fn test_overlapping_anchors_on_diagonal() {
+        // Overlapping k-mers of identical sequences chain without gaps.
+        let costs = GapChainingCosts::new_affine(U64Cost::from(3u64), U64Cost::from(1u64), 8, 8);
+        assert_eq!(
+            chain_cost(&[(0..4, 0..4), (2..6, 2..6), (4..8, 4..8)], &costs),
+            U64Cost::from(0u64)
+        );
+    }
+
+    #[test]
+    fn test_single_gap() {
+        let costs = GapChainingCosts::new_affine(U64Cost::from(3u64), U64Cost::from(1u64), 10, 12);
+        assert_eq!(
+            chain_cost(&[(0..5, 0..5), (5..10, 7..12)], &costs),
+            U64Cost::from(5u64)
+        );
+
+        // The overlap with the first anchor is trimmed from the second.
+        assert_eq!(
+            chain_cost(&[(0..6, 0..6), (4..10, 6..12)], &costs),
+            U64Cost::from(5u64)
+        );
+
+        let costs = GapChainingCosts::new_linear(U64Cost::from(2u64), 10, 12);
+        assert_eq!(
+            chain_cost(&[(0..5, 0..5), (5..10, 7..12)], &costs),
+            U64Cost::from(4u64)
+        );
+    }
+}
@@ -0,0 +1,407 @@
+use std::{fmt::Display, ops::Range};
+
+use log::info;
+
+use crate::seed::{AnchorSequence, ChainingAnchor, ChainingAnchors};
+
+/// The orientation of the query relative to the reference in a [`LocalChain`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum ChainOrientation {
+    /// The reference matches the query.
+    Forward,
+    /// The reference matches the reverse complement of the query.
+    ReverseComplement,
+}
+
+/// The parameters for [`LocalChain::compute_local_chains`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LocalChainingParameters {
+    /// The maximum number of chains that are reported.
+    pub max_chains: usize,
+    /// Chains with a lower score are not reported.
+    pub min_score: usize,
+    /// Two anchors are only chained if neither their reference nor their query gap is longer than this.
+    pub max_gap: usize,
+}
+
+impl Default for LocalChainingParameters {
+    /// At most 10 chains, with a score of at least 50, and gaps of at most 1000 characters.
+    fn default() -> Self {
+        Self {
+            max_chains: 10,
+            min_score: 50,
+            max_gap: 1000,
+        }
+    }
+}
+
+/// A chain of anchors that does not need to span from the start to the end of the sequences.
+///
+/// The score of a chain is the total length of its anchors, where overlaps between consecutive anchors are only counted once,
+/// minus the difference between the reference gap and the query gap between each pair of consecutive anchors.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LocalChain {
+    orientation: ChainOrientation,
+    score: usize,
+    anchors: Vec<ChainingAnchor>,
+}
+
+impl LocalChain {
+    /// Compute the best local chains of the given anchors, ordered by descending score.
+    ///
+    /// Forward chains are computed from [`ChainingAnchors::anchors`].
+    /// Reverse complement chains are computed from the reverse complement anchors with the reference as primary
+    /// and the query as secondary, so they only exist if the anchors were computed with
+    /// [`ChainingAnchors::with_reverse_complement_anchors`].
+    ///
+    /// Each anchor is part of at most one chain per orientation.
+    pub fn compute_local_chains(
+        chaining_anchors: &ChainingAnchors,
+        parameters: &LocalChainingParameters,
+    ) -> Vec<Self> {
+        info!("Computing local chains...");
+        let reverse_complement_anchors: Vec<_> = chaining_anchors
+            .reverse_complement_anchors_of(AnchorSequence::Reference, AnchorSequence::Query)
+            .iter()
+            .map(|anchor| {
+                ChainingAnchor::new(
+                    anchor.primary_block().clone(),
+                    anchor.secondary_block().clone(),
+                )
+            })
+            .collect();
+
+        let mut chains = local_chains(
+            chaining_anchors.anchors(),
+            ChainOrientation::Forward,
+            parameters,
+        );
+        chains.extend(local_chains(
+            &reverse_complement_anchors,
+            ChainOrientation::ReverseComplement,
+            parameters,
+        ));
+
+        chains.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.orientation.cmp(&b.orientation))
+                .then_with(|| a.anchors.first().cmp(&b.anchors.first()))
+        });
+        chains.truncate(parameters.max_chains);
+        chains
+    }
+
+    pub fn orientation(&self) -> ChainOrientation {
+        self.orientation
+    }
+
+    pub fn score(&self) -> usize {
+        self.score
+    }
+
+    /// The anchors of the chain, ordered by their reference block.
+    ///
+    /// In a reverse complement chain, the query blocks are in query coordinates, so they are ordered descendingly.
+    pub fn anchors(&self) -> &[ChainingAnchor] {
+        &self.anchors
+    }
+
+    /// The range of the reference covered by the chain.
+    pub fn reference_span(&self) -> Range<usize> {
+        self.anchors.first().unwrap().reference_block().start
+            ..self.anchors.last().unwrap().reference_block().end
+    }
+
+    /// The range of the query covered by the chain.
+    pub fn query_span(&self) -> Range<usize> {
+        let start = self
+            .anchors
+            .iter()
+            .map(|anchor| anchor.query_block().start)
+            .min()
+            .unwrap();
+        let end = self
+            .anchors
+            .iter()
+            .map(|anchor| anchor.query_block().end)
+            .max()
+            .unwrap();
+        start..end
+    }
+}
+
+/// Compute the local chains of a single orientation.
+///
+/// The anchors must be sorted by their reference block.
+fn local_chains(
+    anchors: &[ChainingAnchor],
+    orientation: ChainOrientation,
+    parameters: &LocalChainingParameters,
+) -> Vec<LocalChain> {
+    debug_assert!(anchors.is_sorted());
+    let max_block_length = anchors
+        .iter()
+        .map(|anchor| anchor.reference_block().len())
+        .max()
+        .unwrap_or(0);
+
+    // The best score of a chain ending in each anchor, and the predecessor of the anchor in that chain.
+    let mut scores: Vec<isize> = Vec::with_capacity(anchors.len());
+    let mut predecessors: Vec<Option<usize>> = Vec::with_capacity(anchors.len());
+
+    for (index, anchor) in anchors.iter().enumerate() {
+        let mut score = anchor.reference_block().len() as isize;
+        let mut predecessor = None;
+
+        for (predecessor_index, predecessor_anchor) in anchors[..index].iter().enumerate().rev() {
+            if predecessor_anchor.reference_block().start + max_block_length + parameters.max_gap
+                < anchor.reference_block().start
+            {
+                break;
+            }
+
+            let Some((reference_gap, query_gap, trimmed_length)) =
+                gaps(predecessor_anchor, anchor, orientation)
+            else {
+                continue;
+            };
+            if reference_gap > parameters.max_gap || query_gap > parameters.max_gap {
+                continue;
+            }
+
+            let chained_score = scores[predecessor_index] + trimmed_length as isize
+                - reference_gap.abs_diff(query_gap) as isize;
+            if chained_score > score {
+                score = chained_score;
+                predecessor = Some(predecessor_index);
+            }
+        }
+
+        scores.push(score);
+        predecessors.push(predecessor);
+    }
+
+    // Extract chains greedily by descending score, such that each anchor is used at most once.
+    let mut order: Vec<_> = (0..anchors.len()).collect();
+    order.sort_by_key(|&index| std::cmp::Reverse(scores[index]));
+    let mut used = vec![false; anchors.len()];
+    let mut chains = Vec::new();
+
+    for end in order {
+        if used[end] {
+            continue;
+        }
+
+        let mut chain_anchors = Vec::new();
+        let mut current = Some(end);
+        let mut start_score = 0;
+        while let Some(index) = current {
+            if used[index] {
+                // The rest of the chain was already reported as part of a better chain.
+                start_score = scores[index];
+                break;
+            }
+
+            used[index] = true;
+            chain_anchors.push(anchors[index].clone());
+            current = predecessors[index];
+        }
+        chain_anchors.reverse();
+
+        let score = (scores[end] - start_score).max(0) as usize;
+        if score >= parameters.min_score {
+            chains.push(LocalChain {
+                orientation,
+                score,
+                anchors: chain_anchors,
+            });
+        }
+    }
+
+    chains
+}
+
+/// Returns the reference and query gaps between two anchors and the length of `to` after trimming its overlap with `from`,
+/// or `None` if `to` cannot be chained after `from`.
+///
+/// Overlapping anchors are chained like in [`Chain`](crate::chain::Chain), see [`ChainingAnchor::trimmed_after`].
+/// In reverse complement orientation, the query coordinates are mirrored, such that `to` lies after `from` on both axes.
+fn gaps(
+    from: &ChainingAnchor,
+    to: &ChainingAnchor,
+    orientation: ChainOrientation,
+) -> Option<(usize, usize, usize)> {
+    let (from, to) = match orientation {
+        ChainOrientation::Forward => (from.clone(), to.clone()),
+        ChainOrientation::ReverseComplement => (mirror_query(from), mirror_query(to)),
+    };
+    let to = to.trimmed_after(&from)?;
+
+    Some((
+        to.reference_block().start - from.reference_block().end,
+        to.query_block().start - from.query_block().end,
+        to.reference_block().len(),
+    ))
+}
+
+/// Mirror the query block of the anchor, such that descending query coordinates become ascending.
+fn mirror_query(anchor: &ChainingAnchor) -> ChainingAnchor {
+    ChainingAnchor::new(
+        anchor.reference_block().clone(),
+        usize::MAX - anchor.query_block().end..usize::MAX - anchor.query_block().start,
+    )
+}
+
+impl Display for LocalChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reference_span = self.reference_span();
+        let query_span = self.query_span();
+        write!(
+            f,
+            "{:?} chain with score {} and {} anchors: R{}..{}, Q{}..{}",
+            self.orientation,
+            self.score,
+            self.anchors.len(),
+            reference_span.start,
+            reference_span.end,
+            query_span.start,
+            query_span.end,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use compact_genome::{
+        implementation::{alphabets::dna_alphabet::DnaAlphabet, vec_sequence::VectorGenome},
+        interface::sequence::{GenomeSequence, OwnedGenomeSequence},
+    };
+
+    use crate::{
+        seed::{ChainingAnchor, ChainingAnchors, SeedingMethod},
+        test_util::{DNA, pseudo_random_sequence, reverse_complement},
+    };
+
+    use super::{ChainOrientation, LocalChain, LocalChainingParameters, local_chains};
+
+    /// Returns the reference `ABC` and the query `A rc(B) C` for random `A`, `B` and `C` of length 60 each.
+    fn inversion_anchors() -> ChainingAnchors {
+        let a = pseudo_random_sequence(1, 60, DNA);
+        let b = pseudo_random_sequence(2, 60, DNA);
+        let c = pseudo_random_sequence(3, 60, DNA);
+        let reference = format!("{a}{b}{c}");
+        let query = format!("{a}{}{c}", reverse_complement(&b));
+        let reference = VectorGenome::<DnaAlphabet>::from_slice_u8(reference.as_bytes()).unwrap();
+        let query = VectorGenome::<DnaAlphabet>::from_slice_u8(query.as_bytes()).unwrap();
+        let seeding_method = SeedingMethod::NonOverlapping { block_size: 10 };
+
+        ChainingAnchors::seed(
+            reference.as_genome_subsequence(),
+            query.as_genome_subsequence(),
+            &seeding_method,
+        )
+        .with_reverse_complement_anchors(
+            reference.as_genome_subsequence(),
+            query.as_genome_subsequence(),
+            &seeding_method,
+        )
+    }
+
+    #[test]
+    fn test_local_chains_inversion() {
+        let chains = LocalChain::compute_local_chains(
+            &inversion_anchors(),
+            &LocalChainingParameters {
+                max_chains: 10,
+                min_score: 30,
+                max_gap: 100,
+            },
+        );
+
+        assert_eq!(chains.len(), 2, "{chains:?}");
+        assert_eq!(chains[0].orientation(), ChainOrientation::Forward);
+        assert_eq!(chains[0].score(), 120);
+        assert_eq!(chains[0].reference_span(), 0..180);
+        assert_eq!(chains[0].query_span(), 0..180);
+        assert_eq!(chains[1].orientation(), ChainOrientation::ReverseComplement);
+        assert_eq!(chains[1].score(), 60);
+        assert_eq!(chains[1].reference_span(), 60..120);
+        assert_eq!(chains[1].query_span(), 60..120);
+    }
+
+    #[test]
+    fn test_local_chains_max_gap() {
+        let parameters = LocalChainingParameters {
+            max_chains: 10,
+            min_score: 30,
+            max_gap: 30,
+        };
+        let chains = LocalChain::compute_local_chains(&inversion_anchors(), &parameters);
+
+        let mut spans: Vec<_> = chains
+            .iter()
+            .map(|chain| {
+                assert_eq!(chain.score(), 60);
+                (
+                    chain.orientation(),
+                    chain.reference_span(),
+                    chain.query_span(),
+                )
+            })
+            .collect();
+        spans.sort_by_key(|(orientation, reference_span, _)| (*orientation, reference_span.start));
+        assert_eq!(
+            spans,
+            vec![
+                (ChainOrientation::Forward, 0..60, 0..60),
+                (ChainOrientation::Forward, 120..180, 120..180),
+                (ChainOrientation::ReverseComplement, 60..120, 60..120),
+            ]
+        );
+
+        let chains = LocalChain::compute_local_chains(
+            &inversion_anchors(),
+            &LocalChainingParameters {
+                max_chains: 2,
+                ..parameters
+            },
+        );
+        assert_eq!(chains.len(), 2);
+    }
+
+    #[test]
+    fn test_local_chains_overlapping_anchors() {
+        let parameters = LocalChainingParameters {
+            max_chains: 10,
+            min_score: 0,
+            max_gap: 10,
+        };
+
+        let chains = local_chains(
+            &[
+                ChainingAnchor::new(0..4, 0..4),
+                ChainingAnchor::new(2..6, 2..6),
+                ChainingAnchor::new(4..8, 5..9),
+            ],
+            ChainOrientation::Forward,
+            &parameters,
+        );
+        assert_eq!(chains.len(), 1, "{chains:?}");
+        assert_eq!(chains[0].score(), 7);
+        assert_eq!(chains[0].anchors().len(), 3);
+
+        let chains = local_chains(
+            &[
+                ChainingAnchor::new(0..4, 6..10),
+                ChainingAnchor::new(2..6, 4..8),
+                ChainingAnchor::new(4..8, 1..5),
+            ],
+            ChainOrientation::ReverseComplement,
+            &parameters,
+        );
+        assert_eq!(chains.len(), 1, "{chains:?}");
+        assert_eq!(chains[0].score(), 7);
+        assert_eq!(chains[0].query_span(), 1..10);
+    }
+}
@@ -11,11 +11,30 @@ pub enum Identifier {
     Target,
 }
 
+impl Identifier {
+    /// Returns the anchor of this identifier,
+    /// where the root is an empty anchor at the start and the target is an empty anchor at the end of the sequences.
+    pub fn to_anchor(&self, reference_length: usize, query_length: usize) -> ChainingAnchor {
+        match self {
+            Identifier::Root => ChainingAnchor::new(0..0, 0..0),
+            Identifier::Anchor { anchor } => anchor.clone(),
+            Identifier::Target => ChainingAnchor::new(
+                reference_length..reference_length,
+                query_length..query_length,
+            ),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Node<Cost> {
     identifier: Identifier,
     predecessor: Option<Identifier>,
     cost: Cost,
+    /// True if this node was generated by a reverse search.
+    ///
+    /// In this case, the predecessor is the successor in the chaining graph.
+    is_reverse: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -49,9 +68,18 @@ impl<Cost: AStarCost> AStarNode for Node<Cost> {
     }
 
     fn predecessor_edge_type(&self) -> Option<Self::EdgeType> {
-        self.predecessor.clone().map(|from| EdgeType {
-            from,
-            to: self.identifier.clone(),
+        self.predecessor.clone().map(|predecessor| {
+            if self.is_reverse {
+                EdgeType {
+                    from: self.identifier.clone(),
+                    to: predecessor,
+                }
+            } else {
+                EdgeType {
+                    from: predecessor,
+                    to: self.identifier.clone(),
+                }
+            }
         })
     }
 }
@@ -62,6 +90,16 @@ impl<Cost: AStarCost> Node<Cost> {
             identifier: Identifier::Root,
             predecessor: None,
             cost: Cost::zero(),
+            is_reverse: false,
+        }
+    }
+
+    pub fn new_reverse_root() -> Self {
+        Self {
+            identifier: Identifier::Target,
+            predecessor: None,
+            cost: Cost::zero(),
+            is_reverse: true,
         }
     }
 
@@ -80,6 +118,27 @@ impl<Cost: AStarCost> Node<Cost> {
             identifier: successor_identifier,
             predecessor: Some(self.identifier.clone()),
             cost: self.cost + cost_increment,
+            is_reverse: false,
+        })
+    }
+
+    /// Generate a predecessor of this node in the chaining graph for a reverse search.
+    pub fn generate_reverse_successor(
+        &self,
+        predecessor_identifier: Identifier,
+        cost_increment: Cost,
+    ) -> Option<Self> {
+        if cost_increment == Cost::max_value() {
+            return None;
+        }
+
+        debug_assert!(!matches!(self.identifier, Identifier::Root));
+
+        Some(Self {
+            identifier: predecessor_identifier,
+            predecessor: Some(self.identifier.clone()),
+            cost: self.cost + cost_increment,
+            is_reverse: true,
         })
     }
 }
@@ -2,3 +2,6 @@
 
 pub mod chain;
 pub mod seed;
+
+#[cfg(test)]
+mod test_util;
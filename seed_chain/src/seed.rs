@@ -1,11 +1,17 @@
-use std::ops::Range;
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+    ops::Range,
+};
 
 use compact_genome::interface::{alphabet::Alphabet, sequence::GenomeSequence};
-use log::info;
+use log::{info, warn};
 
 #[derive(Debug, Clone)]
 pub struct ChainingAnchors {
     anchors: Vec<ChainingAnchor>,
+    reverse_complement_anchors: Vec<ReverseComplementAnchor>,
+    max_reverse_complement_block_length: usize,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -14,12 +20,96 @@ pub struct ChainingAnchor {
     query_block: Range<usize>,
 }
 
+/// One of the two sequences that are aligned.
+///
+/// Used to tag [`ReverseComplementAnchor`]s with the primary and secondary sequence of the template switches they seed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum AnchorSequence {
+    Reference,
+    Query,
+}
+
+/// A match between a block of the primary sequence and the reverse complement of a block of the secondary sequence.
+///
+/// The `i`th character of the primary block matches the complement of the `i`th-last character of the secondary block.
+/// Hence, it marks a candidate location for the inner alignment of a template switch.
+///
+/// These anchors are a library building block for ranking or restricting template switch entrances and offsets.
+/// Neither the chaining of this crate nor the template switch aligner use them yet.
+/// They only seed inverted template switches, whose inner alignment runs against the reverse complement of the secondary.
+/// The inner alignment of a forward template switch matches the secondary as is, which these anchors do not cover.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ReverseComplementAnchor {
+    primary: AnchorSequence,
+    secondary: AnchorSequence,
+    primary_block: Range<usize>,
+    secondary_block: Range<usize>,
+}
+
+/// The method used to compute [`ChainingAnchors`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SeedingMethod {
+    /// See [`ChainingAnchors::seed_nonoverlapping`].
+    NonOverlapping { block_size: usize },
+    /// See [`ChainingAnchors::seed_minimizers`].
+    Minimizers { window_size: usize, k: usize },
+    /// See [`ChainingAnchors::seed_spaced`].
+    ///
+    /// The pattern is `true` at the positions that need to match, and `false` at "don't care" positions.
+    SpacedSeed { pattern: Vec<bool> },
+}
+
+impl Default for SeedingMethod {
+    /// Non-overlapping blocks of size 20.
+    fn default() -> Self {
+        Self::NonOverlapping { block_size: 20 }
+    }
+}
+
 impl ChainingAnchors {
+    /// Compute a set of anchors for the given reference and query sequences with the given seeding method.
+    pub fn seed<
+        AlphabetType: Alphabet,
+        SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized,
+    >(
+        reference: &SubsequenceType,
+        query: &SubsequenceType,
+        seeding_method: &SeedingMethod,
+    ) -> Self {
+        match seeding_method {
+            SeedingMethod::NonOverlapping { block_size } => {
+                Self::seed_nonoverlapping(reference, query, *block_size)
+            }
+            SeedingMethod::Minimizers { window_size, k } => {
+                Self::seed_minimizers(reference, query, *window_size, *k)
+            }
+            SeedingMethod::SpacedSeed { pattern } => Self::seed_spaced(reference, query, pattern),
+        }
+    }
+
+    /// Returns a set without anchors.
+    pub fn empty() -> Self {
+        Self::from_anchors(Vec::new())
+    }
+
+    /// Creates a set from the given anchors, which do not need to be sorted.
+    pub fn from_anchors(mut anchors: Vec<ChainingAnchor>) -> Self {
+        anchors.sort();
+
+        Self {
+            anchors,
+            reverse_complement_anchors: Vec::new(),
+            max_reverse_complement_block_length: 0,
+        }
+    }
+
     /// Compute a set of anchors for the given reference and query sequences.
     ///
     /// The anchors are computed by subdividing the reference sequence into non-overlapping blocks of size `block_size`,
     /// and collecting all their matches in the query sequence.
     /// The last block is merged with the second-to-last block if it is smaller than `block_size`.
+    ///
+    /// If the reference is shorter than `block_size`, then there are no anchors.
     pub fn seed_nonoverlapping<
         AlphabetType: Alphabet,
         SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized,
@@ -29,50 +119,177 @@ impl ChainingAnchors {
         block_size: usize,
     ) -> Self {
         info!("Computing non-overlapping chaining anchors with block size {block_size}...");
-        assert!(
-            reference.len() >= block_size,
-            "Reference (length: {}) is shorter than the block size {}",
-            reference.len(),
-            block_size
+        // Read into strings so we can hash k-mers with the stdlib.
+        Self::from_anchors(nonoverlapping_anchors(
+            &reference.as_string(),
+            &query.as_string(),
+            block_size,
+        ))
+    }
+
+    /// Compute a set of anchors for the given reference and query sequences from their `(window_size, k)`-minimizers.
+    ///
+    /// A minimizer is the k-mer with the smallest hash value among `window_size` consecutive k-mers.
+    /// The anchors are all pairs of a reference minimizer and a query minimizer of the same k-mer.
+    /// Hence, each exact match of at least `window_size + k - 1` characters contains an anchor.
+    pub fn seed_minimizers<
+        AlphabetType: Alphabet,
+        SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized,
+    >(
+        reference: &SubsequenceType,
+        query: &SubsequenceType,
+        window_size: usize,
+        k: usize,
+    ) -> Self {
+        info!("Computing ({window_size}, {k})-minimizer chaining anchors...");
+        Self::from_anchors(minimizer_anchors(
+            &reference.as_string(),
+            &query.as_string(),
+            window_size,
+            k,
+        ))
+    }
+
+    /// Compute a set of anchors for the given reference and query sequences with a spaced seed.
+    ///
+    /// Like [`ChainingAnchors::seed_nonoverlapping`], but the blocks have the length of the pattern,
+    /// and only need to match at the positions where the pattern is `true`.
+    /// The pattern is repeated for the last block if it is longer.
+    ///
+    /// If the reference is shorter than the pattern, then there are no anchors.
+    pub fn seed_spaced<
+        AlphabetType: Alphabet,
+        SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized,
+    >(
+        reference: &SubsequenceType,
+        query: &SubsequenceType,
+        pattern: &[bool],
+    ) -> Self {
+        info!(
+            "Computing spaced seed chaining anchors with pattern {}...",
+            pattern
+                .iter()
+                .map(|&care| if care { '1' } else { '0' })
+                .collect::<String>()
         );
-        assert!(block_size > 0, "Block size must be positive, but is zero");
+        Self::from_anchors(spaced_seed_anchors(
+            &reference.as_string(),
+            &query.as_string(),
+            pattern,
+        ))
+    }
 
-        // Read into strings so we can use stdlib string matching and don't need to implement our own.
-        let reference = reference.as_string();
-        let query = query.as_string();
+    /// Additionally compute the reverse complement anchors of the given reference and query sequences.
+    ///
+    /// For each combination of primary and secondary sequence, the primary sequence is seeded against the reverse complement
+    /// of the secondary sequence with the given seeding method, where the primary takes the role of the reference.
+    /// This includes matches of a sequence against its own reverse complement.
+    pub fn with_reverse_complement_anchors<
+        AlphabetType: Alphabet,
+        SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized,
+    >(
+        mut self,
+        reference: &SubsequenceType,
+        query: &SubsequenceType,
+        seeding_method: &SeedingMethod,
+    ) -> Self {
+        info!("Computing reverse complement anchors with {seeding_method:?}...");
+        let sequence = |anchor_sequence| match anchor_sequence {
+            AnchorSequence::Reference => reference,
+            AnchorSequence::Query => query,
+        };
+
+        self.reverse_complement_anchors.clear();
+        for primary in [AnchorSequence::Reference, AnchorSequence::Query] {
+            for secondary in [AnchorSequence::Reference, AnchorSequence::Query] {
+                let primary_sequence = sequence(primary).as_string();
+                let secondary_sequence = sequence(secondary);
+                let secondary_length = secondary_sequence.len();
+                let secondary_reverse_complement: String = secondary_sequence
+                    .reverse_complement_iter()
+                    .map(Into::<char>::into)
+                    .collect();
 
-        let reference_block_ranges: Vec<_> =
-            nonoverlapping_block_ranges(reference.len(), block_size).collect();
-        let reference_blocks: Vec<_> = reference_block_ranges
+                self.reverse_complement_anchors.extend(
+                    seed_strings(
+                        &primary_sequence,
+                        &secondary_reverse_complement,
+                        seeding_method,
+                    )
+                    .into_iter()
+                    .map(|anchor| ReverseComplementAnchor {
+                        primary,
+                        secondary,
+                        primary_block: anchor.reference_block,
+                        secondary_block: secondary_length - anchor.query_block.end
+                            ..secondary_length - anchor.query_block.start,
+                    }),
+                );
+            }
+        }
+
+        self.reverse_complement_anchors.sort();
+        self.max_reverse_complement_block_length = self
+            .reverse_complement_anchors
             .iter()
-            .cloned()
-            .map(|block_range| &reference[block_range])
-            .collect();
-        let mut anchors: Vec<_> = find_all_substrings(&query, &reference_blocks)
-            .map(
-                |SubstringMatch {
-                     haystack_offset,
-                     needle_index,
-                 }| {
-                    let reference_block = reference_block_ranges[needle_index].clone();
-                    let block_size = reference_block.len();
-                    let query_block = haystack_offset..haystack_offset + block_size;
-
-                    ChainingAnchor {
-                        reference_block,
-                        query_block,
-                    }
-                },
-            )
-            .collect();
-        anchors.sort_unstable();
+            .map(|anchor| anchor.primary_block.len())
+            .max()
+            .unwrap_or(0);
 
-        ChainingAnchors { anchors }
+        self
     }
 
     pub fn anchors(&self) -> &[ChainingAnchor] {
         &self.anchors
     }
+
+    /// Returns all reverse complement anchors, ordered by primary, secondary, primary block and secondary block.
+    ///
+    /// These are empty unless computed with [`ChainingAnchors::with_reverse_complement_anchors`].
+    pub fn reverse_complement_anchors(&self) -> &[ReverseComplementAnchor] {
+        &self.reverse_complement_anchors
+    }
+
+    /// Returns the reverse complement anchors with the given primary and secondary sequence, ordered by primary block.
+    pub fn reverse_complement_anchors_of(
+        &self,
+        primary: AnchorSequence,
+        secondary: AnchorSequence,
+    ) -> &[ReverseComplementAnchor] {
+        let start = self
+            .reverse_complement_anchors
+            .partition_point(|anchor| (anchor.primary, anchor.secondary) < (primary, secondary));
+        let end = self
+            .reverse_complement_anchors
+            .partition_point(|anchor| (anchor.primary, anchor.secondary) <= (primary, secondary));
+        &self.reverse_complement_anchors[start..end]
+    }
+
+    /// Returns the secondary indices at which a template switch entering the secondary at `primary_index`
+    /// continues along a reverse complement anchor.
+    ///
+    /// A secondary index `s` means that the primary character at `primary_index` matches the complement
+    /// of the secondary character at `s - 1`, like in the inner alignment of a template switch.
+    /// Subtracting the secondary index of the template switch entrance yields the corresponding template switch offset.
+    pub fn template_switch_secondary_indices(
+        &self,
+        primary: AnchorSequence,
+        secondary: AnchorSequence,
+        primary_index: usize,
+    ) -> impl Iterator<Item = usize> {
+        let anchors = self.reverse_complement_anchors_of(primary, secondary);
+        let min_start =
+            (primary_index + 1).saturating_sub(self.max_reverse_complement_block_length);
+        let start = anchors.partition_point(|anchor| anchor.primary_block.start < min_start);
+        let end = anchors.partition_point(|anchor| anchor.primary_block.start <= primary_index);
+
+        anchors[start..end]
+            .iter()
+            .filter(move |anchor| anchor.primary_block.contains(&primary_index))
+            .map(move |anchor| {
+                anchor.secondary_block.end - (primary_index - anchor.primary_block.start)
+            })
+    }
 }
 
 impl ChainingAnchor {
@@ -90,6 +307,180 @@ impl ChainingAnchor {
     pub fn query_block(&self) -> &Range<usize> {
         &self.query_block
     }
+
+    /// Returns this anchor trimmed such that it can be chained after `predecessor`,
+    /// or `None` if it cannot be chained after `predecessor`.
+    ///
+    /// An anchor can be chained after another if it starts at least as late and ends strictly later in both sequences.
+    /// If the anchors overlap, then the start of this anchor is trimmed by the same amount in both sequences,
+    /// such that it stays on its diagonal.
+    pub fn trimmed_after(&self, predecessor: &ChainingAnchor) -> Option<Self> {
+        if self.reference_block.start < predecessor.reference_block.start
+            || self.query_block.start < predecessor.query_block.start
+            || self.reference_block.end <= predecessor.reference_block.end
+            || self.query_block.end <= predecessor.query_block.end
+        {
+            return None;
+        }
+
+        let overlap = predecessor
+            .reference_block
+            .end
+            .saturating_sub(self.reference_block.start)
+            .max(
+                predecessor
+                    .query_block
+                    .end
+                    .saturating_sub(self.query_block.start),
+            );
+        let reference_start = self.reference_block.start + overlap;
+        let query_start = self.query_block.start + overlap;
+
+        (reference_start < self.reference_block.end && query_start < self.query_block.end)
+            .then_some(Self {
+                reference_block: reference_start..self.reference_block.end,
+                query_block: query_start..self.query_block.end,
+            })
+    }
+}
+
+impl ReverseComplementAnchor {
+    pub fn new(
+        primary: AnchorSequence,
+        secondary: AnchorSequence,
+        primary_block: Range<usize>,
+        secondary_block: Range<usize>,
+    ) -> Self {
+        debug_assert_eq!(primary_block.len(), secondary_block.len());
+
+        Self {
+            primary,
+            secondary,
+            primary_block,
+            secondary_block,
+        }
+    }
+
+    pub fn primary(&self) -> AnchorSequence {
+        self.primary
+    }
+
+    pub fn secondary(&self) -> AnchorSequence {
+        self.secondary
+    }
+
+    pub fn primary_block(&self) -> &Range<usize> {
+        &self.primary_block
+    }
+
+    pub fn secondary_block(&self) -> &Range<usize> {
+        &self.secondary_block
+    }
+}
+
+fn seed_strings(
+    reference: &str,
+    query: &str,
+    seeding_method: &SeedingMethod,
+) -> Vec<ChainingAnchor> {
+    match seeding_method {
+        SeedingMethod::NonOverlapping { block_size } => {
+            nonoverlapping_anchors(reference, query, *block_size)
+        }
+        SeedingMethod::Minimizers { window_size, k } => {
+            minimizer_anchors(reference, query, *window_size, *k)
+        }
+        SeedingMethod::SpacedSeed { pattern } => spaced_seed_anchors(reference, query, pattern),
+    }
+}
+
+fn nonoverlapping_anchors(reference: &str, query: &str, block_size: usize) -> Vec<ChainingAnchor> {
+    assert!(block_size > 0, "Block size must be positive, but is zero");
+    if reference.len() < block_size {
+        warn!(
+            "Reference (length: {}) is shorter than the block size {block_size}, using no anchors",
+            reference.len(),
+        );
+        return Vec::new();
+    }
+
+    let query_index = KmerIndex::new(query, block_size);
+
+    nonoverlapping_block_ranges(reference.len(), block_size)
+        .flat_map(|reference_block| {
+            let block = &reference[reference_block.clone()];
+            let block_size = reference_block.len();
+
+            query_index
+                .find_all(block)
+                .map(move |query_offset| ChainingAnchor {
+                    reference_block: reference_block.clone(),
+                    query_block: query_offset..query_offset + block_size,
+                })
+        })
+        .collect()
+}
+
+fn minimizer_anchors(
+    reference: &str,
+    query: &str,
+    window_size: usize,
+    k: usize,
+) -> Vec<ChainingAnchor> {
+    assert!(window_size > 0, "Window size must be positive, but is zero");
+    assert!(k > 0, "k must be positive, but is zero");
+
+    let mut query_minimizers: HashMap<_, Vec<_>> = HashMap::new();
+    for query_offset in minimizers(query, window_size, k) {
+        query_minimizers
+            .entry(&query[query_offset..query_offset + k])
+            .or_default()
+            .push(query_offset);
+    }
+
+    minimizers(reference, window_size, k)
+        .flat_map(|reference_offset| {
+            query_minimizers
+                .get(&reference[reference_offset..reference_offset + k])
+                .into_iter()
+                .flatten()
+                .map(move |&query_offset| ChainingAnchor {
+                    reference_block: reference_offset..reference_offset + k,
+                    query_block: query_offset..query_offset + k,
+                })
+        })
+        .collect()
+}
+
+fn spaced_seed_anchors(reference: &str, query: &str, pattern: &[bool]) -> Vec<ChainingAnchor> {
+    assert!(
+        pattern.iter().any(|&care| care),
+        "Spaced seed pattern must contain at least one position that needs to match"
+    );
+    if reference.len() < pattern.len() {
+        warn!(
+            "Reference (length: {}) is shorter than the spaced seed (length: {}), using no anchors",
+            reference.len(),
+            pattern.len(),
+        );
+        return Vec::new();
+    }
+
+    let query_index = SpacedSeedIndex::new(query, pattern);
+
+    nonoverlapping_block_ranges(reference.len(), pattern.len())
+        .flat_map(|reference_block| {
+            let block = &reference[reference_block.clone()];
+            let block_size = reference_block.len();
+
+            query_index
+                .find_all(block)
+                .map(move |query_offset| ChainingAnchor {
+                    reference_block: reference_block.clone(),
+                    query_block: query_offset..query_offset + block_size,
+                })
+        })
+        .collect()
 }
 
 fn nonoverlapping_block_ranges(
@@ -106,12 +497,162 @@ fn nonoverlapping_block_ranges(
         ))
 }
 
+/// An index of all k-mers of a sequence.
+struct KmerIndex<'sequence> {
+    sequence: &'sequence str,
+    k: usize,
+    kmers: HashMap<&'sequence str, Vec<usize>>,
+}
+
+impl<'sequence> KmerIndex<'sequence> {
+    fn new(sequence: &'sequence str, k: usize) -> Self {
+        debug_assert!(k > 0);
+
+        let mut kmers: HashMap<_, Vec<_>> = HashMap::new();
+        for offset in 0..(sequence.len() + 1).saturating_sub(k) {
+            kmers
+                .entry(&sequence[offset..offset + k])
+                .or_default()
+                .push(offset);
+        }
+
+        Self { sequence, k, kmers }
+    }
+
+    /// Returns the offsets of all occurrences of `needle` in ascending order.
+    ///
+    /// The needle must be at least `k` characters long.
+    /// Its first `k` characters are looked up in the index, and the remaining characters are compared directly.
+    fn find_all<'needle>(
+        &self,
+        needle: &'needle str,
+    ) -> impl use<'_, 'sequence, 'needle> + Iterator<Item = usize> {
+        debug_assert!(needle.len() >= self.k);
+
+        self.kmers
+            .get(&needle[..self.k])
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(move |&offset| {
+                self.sequence
+                    .get(offset + self.k..offset + needle.len())
+                    .is_some_and(|suffix| suffix == &needle[self.k..])
+            })
+    }
+}
+
+/// An index of the spaced seeds of all positions of a sequence.
+struct SpacedSeedIndex<'sequence, 'pattern> {
+    sequence: &'sequence str,
+    pattern: &'pattern [bool],
+    seeds: HashMap<String, Vec<usize>>,
+}
+
+impl<'sequence, 'pattern> SpacedSeedIndex<'sequence, 'pattern> {
+    fn new(sequence: &'sequence str, pattern: &'pattern [bool]) -> Self {
+        let mut seeds: HashMap<_, Vec<_>> = HashMap::new();
+        for offset in 0..(sequence.len() + 1).saturating_sub(pattern.len()) {
+            seeds
+                .entry(spaced_seed(
+                    &sequence[offset..offset + pattern.len()],
+                    pattern,
+                ))
+                .or_default()
+                .push(offset);
+        }
+
+        Self {
+            sequence,
+            pattern,
+            seeds,
+        }
+    }
+
+    /// Returns the offsets of all matches of `needle` in ascending order.
+    ///
+    /// The needle must be at least as long as the pattern, and the pattern is repeated for longer needles.
+    fn find_all<'needle>(
+        &self,
+        needle: &'needle str,
+    ) -> impl use<'_, 'sequence, 'pattern, 'needle> + Iterator<Item = usize> {
+        debug_assert!(needle.len() >= self.pattern.len());
+
+        self.seeds
+            .get(&spaced_seed(&needle[..self.pattern.len()], self.pattern))
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(move |&offset| {
+                self.sequence
+                    .get(offset..offset + needle.len())
+                    .is_some_and(|candidate| {
+                        candidate
+                            .bytes()
+                            .zip(needle.bytes())
+                            .zip(self.pattern.iter().cycle())
+                            .all(|((a, b), &care)| !care || a == b)
+                    })
+            })
+    }
+}
+
+/// Returns the characters of `kmer` at the positions where `pattern` is `true`.
+fn spaced_seed(kmer: &str, pattern: &[bool]) -> String {
+    debug_assert_eq!(kmer.len(), pattern.len());
+
+    kmer.chars()
+        .zip(pattern)
+        .filter_map(|(c, &care)| care.then_some(c))
+        .collect()
+}
+
+/// Returns the offsets of the `(window_size, k)`-minimizers of `sequence` in ascending order.
+///
+/// If the sequence has fewer than `window_size` k-mers, then it is treated as a single window.
+fn minimizers(sequence: &str, window_size: usize, k: usize) -> impl Iterator<Item = usize> {
+    let hashes: Vec<_> = (0..(sequence.len() + 1).saturating_sub(k))
+        .map(|offset| {
+            let mut hasher = DefaultHasher::new();
+            sequence[offset..offset + k].hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect();
+
+    let mut minimizers = Vec::new();
+    if !hashes.is_empty() {
+        // The leftmost minimum is chosen, such that equal windows have equal minimizers.
+        let mut minimum: Option<usize> = None;
+        for window_start in 0..=hashes.len().saturating_sub(window_size) {
+            let window = window_start..(window_start + window_size).min(hashes.len());
+
+            minimum = match minimum {
+                Some(minimum)
+                    if minimum >= window.start && hashes[window.end - 1] >= hashes[minimum] =>
+                {
+                    Some(minimum)
+                }
+                _ => window.clone().min_by_key(|&offset| hashes[offset]),
+            };
+
+            if minimizers.last() != minimum.as_ref() {
+                minimizers.extend(minimum);
+            }
+        }
+    }
+
+    minimizers.into_iter()
+}
+
+#[cfg(test)]
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 struct SubstringMatch {
     haystack_offset: usize,
     needle_index: usize,
 }
 
+/// A slow but simple reference implementation of searching with a [`KmerIndex`].
+#[cfg(test)]
 fn find_all_substrings<'haystack, 'needles>(
     haystack: &'haystack str,
     needles: &'needles [&str],
@@ -175,6 +716,24 @@ impl PartialOrd for ChainingAnchor {
     }
 }
 
+impl Ord for ReverseComplementAnchor {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.primary
+            .cmp(&other.primary)
+            .then_with(|| self.secondary.cmp(&other.secondary))
+            .then_with(|| self.primary_block.start.cmp(&other.primary_block.start))
+            .then_with(|| self.primary_block.end.cmp(&other.primary_block.end))
+            .then_with(|| self.secondary_block.start.cmp(&other.secondary_block.start))
+            .then_with(|| self.secondary_block.end.cmp(&other.secondary_block.end))
+    }
+}
+
+impl PartialOrd for ReverseComplementAnchor {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use compact_genome::{
@@ -183,9 +742,11 @@ mod tests {
     };
 
     use super::{
-        ChainingAnchor, ChainingAnchors, SubstringMatch, find_all_substrings,
+        AnchorSequence, ChainingAnchor, ChainingAnchors, KmerIndex, ReverseComplementAnchor,
+        SeedingMethod, SubstringMatch, find_all_substrings, minimizers,
         nonoverlapping_block_ranges,
     };
+    use crate::test_util::{REPETITIVE_DNA, pseudo_random_sequence, reverse_complement};
 
     #[test]
     fn test_nonoverlapping_block_ranges() {
@@ -245,4 +806,379 @@ mod tests {
 
         assert_eq!(&expected, actual.as_slice());
     }
+
+    #[test]
+    fn test_kmer_index_matches_find_all_substrings() {
+        let haystack = "AAAACATAAACATAAAAC";
+        let needles = [
+            "AAA", "ATA", "CAT", "ACA", "AAAC", "CATAAAAC", "AAACC", "GGG",
+        ];
+        let mut expected: Vec<_> = find_all_substrings(haystack, &needles).collect();
+        expected.sort();
+
+        let index = KmerIndex::new(haystack, 3);
+        let mut actual: Vec<_> = needles
+            .iter()
+            .enumerate()
+            .flat_map(|(needle_index, needle)| {
+                index
+                    .find_all(needle)
+                    .map(move |haystack_offset| SubstringMatch {
+                        haystack_offset,
+                        needle_index,
+                    })
+            })
+            .collect();
+        actual.sort();
+
+        assert_eq!(expected, actual);
+        assert_eq!(KmerIndex::new("AA", 3).find_all("AAA").count(), 0);
+    }
+
+    #[test]
+    fn test_seed_nonoverlapping_matches_find_all_substrings() {
+        let sequence = |seed, length| pseudo_random_sequence(seed, length, REPETITIVE_DNA);
+
+        for (reference, query, block_size) in [
+            (sequence(1, 200), sequence(2, 300), 3),
+            (sequence(3, 101), sequence(3, 101), 4),
+            (sequence(4, 50), sequence(5, 20), 7),
+            (sequence(6, 10), sequence(7, 5), 10),
+        ] {
+            let reference_block_ranges: Vec<_> =
+                nonoverlapping_block_ranges(reference.len(), block_size).collect();
+            let reference_blocks: Vec<_> = reference_block_ranges
+                .iter()
+                .map(|block_range| &reference[block_range.clone()])
+                .collect();
+            let mut expected: Vec<_> = find_all_substrings(&query, &reference_blocks)
+                .map(
+                    |SubstringMatch {
+                         haystack_offset,
+                         needle_index,
+                     }| {
+                        let reference_block = reference_block_ranges[needle_index].clone();
+                        let query_block = haystack_offset..haystack_offset + reference_block.len();
+                        ChainingAnchor::new(reference_block, query_block)
+                    },
+                )
+                .collect();
+            expected.sort();
+
+            let reference =
+                VectorGenome::<DnaAlphabet>::from_slice_u8(reference.as_bytes()).unwrap();
+            let query = VectorGenome::<DnaAlphabet>::from_slice_u8(query.as_bytes()).unwrap();
+            let actual = ChainingAnchors::seed_nonoverlapping(
+                reference.as_genome_subsequence(),
+                query.as_genome_subsequence(),
+                block_size,
+            )
+            .anchors;
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_seed_short_reference() {
+        let reference = VectorGenome::<DnaAlphabet>::from_slice_u8(b"ACT").unwrap();
+        let query = VectorGenome::<DnaAlphabet>::from_slice_u8(b"TACTGG").unwrap();
+
+        for seeding_method in [
+            SeedingMethod::NonOverlapping { block_size: 4 },
+            SeedingMethod::Minimizers {
+                window_size: 2,
+                k: 4,
+            },
+            SeedingMethod::SpacedSeed {
+                pattern: vec![true, false, true, true],
+            },
+        ] {
+            let anchors = ChainingAnchors::seed(
+                reference.as_genome_subsequence(),
+                query.as_genome_subsequence(),
+                &seeding_method,
+            );
+            assert!(anchors.anchors().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_minimizers() {
+        for (window_size, k) in [(1, 3), (4, 3), (10, 5), (30, 2)] {
+            let sequence = pseudo_random_sequence(8, 100, REPETITIVE_DNA);
+            let hash = |offset: usize| {
+                use std::hash::{DefaultHasher, Hash, Hasher};
+                let mut hasher = DefaultHasher::new();
+                sequence[offset..offset + k].hash(&mut hasher);
+                hasher.finish()
+            };
+
+            let kmer_amount = sequence.len() - k + 1;
+            let mut expected: Vec<_> = (0..=kmer_amount.saturating_sub(window_size))
+                .map(|window_start| {
+                    (window_start..(window_start + window_size).min(kmer_amount))
+                        .min_by_key(|&offset| hash(offset))
+                        .unwrap()
+                })
+                .collect();
+            expected.dedup();
+
+            assert_eq!(
+                expected,
+                minimizers(&sequence, window_size, k).collect::<Vec<_>>()
+            );
+        }
+
+        assert_eq!(minimizers("ACG", 2, 4).count(), 0);
+    }
+
+    #[test]
+    fn test_seed_minimizers() {
+        // Each exact match of length at least `window_size + k - 1` contains an anchor.
+        let (window_size, k) = (5, 4);
+        let shared = pseudo_random_sequence(9, window_size + k - 1, REPETITIVE_DNA);
+        let reference = format!(
+            "{}{shared}{}",
+            pseudo_random_sequence(10, 30, REPETITIVE_DNA),
+            "T".repeat(10)
+        );
+        let query = format!(
+            "{}{shared}{}",
+            "T".repeat(15),
+            pseudo_random_sequence(11, 20, REPETITIVE_DNA)
+        );
+        let reference_offset = 30;
+        let query_offset = 15;
+
+        let anchors = ChainingAnchors::seed_minimizers(
+            VectorGenome::<DnaAlphabet>::from_slice_u8(reference.as_bytes())
+                .unwrap()
+                .as_genome_subsequence(),
+            VectorGenome::<DnaAlphabet>::from_slice_u8(query.as_bytes())
+                .unwrap()
+                .as_genome_subsequence(),
+            window_size,
+            k,
+        )
+        .anchors;
+
+        assert!(anchors.is_sorted());
+        assert!(anchors.iter().all(|anchor| {
+            reference[anchor.reference_block().clone()] == query[anchor.query_block().clone()]
+        }));
+        assert!(anchors.iter().any(|anchor| {
+            anchor.reference_block().start >= reference_offset
+                && anchor.reference_block().end <= reference_offset + shared.len()
+                && anchor.query_block().start - query_offset
+                    == anchor.reference_block().start - reference_offset
+        }));
+    }
+
+    #[test]
+    fn test_seed_spaced() {
+        let reference = VectorGenome::<DnaAlphabet>::from_slice_u8(b"ACTTGGAAAA").unwrap();
+        // Like in `test_seed_nonoverlapping`, but with a substitution at a "don't care" position.
+        let query = VectorGenome::<DnaAlphabet>::from_slice_u8(b"TACTCGAAAAACT").unwrap();
+        let pattern = [true, false, true];
+
+        let mut expected = [
+            (0..3, 1..4),
+            (0..3, 10..13),
+            (3..6, 3..6),
+            (6..10, 6..10),
+            (6..10, 7..11),
+        ]
+        .map(|(reference_block, query_block)| ChainingAnchor {
+            reference_block,
+            query_block,
+        });
+        expected.sort();
+        let actual = ChainingAnchors::seed_spaced(
+            reference.as_genome_subsequence(),
+            query.as_genome_subsequence(),
+            &pattern,
+        )
+        .anchors;
+
+        assert_eq!(&expected, actual.as_slice());
+
+        // Without "don't care" positions, spaced seeds are equal to non-overlapping blocks.
+        let reference = pseudo_random_sequence(12, 200, REPETITIVE_DNA);
+        let query = pseudo_random_sequence(13, 300, REPETITIVE_DNA);
+        let reference = VectorGenome::<DnaAlphabet>::from_slice_u8(reference.as_bytes()).unwrap();
+        let query = VectorGenome::<DnaAlphabet>::from_slice_u8(query.as_bytes()).unwrap();
+        assert_eq!(
+            ChainingAnchors::seed_spaced(
+                reference.as_genome_subsequence(),
+                query.as_genome_subsequence(),
+                &[true; 4],
+            )
+            .anchors,
+            ChainingAnchors::seed_nonoverlapping(
+                reference.as_genome_subsequence(),
+                query.as_genome_subsequence(),
+                4,
+            )
+            .anchors,
+        );
+    }
+
+    #[test]
+    fn test_reverse_complement_anchors() {
+        let reference = VectorGenome::<DnaAlphabet>::from_slice_u8(b"GATTACA").unwrap();
+        let query = VectorGenome::<DnaAlphabet>::from_slice_u8(b"TGTAATCCC").unwrap();
+        let anchors = ChainingAnchors::seed_nonoverlapping(
+            reference.as_genome_subsequence(),
+            query.as_genome_subsequence(),
+            3,
+        )
+        .with_reverse_complement_anchors(
+            reference.as_genome_subsequence(),
+            query.as_genome_subsequence(),
+            &SeedingMethod::NonOverlapping { block_size: 3 },
+        );
+
+        assert_eq!(
+            anchors.reverse_complement_anchors_of(AnchorSequence::Query, AnchorSequence::Reference),
+            [(0..3, 4..7), (3..6, 1..4)].map(|(primary_block, secondary_block)| {
+                ReverseComplementAnchor::new(
+                    AnchorSequence::Query,
+                    AnchorSequence::Reference,
+                    primary_block,
+                    secondary_block,
+                )
+            })
+        );
+        assert_eq!(
+            anchors
+                .template_switch_secondary_indices(
+                    AnchorSequence::Query,
+                    AnchorSequence::Reference,
+                    0
+                )
+                .collect::<Vec<_>>(),
+            vec![7]
+        );
+        assert_eq!(
+            anchors
+                .template_switch_secondary_indices(
+                    AnchorSequence::Query,
+                    AnchorSequence::Reference,
+                    4
+                )
+                .collect::<Vec<_>>(),
+            vec![3]
+        );
+        assert_eq!(
+            anchors
+                .template_switch_secondary_indices(
+                    AnchorSequence::Query,
+                    AnchorSequence::Reference,
+                    7
+                )
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_reverse_complement_anchors_match() {
+        // The pseudo-random sequences contain no `T`, so copy some reverse complements explicitly.
+        let reference = format!(
+            "{}{}",
+            pseudo_random_sequence(14, 100, REPETITIVE_DNA),
+            reverse_complement(&pseudo_random_sequence(16, 50, REPETITIVE_DNA))
+        );
+        let query = format!(
+            "{}{}",
+            reverse_complement(&reference[10..90]),
+            pseudo_random_sequence(15, 40, REPETITIVE_DNA)
+        );
+        let sequence = |anchor_sequence| match anchor_sequence {
+            AnchorSequence::Reference => &reference,
+            AnchorSequence::Query => &query,
+        };
+        let block_size = 4;
+
+        let anchors = ChainingAnchors::empty().with_reverse_complement_anchors(
+            VectorGenome::<DnaAlphabet>::from_slice_u8(reference.as_bytes())
+                .unwrap()
+                .as_genome_subsequence(),
+            VectorGenome::<DnaAlphabet>::from_slice_u8(query.as_bytes())
+                .unwrap()
+                .as_genome_subsequence(),
+            &SeedingMethod::NonOverlapping { block_size },
+        );
+        assert!(anchors.reverse_complement_anchors().is_sorted());
+
+        for primary in [AnchorSequence::Reference, AnchorSequence::Query] {
+            for secondary in [AnchorSequence::Reference, AnchorSequence::Query] {
+                let primary_sequence = sequence(primary);
+                let secondary_sequence = sequence(secondary);
+
+                let mut expected = Vec::new();
+                for primary_block in nonoverlapping_block_ranges(primary_sequence.len(), block_size)
+                {
+                    let block = &primary_sequence[primary_block.clone()];
+                    for secondary_offset in 0..=secondary_sequence.len() - block.len() {
+                        let secondary_block = secondary_offset..secondary_offset + block.len();
+                        if reverse_complement(&secondary_sequence[secondary_block.clone()]) == block
+                        {
+                            expected.push(ReverseComplementAnchor::new(
+                                primary,
+                                secondary,
+                                primary_block.clone(),
+                                secondary_block,
+                            ));
+                        }
+                    }
+                }
+                assert!(!expected.is_empty() || primary == secondary);
+
+                assert_eq!(
+                    anchors.reverse_complement_anchors_of(primary, secondary),
+                    expected.as_slice()
+                );
+
+                for primary_index in 0..primary_sequence.len() {
+                    let mut expected: Vec<_> = expected
+                        .iter()
+                        .filter(|anchor| anchor.primary_block().contains(&primary_index))
+                        .map(|anchor| {
+                            anchor.secondary_block().end
+                                - (primary_index - anchor.primary_block().start)
+                        })
+                        .collect();
+                    expected.sort();
+                    let mut actual: Vec<_> = anchors
+                        .template_switch_secondary_indices(primary, secondary, primary_index)
+                        .collect();
+                    actual.sort();
+                    assert_eq!(expected, actual);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_trimmed_after() {
+        let anchor = ChainingAnchor::new(0..6, 0..6);
+
+        assert_eq!(
+            ChainingAnchor::new(6..10, 8..12).trimmed_after(&anchor),
+            Some(ChainingAnchor::new(6..10, 8..12))
+        );
+        assert_eq!(
+            ChainingAnchor::new(2..8, 2..8).trimmed_after(&anchor),
+            Some(ChainingAnchor::new(6..8, 6..8))
+        );
+        assert_eq!(
+            ChainingAnchor::new(4..10, 2..8).trimmed_after(&anchor),
+            Some(ChainingAnchor::new(8..10, 6..8))
+        );
+        assert_eq!(ChainingAnchor::new(4..7, 1..4).trimmed_after(&anchor), None);
+        assert_eq!(ChainingAnchor::new(0..6, 0..6).trimmed_after(&anchor), None);
+        assert_eq!(ChainingAnchor::new(1..7, 0..6).trimmed_after(&anchor), None);
+    }
 }
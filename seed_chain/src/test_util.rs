@@ -0,0 +1,35 @@
+//! Helpers shared by the tests of this crate.
+
+/// The four DNA characters, each drawn with the same probability.
+pub const DNA: &[u8; 4] = b"ACGT";
+
+/// An alphabet that yields DNA with many repeats and without `T`.
+pub const REPETITIVE_DNA: &[u8; 4] = b"AACG";
+
+/// Deterministic pseudo-random sequence over the given four characters.
+pub fn pseudo_random_sequence(seed: u64, length: usize, alphabet: &[u8; 4]) -> String {
+    let mut state = seed;
+    (0..length)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            alphabet[(state >> 62) as usize] as char
+        })
+        .collect()
+}
+
+/// The reverse complement of a DNA sequence.
+pub fn reverse_complement(sequence: &str) -> String {
+    sequence
+        .chars()
+        .rev()
+        .map(|c| match c {
+            'A' => 'T',
+            'C' => 'G',
+            'G' => 'C',
+            'T' => 'A',
+            c => panic!("unexpected character {c:?}"),
+        })
+        .collect()
+}
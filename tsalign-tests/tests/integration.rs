@@ -22,3 +22,170 @@ fn test_align_with_cost_limit() -> Result<()> {
 fn test_align_with_memory_limit() -> Result<()> {
     run_in_repo_root("align -p test_files/twin_100_0.01.fa --memory-limit 1000")
 }
+
+#[test]
+fn test_align_with_epsilon() -> Result<()> {
+    run_in_repo_root("align -p test_files/twin_100_0.01.fa --epsilon 2")
+}
+
+#[test]
+fn test_align_top_k() -> Result<()> {
+    run_in_repo_root("align -p test_files/twin_100_0.01.fa --top-k 3")
+}
+
+#[test]
+fn test_align_with_time_limit() -> Result<()> {
+    run_in_repo_root("align -p test_files/twin_100_0.01.fa --time-limit 0")
+}
+
+#[test]
+fn test_align_with_initial_cost_limit() -> Result<()> {
+    run_in_repo_root("align -p test_files/twin_100_0.01.fa --initial-cost-limit 1")
+}
+
+#[test]
+fn test_align_with_compact_closed_list() -> Result<()> {
+    run_in_repo_root("align -p test_files/twin_100_0.01.fa --compact-closed-list")
+}
+
+#[test]
+fn test_align_decimal_costs_and_show() -> Result<()> {
+    let output = std::env::temp_dir().join(format!("tsalign-decimal-{}.toml", std::process::id()));
+    let output = output.to_str().unwrap();
+
+    run_in_repo_root(&format!(
+        "align -p test_files/twin_show_ts.fa -c test_files/config/decimal --cost-type f64 --cost-limit 100.5 -o {output}"
+    ))?;
+    run_in_repo_root(&format!("show -i {output}"))?;
+
+    std::fs::remove_file(output)?;
+    Ok(())
+}
+
+#[test]
+fn test_align_negative_costs_and_show() -> Result<()> {
+    let output = std::env::temp_dir().join(format!("tsalign-negative-{}.toml", std::process::id()));
+    let output = output.to_str().unwrap();
+
+    run_in_repo_root(&format!(
+        "align -p test_files/twin_in_place_inversion.fa -c test_files/config/negative --cost-type i64 -o {output}"
+    ))?;
+    run_in_repo_root(&format!("show -i {output}"))?;
+
+    std::fs::remove_file(output)?;
+    Ok(())
+}
+
+#[test]
+fn test_align_local_and_show() -> Result<()> {
+    let output = std::env::temp_dir().join(format!("tsalign-local-{}.toml", std::process::id()));
+    let output = output.to_str().unwrap();
+
+    run_in_repo_root(&format!(
+        "align -p test_files/twin_in_place_inversion.fa -c test_files/config/negative --cost-type i64 --mode local -o {output}"
+    ))?;
+    run_in_repo_root(&format!("show -i {output}"))?;
+
+    std::fs::remove_file(output)?;
+    Ok(())
+}
+
+#[test]
+fn test_align_semi_global() -> Result<()> {
+    run_in_repo_root("align -p test_files/twin_a.fa --mode semi-global")
+}
+
+#[test]
+fn test_align_overlap() -> Result<()> {
+    run_in_repo_root(
+        "align -p test_files/twin_a.fa -c test_files/config/negative --cost-type i64 --mode overlap",
+    )
+}
+
+#[test]
+fn test_align_overlap_gap_affine() -> Result<()> {
+    run_in_repo_root(
+        "align -p test_files/twin_a.fa -c test_files/config/negative --cost-type i64 --mode overlap --alignment-method a-star-gap-affine --alphabet dna",
+    )
+}
+
+#[test]
+#[should_panic]
+fn test_align_overlap_gap_affine_without_negative_costs() {
+    run_in_repo_root(
+        "align -p test_files/twin_a.fa --mode overlap --alignment-method a-star-gap-affine --alphabet dna",
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_align_check_heuristic() -> Result<()> {
+    run_in_repo_root("align -p test_files/twin_a.fa --check-heuristic")
+}
+
+#[test]
+fn test_align_checkpoint_and_resume() -> Result<()> {
+    let checkpoint =
+        std::env::temp_dir().join(format!("tsalign-checkpoint-{}", std::process::id()));
+    let checkpoint = checkpoint.to_str().unwrap();
+
+    run_in_repo_root(&format!(
+        "align -p test_files/twin_100_0.01.fa --checkpoint {checkpoint} --checkpoint-interval 0"
+    ))?;
+    run_in_repo_root(&format!(
+        "align -p test_files/twin_100_0.01.fa --resume {checkpoint}"
+    ))?;
+
+    std::fs::remove_file(checkpoint)?;
+    Ok(())
+}
+
+#[test]
+fn test_align_dump_search_and_show_heatmap() -> Result<()> {
+    let prefix = std::env::temp_dir().join(format!("tsalign-search-dump-{}", std::process::id()));
+    let prefix = prefix.to_str().unwrap();
+
+    run_in_repo_root(&format!(
+        "align -p test_files/twin_show_ts.fa -o {prefix}.toml --dump-search {prefix}.csv"
+    ))?;
+    run_in_repo_root(&format!(
+        "show -i {prefix}.toml --search-dump {prefix}.csv --search-heatmap {prefix}.svg"
+    ))?;
+
+    for extension in ["toml", "csv", "svg"] {
+        std::fs::remove_file(format!("{prefix}.{extension}"))?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_align_partial_and_show() -> Result<()> {
+    let prefix = std::env::temp_dir().join(format!("tsalign-partial-{}", std::process::id()));
+    let prefix = prefix.to_str().unwrap();
+
+    run_in_repo_root(&format!(
+        "align -p test_files/twin_show_ts.fa --cost-limit 0 -o {prefix}.toml"
+    ))?;
+    run_in_repo_root(&format!("show -i {prefix}.toml -s {prefix}.svg"))?;
+
+    for extension in ["toml", "svg"] {
+        std::fs::remove_file(format!("{prefix}.{extension}"))?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_align_forward_template_switch_and_show() -> Result<()> {
+    let prefix = std::env::temp_dir().join(format!("tsalign-forward-{}", std::process::id()));
+    let prefix = prefix.to_str().unwrap();
+
+    run_in_repo_root(&format!(
+        "align -p test_files/twin_tandem_duplication.fa -c test_files/config/forward -o {prefix}.toml"
+    ))?;
+    run_in_repo_root(&format!("show -i {prefix}.toml -s {prefix}.svg"))?;
+
+    for extension in ["toml", "svg"] {
+        std::fs::remove_file(format!("{prefix}.{extension}"))?;
+    }
+    Ok(())
+}
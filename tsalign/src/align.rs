@@ -21,14 +21,18 @@ use compact_genome::{
     io::fasta::read_fasta_file,
 };
 use lib_tsalign::{
-    a_star_aligner::{gap_affine_edit_distance, gap_affine_edit_distance_a_star_align},
+    a_star_aligner::{
+        alignment_geometry::AlignmentMode, gap_affine_edit_distance,
+        gap_affine_edit_distance_a_star_align,
+    },
     alignment_configuration::AlignmentConfiguration,
     alignment_matrix::AlignmentMatrix,
-    costs::U64Cost,
+    costs::{AStarCost, F64Cost, I64Cost, U64Cost},
 };
 use log::{LevelFilter, debug, info};
 use simplelog::{ColorChoice, TermLogger, TerminalMode};
 use template_switch_distance_type_selectors::{
+    CostTypeSelector, TemplateSwitchChainingSeedingSelector,
     TemplateSwitchChainingStrategySelector, TemplateSwitchMinLengthStrategySelector,
     TemplateSwitchNodeOrdStrategySelector, align_a_star_template_switch_distance,
 };
@@ -68,6 +72,26 @@ pub struct Cli {
     #[clap(long, default_value = "a-star-template-switch")]
     alignment_method: AlignmentMethod,
 
+    /// Align the sequences globally, or with free ends.
+    ///
+    /// A local alignment may start and end anywhere, and the characters before and after it are clipped without cost.
+    /// A semi-global alignment aligns the whole query, but may start and end anywhere in the reference.
+    /// An overlap alignment starts at the beginning of the reference or query and ends at the end of the reference or query.
+    /// Clipped characters are shown as soft clips in the CIGAR string.
+    ///
+    /// Since clipping is only avoided if some alignment steps have negative costs, local and overlap mode are rejected without negative costs, see `--cost-type`.
+    #[clap(long, default_value = "global", conflicts_with_all = ["top_k", "threads", "checkpoint", "resume"])]
+    mode: AlignmentModeSelector,
+
+    /// The type of the costs in the template switch and gap-affine configs.
+    ///
+    /// Use `f64` to allow decimal costs, such as negative log probabilities.
+    /// Use `i64` to allow negative costs, such as match bonuses.
+    /// Negative costs are supported only in the primary and flank edit costs, and cannot be combined with chaining or `--epsilon`.
+    /// Aligning with unsigned integer costs is fastest.
+    #[clap(long, default_value = "u64")]
+    cost_type: CostTypeSelector,
+
     #[clap(long, default_value = "anti-diagonal")]
     ts_node_ord_strategy: TemplateSwitchNodeOrdStrategySelector,
 
@@ -77,6 +101,28 @@ pub struct Cli {
     #[clap(long, default_value = "none")]
     ts_chaining_strategy: TemplateSwitchChainingStrategySelector,
 
+    /// How the anchors of the chaining strategy are computed.
+    #[clap(long, default_value = "non-overlapping")]
+    ts_chaining_seeding: TemplateSwitchChainingSeedingSelector,
+
+    /// The block size for `--ts-chaining-seeding non-overlapping`.
+    #[clap(long, default_value = "20")]
+    ts_chaining_block_size: usize,
+
+    /// The window size in k-mers for `--ts-chaining-seeding minimizers`.
+    #[clap(long, default_value = "10")]
+    ts_chaining_minimizer_window_size: usize,
+
+    /// The k-mer size for `--ts-chaining-seeding minimizers`.
+    #[clap(long, default_value = "12")]
+    ts_chaining_minimizer_k: usize,
+
+    /// The pattern for `--ts-chaining-seeding spaced-seed`.
+    ///
+    /// A `1` marks a position that must match, and a `0` marks a position that is ignored.
+    #[clap(long, default_value = "11011011011011011011")]
+    ts_chaining_spaced_seed: String,
+
     /// If set, template switches are not allowed.
     ///
     /// Use this to compare a template switch alignment against an alignment with out template switches.
@@ -86,8 +132,9 @@ pub struct Cli {
     /// A cost limit for the alignment.
     ///
     /// If there is no alignment with that cost, the aligner will abort without result.
+    /// The cost limit must be of the type given by `--cost-type`.
     #[clap(long)]
-    cost_limit: Option<U64Cost>,
+    cost_limit: Option<String>,
 
     /// An approximate memory limit in bytes for the aligner.
     ///
@@ -95,6 +142,75 @@ pub struct Cli {
     #[clap(long)]
     memory_limit: Option<usize>,
 
+    /// A time limit in seconds for the aligner.
+    ///
+    /// If it is exceeded, then the aligner will abort without result.
+    #[clap(long)]
+    time_limit: Option<f64>,
+
+    /// Inflate the A* lower bounds by this factor to find a first alignment faster.
+    ///
+    /// The cost of the first alignment found is at most this factor times the optimal cost.
+    /// Afterwards, the aligner keeps improving the alignment until it is optimal or the memory limit is reached.
+    #[clap(long)]
+    epsilon: Option<f64>,
+
+    /// Start with this cost limit, and double it until an alignment is found.
+    ///
+    /// The search is continued after raising the cost limit, so no work is repeated.
+    /// The cost limit is not raised above `--cost-limit`.
+    #[clap(long, conflicts_with_all = ["epsilon", "top_k", "checkpoint", "resume"])]
+    initial_cost_limit: Option<String>,
+
+    /// Store only the cost, predecessor and edge type of closed nodes.
+    ///
+    /// This greatly reduces the memory usage of the aligner, allowing to align longer sequences under the same `--memory-limit`.
+    #[clap(long, conflicts_with_all = ["top_k", "checkpoint", "resume"])]
+    compact_closed_list: bool,
+
+    /// Write the closed nodes of the template switch alignment to this file.
+    ///
+    /// If the file name ends with `.csv`, then the columns are separated by commas, otherwise by tabs.
+    /// The closed nodes can be rendered as a heatmap with `tsalign show --search-dump`.
+    #[clap(long, conflicts_with_all = ["top_k", "resume"])]
+    dump_search: Option<PathBuf>,
+
+    /// Check the A* lower bound for admissibility and consistency while aligning, and log a report of all violations.
+    ///
+    /// This is meant for testing new lower bounds, and roughly doubles the memory usage of the aligner.
+    #[clap(long, conflicts_with = "top_k")]
+    check_heuristic: bool,
+
+    /// Output this many cheapest alignments instead of only an optimal one.
+    ///
+    /// The alignments are ordered by ascending cost.
+    /// If an output file is given, then the alignments are stored as an array of tables named `alignments`.
+    #[clap(long, conflicts_with = "epsilon")]
+    top_k: Option<usize>,
+
+    /// The number of threads to align with.
+    ///
+    /// With more than one thread, the nodes of the search are distributed over the threads by their hashes (HDA*).
+    /// Each thread initialises the memory of the alignment strategies separately.
+    #[clap(long, default_value = "1", value_parser = clap::value_parser!(u16).range(1..), conflicts_with_all = ["epsilon", "initial_cost_limit", "compact_closed_list", "dump_search", "check_heuristic", "top_k", "checkpoint", "resume"])]
+    threads: u16,
+
+    /// Regularly write a checkpoint of the alignment to this file.
+    ///
+    /// If the aligner is killed, then the alignment can be continued from the checkpoint with `--resume`.
+    #[clap(long, conflicts_with_all = ["epsilon", "top_k"])]
+    checkpoint: Option<PathBuf>,
+
+    /// The interval in seconds between writing checkpoints.
+    #[clap(long, default_value = "600")]
+    checkpoint_interval: f64,
+
+    /// Continue the alignment from a checkpoint written with `--checkpoint`.
+    ///
+    /// All other arguments must be the same as for the checkpointed alignment, except for the limits.
+    #[clap(long, conflicts_with_all = ["epsilon", "top_k"])]
+    resume: Option<PathBuf>,
+
     /// First character in the reference to start the alignment from.
     ///
     /// Skipped characters are ignored for computing this index.
@@ -166,6 +282,25 @@ enum AlignmentMethod {
     AStarTemplateSwitch,
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq, ValueEnum)]
+enum AlignmentModeSelector {
+    Global,
+    Local,
+    SemiGlobal,
+    Overlap,
+}
+
+impl From<AlignmentModeSelector> for AlignmentMode {
+    fn from(mode: AlignmentModeSelector) -> Self {
+        match mode {
+            AlignmentModeSelector::Global => Self::Global,
+            AlignmentModeSelector::Local => Self::Local,
+            AlignmentModeSelector::SemiGlobal => Self::SemiGlobal,
+            AlignmentModeSelector::Overlap => Self::Overlap,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, ValueEnum)]
 enum InputAlphabet {
     Dna,
@@ -203,7 +338,7 @@ pub fn cli(cli: Cli) -> Result<()> {
     Ok(())
 }
 
-fn execute_with_alphabet<AlphabetType: Alphabet + Debug + Clone + Eq + 'static>(cli: Cli) {
+fn execute_with_alphabet<AlphabetType: Alphabet + Debug + Clone + Eq + Sync + 'static>(cli: Cli) {
     let mut skip_characters = Vec::new();
     for character in cli.skip_characters.bytes().map(usize::from) {
         if skip_characters.len() <= character {
@@ -330,13 +465,35 @@ fn align_a_star_gap_affine_edit_distance<
     cli: Cli,
     reference: &SubsequenceType,
     query: &SubsequenceType,
+) {
+    match cli.cost_type {
+        CostTypeSelector::U64 => {
+            align_a_star_gap_affine_edit_distance_with_cost::<_, _, U64Cost>(cli, reference, query)
+        }
+        CostTypeSelector::I64 => {
+            align_a_star_gap_affine_edit_distance_with_cost::<_, _, I64Cost>(cli, reference, query)
+        }
+        CostTypeSelector::F64 => {
+            align_a_star_gap_affine_edit_distance_with_cost::<_, _, F64Cost>(cli, reference, query)
+        }
+    }
+}
+
+fn align_a_star_gap_affine_edit_distance_with_cost<
+    AlphabetType: Alphabet,
+    SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized,
+    Cost: AStarCost,
+>(
+    cli: Cli,
+    reference: &SubsequenceType,
+    query: &SubsequenceType,
 ) {
     #[derive(serde::Deserialize)]
-    struct GapAffineConfig {
-        match_cost: u64,
-        substitution_cost: u64,
-        gap_open_cost: u64,
-        gap_extend_cost: u64,
+    struct GapAffineConfig<Cost> {
+        match_cost: Cost,
+        substitution_cost: Cost,
+        gap_open_cost: Cost,
+        gap_extend_cost: Cost,
     }
 
     let mut config_path = cli.configuration_directory.clone();
@@ -344,18 +501,20 @@ fn align_a_star_gap_affine_edit_distance<
     let mut config_file = BufReader::new(File::open(config_path).unwrap());
     let mut config = String::new();
     config_file.read_to_string(&mut config).unwrap();
-    let gap_affine_config: GapAffineConfig = toml::from_str(&config).unwrap();
+    let gap_affine_config: GapAffineConfig<Cost> = toml::from_str(&config).unwrap();
 
     let alignment = gap_affine_edit_distance_a_star_align(
         reference,
         query,
-        gap_affine_edit_distance::ScoringTable::<U64Cost> {
-            match_cost: gap_affine_config.match_cost.into(),
-            substitution_cost: gap_affine_config.substitution_cost.into(),
-            gap_open_cost: gap_affine_config.gap_open_cost.into(),
-            gap_extend_cost: gap_affine_config.gap_extend_cost.into(),
+        gap_affine_edit_distance::ScoringTable {
+            match_cost: gap_affine_config.match_cost,
+            substitution_cost: gap_affine_config.substitution_cost,
+            gap_open_cost: gap_affine_config.gap_open_cost,
+            gap_extend_cost: gap_affine_config.gap_extend_cost,
         },
-    );
+        cli.mode.into(),
+    )
+    .unwrap_or_else(|error| panic!("Error aligning:\n{error}"));
 
     if let Some(output) = cli.output {
         use std::io::Write;
@@ -1,39 +1,53 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, time::Duration};
 
 use clap::ValueEnum;
 use compact_genome::interface::{alphabet::Alphabet, sequence::GenomeSequence};
 use lib_tsalign::{
     a_star_aligner::{
+        ClosedListMode, HeuristicChecker,
         alignment_geometry::{AlignmentCoordinates, AlignmentRange},
-        template_switch_distance::strategies::{
-            AlignmentStrategySelection,
-            chaining::{
-                ChainingStrategy, LowerBoundChainingStrategy, NoChainingStrategy,
-                PrecomputeOnlyChainingStrategy,
-            },
-            node_ord::{AntiDiagonalNodeOrdStrategy, CostOnlyNodeOrdStrategy, NodeOrdStrategy},
-            primary_match::AllowPrimaryMatchStrategy,
-            primary_range::NoPrunePrimaryRangeStrategy,
-            secondary_deletion::AllowSecondaryDeletionStrategy,
-            shortcut::NoShortcutStrategy,
-            template_switch_count::{
-                MaxTemplateSwitchCountStrategy, NoTemplateSwitchCountStrategy,
-                TemplateSwitchCountStrategy,
-            },
-            template_switch_min_length::{
-                LookaheadTemplateSwitchMinLengthStrategy, NoTemplateSwitchMinLengthStrategy,
-                TemplateSwitchMinLengthStrategy,
+        alignment_options::AlignmentOptions,
+        template_switch_distance::{
+            search_dump::SearchDumpObserver,
+            strategies::{
+                AlignmentStrategySelection,
+                chaining::{
+                    ChainingStrategy, LowerBoundChainingStrategy, NoChainingStrategy,
+                    PrecomputeOnlyChainingStrategy, SeedingMethod,
+                },
+                node_ord::{AntiDiagonalNodeOrdStrategy, CostOnlyNodeOrdStrategy, NodeOrdStrategy},
+                primary_match::AllowPrimaryMatchStrategy,
+                primary_range::NoPrunePrimaryRangeStrategy,
+                secondary_deletion::AllowSecondaryDeletionStrategy,
+                shortcut::NoShortcutStrategy,
+                template_switch_count::{
+                    MaxTemplateSwitchCountStrategy, NoTemplateSwitchCountStrategy,
+                    TemplateSwitchCountStrategy,
+                },
+                template_switch_min_length::{
+                    LookaheadTemplateSwitchMinLengthStrategy, NoTemplateSwitchMinLengthStrategy,
+                    TemplateSwitchMinLengthStrategy,
+                },
             },
         },
-        template_switch_distance_a_star_align,
+        template_switch_distance_a_star_align, template_switch_distance_checkpointed_a_star_align,
+        template_switch_distance_k_best_a_star_align,
+        template_switch_distance_parallel_a_star_align,
     },
     config::TemplateSwitchConfig,
-    costs::U64Cost,
+    costs::{AStarCost, F64Cost, I64Cost, U64Cost},
 };
-use log::info;
+use log::{info, warn};
 
 use super::Cli;
 
+#[derive(Clone, ValueEnum)]
+pub enum CostTypeSelector {
+    U64,
+    I64,
+    F64,
+}
+
 #[derive(Clone, ValueEnum)]
 pub enum TemplateSwitchNodeOrdStrategySelector {
     CostOnly,
@@ -53,9 +67,16 @@ pub enum TemplateSwitchChainingStrategySelector {
     LowerBound,
 }
 
+#[derive(Clone, ValueEnum)]
+pub enum TemplateSwitchChainingSeedingSelector {
+    NonOverlapping,
+    Minimizers,
+    SpacedSeed,
+}
+
 pub fn align_a_star_template_switch_distance<
-    AlphabetType: Alphabet + Debug + Clone + Eq,
-    SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized,
+    AlphabetType: Alphabet + Debug + Clone + Eq + Sync,
+    SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized + Sync,
 >(
     cli: Cli,
     reference: &SubsequenceType,
@@ -63,18 +84,29 @@ pub fn align_a_star_template_switch_distance<
     reference_name: &str,
     query_name: &str,
 ) {
-    align_a_star_template_switch_distance_select_node_ord_strategy(
-        cli,
-        reference,
-        query,
-        reference_name,
-        query_name,
-    );
+    match cli.cost_type {
+        CostTypeSelector::U64 => align_a_star_template_switch_distance_select_node_ord_strategy::<
+            _,
+            _,
+            U64Cost,
+        >(cli, reference, query, reference_name, query_name),
+        CostTypeSelector::I64 => align_a_star_template_switch_distance_select_node_ord_strategy::<
+            _,
+            _,
+            I64Cost,
+        >(cli, reference, query, reference_name, query_name),
+        CostTypeSelector::F64 => align_a_star_template_switch_distance_select_node_ord_strategy::<
+            _,
+            _,
+            F64Cost,
+        >(cli, reference, query, reference_name, query_name),
+    }
 }
 
 fn align_a_star_template_switch_distance_select_node_ord_strategy<
-    AlphabetType: Alphabet + Debug + Clone + Eq,
-    SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized,
+    AlphabetType: Alphabet + Debug + Clone + Eq + Sync,
+    SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized + Sync,
+    Cost: AStarCost,
 >(
     cli: Cli,
     reference: &SubsequenceType,
@@ -87,6 +119,7 @@ fn align_a_star_template_switch_distance_select_node_ord_strategy<
             align_a_star_template_switch_distance_select_template_switch_min_length_strategy::<
                 _,
                 _,
+                Cost,
                 CostOnlyNodeOrdStrategy,
             >(cli, reference, query, reference_name, query_name)
         }
@@ -94,6 +127,7 @@ fn align_a_star_template_switch_distance_select_node_ord_strategy<
             align_a_star_template_switch_distance_select_template_switch_min_length_strategy::<
                 _,
                 _,
+                Cost,
                 AntiDiagonalNodeOrdStrategy,
             >(cli, reference, query, reference_name, query_name)
         }
@@ -101,9 +135,10 @@ fn align_a_star_template_switch_distance_select_node_ord_strategy<
 }
 
 fn align_a_star_template_switch_distance_select_template_switch_min_length_strategy<
-    AlphabetType: Alphabet + Debug + Clone + Eq,
-    SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized,
-    NodeOrd: NodeOrdStrategy<U64Cost, AllowPrimaryMatchStrategy>,
+    AlphabetType: Alphabet + Debug + Clone + Eq + Sync,
+    SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized + Sync,
+    Cost: AStarCost,
+    NodeOrd: NodeOrdStrategy<Cost, AllowPrimaryMatchStrategy>,
 >(
     cli: Cli,
     reference: &SubsequenceType,
@@ -116,26 +151,29 @@ fn align_a_star_template_switch_distance_select_template_switch_min_length_strat
             align_a_star_template_switch_select_chaining_strategy::<
                 _,
                 _,
+                Cost,
                 NodeOrd,
-                NoTemplateSwitchMinLengthStrategy<U64Cost>,
+                NoTemplateSwitchMinLengthStrategy<Cost>,
             >(cli, reference, query, reference_name, query_name)
         }
         TemplateSwitchMinLengthStrategySelector::Lookahead => {
             align_a_star_template_switch_select_chaining_strategy::<
                 _,
                 _,
+                Cost,
                 NodeOrd,
-                LookaheadTemplateSwitchMinLengthStrategy<U64Cost>,
+                LookaheadTemplateSwitchMinLengthStrategy<Cost>,
             >(cli, reference, query, reference_name, query_name)
         }
     }
 }
 
 fn align_a_star_template_switch_select_chaining_strategy<
-    AlphabetType: Alphabet + Debug + Clone + Eq,
-    SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized,
-    NodeOrd: NodeOrdStrategy<U64Cost, AllowPrimaryMatchStrategy>,
-    TemplateSwitchMinLength: TemplateSwitchMinLengthStrategy<U64Cost>,
+    AlphabetType: Alphabet + Debug + Clone + Eq + Sync,
+    SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized + Sync,
+    Cost: AStarCost,
+    NodeOrd: NodeOrdStrategy<Cost, AllowPrimaryMatchStrategy>,
+    TemplateSwitchMinLength: TemplateSwitchMinLengthStrategy<Cost>,
 >(
     cli: Cli,
     reference: &SubsequenceType,
@@ -148,38 +186,42 @@ fn align_a_star_template_switch_select_chaining_strategy<
             align_a_star_template_switch_select_no_ts_strategy::<
                 _,
                 _,
+                Cost,
                 NodeOrd,
                 TemplateSwitchMinLength,
-                NoChainingStrategy<U64Cost>,
+                NoChainingStrategy<Cost>,
             >(cli, reference, query, reference_name, query_name)
         }
         TemplateSwitchChainingStrategySelector::PrecomputeOnly => {
             align_a_star_template_switch_select_no_ts_strategy::<
                 _,
                 _,
+                Cost,
                 NodeOrd,
                 TemplateSwitchMinLength,
-                PrecomputeOnlyChainingStrategy<U64Cost>,
+                PrecomputeOnlyChainingStrategy<Cost>,
             >(cli, reference, query, reference_name, query_name)
         }
         TemplateSwitchChainingStrategySelector::LowerBound => {
             align_a_star_template_switch_select_no_ts_strategy::<
                 _,
                 _,
+                Cost,
                 NodeOrd,
                 TemplateSwitchMinLength,
-                LowerBoundChainingStrategy<U64Cost>,
+                LowerBoundChainingStrategy<Cost>,
             >(cli, reference, query, reference_name, query_name)
         }
     }
 }
 
 fn align_a_star_template_switch_select_no_ts_strategy<
-    AlphabetType: Alphabet + Debug + Clone + Eq,
-    SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized,
-    NodeOrd: NodeOrdStrategy<U64Cost, AllowPrimaryMatchStrategy>,
-    TemplateSwitchMinLength: TemplateSwitchMinLengthStrategy<U64Cost>,
-    Chaining: ChainingStrategy<U64Cost>,
+    AlphabetType: Alphabet + Debug + Clone + Eq + Sync,
+    SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized + Sync,
+    Cost: AStarCost,
+    NodeOrd: NodeOrdStrategy<Cost, AllowPrimaryMatchStrategy>,
+    TemplateSwitchMinLength: TemplateSwitchMinLengthStrategy<Cost>,
+    Chaining: ChainingStrategy<Cost>,
 >(
     cli: Cli,
     reference: &SubsequenceType,
@@ -191,6 +233,7 @@ fn align_a_star_template_switch_select_no_ts_strategy<
         align_a_star_template_switch_distance_call::<
             _,
             _,
+            Cost,
             NodeOrd,
             TemplateSwitchMinLength,
             Chaining,
@@ -200,6 +243,7 @@ fn align_a_star_template_switch_select_no_ts_strategy<
         align_a_star_template_switch_distance_call::<
             _,
             _,
+            Cost,
             NodeOrd,
             TemplateSwitchMinLength,
             Chaining,
@@ -209,11 +253,12 @@ fn align_a_star_template_switch_select_no_ts_strategy<
 }
 
 fn align_a_star_template_switch_distance_call<
-    AlphabetType: Alphabet + Debug + Clone + Eq,
-    SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized,
-    NodeOrd: NodeOrdStrategy<U64Cost, AllowPrimaryMatchStrategy>,
-    TemplateSwitchMinLength: TemplateSwitchMinLengthStrategy<U64Cost>,
-    Chaining: ChainingStrategy<U64Cost>,
+    AlphabetType: Alphabet + Debug + Clone + Eq + Sync,
+    SubsequenceType: GenomeSequence<AlphabetType, SubsequenceType> + ?Sized + Sync,
+    Cost: AStarCost,
+    NodeOrd: NodeOrdStrategy<Cost, AllowPrimaryMatchStrategy>,
+    TemplateSwitchMinLength: TemplateSwitchMinLengthStrategy<Cost>,
+    Chaining: ChainingStrategy<Cost>,
     TemplateSwitchCount: TemplateSwitchCountStrategy,
 >(
     cli: Cli,
@@ -234,36 +279,194 @@ fn align_a_star_template_switch_distance_call<
     let costs = TemplateSwitchConfig::read_plain(config_file)
         .unwrap_or_else(|error| panic!("Error parsing template switch config:\n{error}"));
 
-    let range = Some(parse_range(&cli, reference.len(), query.len()));
+    let cost_limit = cli
+        .cost_limit
+        .as_deref()
+        .map(|cost_limit| parse_cost::<Cost>(cost_limit, "cost limit"));
+    let initial_cost_limit = cli
+        .initial_cost_limit
+        .as_deref()
+        .map(|initial_cost_limit| parse_cost::<Cost>(initial_cost_limit, "initial cost limit"));
+    let options = AlignmentOptions::default()
+        .with_names(reference_name, query_name)
+        .with_range(Some(parse_range(&cli, reference.len(), query.len())))
+        .with_seeding_method(parse_seeding_method(&cli))
+        .with_cost_limit(cost_limit)
+        .with_memory_limit(cli.memory_limit)
+        .with_time_limit(cli.time_limit.map(Duration::from_secs_f64));
+
+    if let Some(k) = cli.top_k {
+        info!("Calling aligner for the {k} best alignments...");
+        let alignments = template_switch_distance_k_best_a_star_align::<
+            AlignmentStrategySelection<
+                AlphabetType,
+                Cost,
+                NodeOrd,
+                TemplateSwitchMinLength,
+                Chaining,
+                TemplateSwitchCount,
+                AllowSecondaryDeletionStrategy,
+                NoShortcutStrategy<Cost>,
+                AllowPrimaryMatchStrategy,
+                NoPrunePrimaryRangeStrategy,
+            >,
+            _,
+        >(
+            reference,
+            query,
+            costs,
+            &options,
+            k,
+            None,
+            template_switch_count_memory,
+        )
+        .unwrap_or_else(|error| panic!("Error aligning:\n{error}"));
+        info!("Finished aligning");
+
+        if let Some(output) = cli.output {
+            info!("Outputting alignment statistics to {output:?}");
+            use std::io::Write;
+
+            #[derive(serde::Serialize)]
+            struct Alignments<'alignments, Alignment> {
+                alignments: &'alignments [Alignment],
+            }
+
+            let mut output = std::io::BufWriter::new(std::fs::File::create(output).unwrap());
+            write!(
+                output,
+                "{}",
+                toml::to_string(&Alignments {
+                    alignments: &alignments
+                })
+                .unwrap()
+            )
+            .unwrap();
+        }
+
+        for (index, alignment) in alignments.iter().enumerate() {
+            println!("Alignment {} of {}:", index + 1, alignments.len());
+            println!("{alignment}");
+        }
+
+        return;
+    }
+
+    let mut search_dump = cli.dump_search.as_ref().map(|_| SearchDumpObserver::new());
+    let mut heuristic_checker = cli.check_heuristic.then(HeuristicChecker::new);
 
     info!("Calling aligner...");
-    let alignment = template_switch_distance_a_star_align::<
-        AlignmentStrategySelection<
-            AlphabetType,
-            U64Cost,
-            NodeOrd,
-            TemplateSwitchMinLength,
-            Chaining,
-            TemplateSwitchCount,
-            AllowSecondaryDeletionStrategy,
-            NoShortcutStrategy<U64Cost>,
-            AllowPrimaryMatchStrategy,
-            NoPrunePrimaryRangeStrategy,
-        >,
-        _,
-    >(
-        reference,
-        query,
-        reference_name,
-        query_name,
-        range,
-        costs,
-        cli.cost_limit,
-        cli.memory_limit,
-        template_switch_count_memory,
-    );
+    let alignment = if cli.threads > 1 {
+        template_switch_distance_parallel_a_star_align::<
+            AlignmentStrategySelection<
+                AlphabetType,
+                Cost,
+                NodeOrd,
+                TemplateSwitchMinLength,
+                Chaining,
+                TemplateSwitchCount,
+                AllowSecondaryDeletionStrategy,
+                NoShortcutStrategy<Cost>,
+                AllowPrimaryMatchStrategy,
+                NoPrunePrimaryRangeStrategy,
+            >,
+            _,
+        >(
+            reference,
+            query,
+            costs,
+            &options,
+            cli.threads.into(),
+            template_switch_count_memory,
+        )
+        .unwrap_or_else(|error| panic!("Error aligning:\n{error}"))
+    } else if cli.checkpoint.is_some() || cli.resume.is_some() {
+        template_switch_distance_checkpointed_a_star_align::<
+            AlignmentStrategySelection<
+                AlphabetType,
+                Cost,
+                NodeOrd,
+                TemplateSwitchMinLength,
+                Chaining,
+                TemplateSwitchCount,
+                AllowSecondaryDeletionStrategy,
+                NoShortcutStrategy<Cost>,
+                AllowPrimaryMatchStrategy,
+                NoPrunePrimaryRangeStrategy,
+            >,
+            _,
+        >(
+            reference,
+            query,
+            costs,
+            &options,
+            template_switch_count_memory,
+            cli.checkpoint.as_deref(),
+            Duration::from_secs_f64(cli.checkpoint_interval),
+            cli.resume.as_deref(),
+            (search_dump.as_mut(), heuristic_checker.as_mut()),
+        )
+        .unwrap_or_else(|error| panic!("Error checkpointing the alignment:\n{error}"))
+    } else {
+        template_switch_distance_a_star_align::<
+            AlignmentStrategySelection<
+                AlphabetType,
+                Cost,
+                NodeOrd,
+                TemplateSwitchMinLength,
+                Chaining,
+                TemplateSwitchCount,
+                AllowSecondaryDeletionStrategy,
+                NoShortcutStrategy<Cost>,
+                AllowPrimaryMatchStrategy,
+                NoPrunePrimaryRangeStrategy,
+            >,
+            _,
+        >(
+            reference,
+            query,
+            costs,
+            &options
+                .with_mode(cli.mode.into())
+                .with_epsilon(cli.epsilon)
+                .with_initial_cost_limit(initial_cost_limit)
+                .with_closed_list_mode(if cli.compact_closed_list {
+                    ClosedListMode::Compact
+                } else {
+                    ClosedListMode::Full
+                }),
+            template_switch_count_memory,
+            (search_dump.as_mut(), heuristic_checker.as_mut()),
+        )
+        .unwrap_or_else(|error| panic!("Error aligning:\n{error}"))
+    };
     info!("Finished aligning");
 
+    if let Some(heuristic_checker) = heuristic_checker {
+        let report = heuristic_checker.into_report();
+        if report.is_admissible() && report.is_consistent() {
+            info!("Heuristic check:\n{report}");
+        } else {
+            warn!("The A* lower bound has violations:\n{report}");
+        }
+    }
+
+    if let (Some(search_dump), Some(path)) = (search_dump, cli.dump_search.as_ref()) {
+        info!("Writing closed nodes to {path:?}");
+        let delimiter = if path.extension().is_some_and(|extension| extension == "csv") {
+            ','
+        } else {
+            '\t'
+        };
+        search_dump
+            .into_search_dump()
+            .write(
+                std::io::BufWriter::new(std::fs::File::create(path).unwrap()),
+                delimiter,
+            )
+            .unwrap_or_else(|error| panic!("Error writing search dump:\n{error}"));
+    }
+
     if let Some(output) = cli.output {
         info!("Outputting alignment statistics to {output:?}");
         use std::io::Write;
@@ -274,6 +477,38 @@ fn align_a_star_template_switch_distance_call<
     println!("{alignment}");
 }
 
+fn parse_cost<Cost: AStarCost>(cost: &str, name: &str) -> Cost {
+    cost.parse().unwrap_or_else(|_| {
+        panic!("Error parsing {name} {cost:?}: not a valid cost of the selected cost type")
+    })
+}
+
+fn parse_seeding_method(cli: &Cli) -> SeedingMethod {
+    match cli.ts_chaining_seeding {
+        TemplateSwitchChainingSeedingSelector::NonOverlapping => SeedingMethod::NonOverlapping {
+            block_size: cli.ts_chaining_block_size,
+        },
+        TemplateSwitchChainingSeedingSelector::Minimizers => SeedingMethod::Minimizers {
+            window_size: cli.ts_chaining_minimizer_window_size,
+            k: cli.ts_chaining_minimizer_k,
+        },
+        TemplateSwitchChainingSeedingSelector::SpacedSeed => SeedingMethod::SpacedSeed {
+            pattern: cli
+                .ts_chaining_spaced_seed
+                .chars()
+                .map(|c| match c {
+                    '1' => true,
+                    '0' => false,
+                    c => panic!(
+                        "Error parsing spaced seed {:?}: unexpected character {c:?}, expected '0' or '1'",
+                        cli.ts_chaining_spaced_seed
+                    ),
+                })
+                .collect(),
+        },
+    }
+}
+
 fn parse_range(cli: &Cli, reference_length: usize, query_length: usize) -> AlignmentRange {
     let complete_reference_range = 0..reference_length;
     let complete_query_range = 0..query_length;
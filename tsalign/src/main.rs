@@ -15,7 +15,7 @@ struct Cli {
 
 #[derive(clap::Subcommand)]
 enum Subcommand {
-    Align(align::Cli),
+    Align(Box<align::Cli>),
     Show(show::Cli),
 }
 
@@ -23,7 +23,7 @@ fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.subcommand {
-        Subcommand::Align(cli) => align::cli(cli),
+        Subcommand::Align(cli) => align::cli(*cli),
         Subcommand::Show(cli) => show::cli(cli),
     }
 }
@@ -1,14 +1,21 @@
 use std::{
     fs::File,
-    io::{Read, Write, stdout},
+    io::{BufReader, Read, Write, stdout},
     path::PathBuf,
 };
 
 use anyhow::{Context, Result};
 use clap::Parser;
+use lib_tsalign::{
+    a_star_aligner::{
+        alignment_result::AlignmentResult,
+        template_switch_distance::{AlignmentType, search_dump::SearchDump},
+    },
+    costs::F64Cost,
+};
 use lib_tsshow::{
     plain_text::show_template_switches,
-    svg::{SvgConfig, create_error_svg, create_ts_svg},
+    svg::{SvgConfig, create_error_svg, create_ts_svg, search_heatmap::create_search_heatmap_svg},
     svg_to_png,
 };
 use log::{LevelFilter, info, warn};
@@ -51,6 +58,16 @@ pub struct Cli {
     /// Draw the SVG image with more complement characters than just the bare minimum needed to visualise the template switch.
     #[clap(long, short = 'c')]
     more_svg_complement: bool,
+
+    /// Path to a file created with `tsalign align --dump-search`.
+    ///
+    /// The closed nodes from this file are rendered as a heatmap to the file given by --search-heatmap.
+    #[clap(long, requires = "search_heatmap")]
+    search_dump: Option<PathBuf>,
+
+    /// Create an SVG image file showing the number of closed nodes in each cell of the alignment matrix, with the alignment drawn on top.
+    #[clap(long, requires = "search_dump")]
+    search_heatmap: Option<PathBuf>,
 }
 
 pub fn cli(cli: Cli) -> Result<()> {
@@ -68,7 +85,8 @@ pub fn cli(cli: Cli) -> Result<()> {
         .unwrap_or_else(|error| panic!("Error opening input file: {error}"))
         .read_to_string(&mut buffer)
         .unwrap_or_else(|error| panic!("Error reading input file: {error}"));
-    let result =
+    // Integer costs can be read as decimal costs, so this supports the output of all cost types.
+    let result: AlignmentResult<AlignmentType, F64Cost> =
         toml::from_str(&buffer).unwrap_or_else(|error| panic!("Error parsing input file: {error}"));
 
     let no_ts_result = cli.no_ts_input.as_ref().map(|no_ts_input| {
@@ -83,6 +101,26 @@ pub fn cli(cli: Cli) -> Result<()> {
 
     show_template_switches(stdout(), &result, &no_ts_result);
 
+    if let (Some(search_dump_path), Some(search_heatmap_path)) =
+        (cli.search_dump.as_ref(), cli.search_heatmap.as_ref())
+    {
+        info!("Reading search dump {search_dump_path:?}");
+        let search_dump = SearchDump::read(BufReader::new(
+            File::open(search_dump_path).with_context(|| "Error opening search dump")?,
+        ))
+        .with_context(|| "Error reading search dump")?;
+
+        let mut svg = Vec::new();
+        create_search_heatmap_svg(&mut svg, &search_dump, &result)
+            .with_context(|| "Error creating search heatmap SVG")?;
+
+        info!("Writing search heatmap svg to {search_heatmap_path:?}");
+        File::create(search_heatmap_path)
+            .unwrap()
+            .write_all(&svg)
+            .unwrap();
+    }
+
     if let Some(svg_out_path) = cli.svg.as_ref() {
         let mut svg = Vec::new();
         if let Err(error) = create_ts_svg(